@@ -61,5 +61,7 @@ fn main() {
         }
     }
 
+    use rustc_driver::pretty::{WriteSmirJson, WRITE_SMIR_JSON};
+    WRITE_SMIR_JSON.swap(&(rustc_smir::rustc_internal::write_smir_json as WriteSmirJson));
     rustc_driver::main()
 }
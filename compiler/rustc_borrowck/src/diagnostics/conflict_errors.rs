@@ -851,6 +851,7 @@ pub(crate) fn report_move_out_while_borrowed(
                 Some(borrow_span),
                 None,
             );
+        self.add_borrow_path_explanation(&mut err, location, borrow, span);
         self.buffer_error(err);
     }
 
@@ -1117,6 +1118,7 @@ pub(crate) fn report_conflicting_borrow(
             None,
             Some((issued_span, span)),
         );
+        self.add_borrow_path_explanation(&mut err, location, issued_borrow, span);
 
         self.suggest_using_local_if_applicable(&mut err, location, issued_borrow, explanation);
 
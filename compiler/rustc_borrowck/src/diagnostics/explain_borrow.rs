@@ -1,6 +1,6 @@
 //! Print diagnostics to explain why values are borrowed.
 
-use rustc_errors::{Applicability, Diagnostic};
+use rustc_errors::{Applicability, Diagnostic, MultiSpan};
 use rustc_index::vec::IndexVec;
 use rustc_infer::infer::NllRegionVariableOrigin;
 use rustc_middle::mir::{
@@ -440,6 +440,82 @@ pub(crate) fn explain_why_borrow_contains_point(
         }
     }
 
+    /// With `-Zborrowck-explain`, adds a note to `err` that walks through why `borrow` is still
+    /// live at `location`: where the borrow starts, each constraint on the path that
+    /// `best_blame_constraint` would walk, the conflicting use at `use_span`, and the later use
+    /// that keeps the borrow alive.
+    pub(crate) fn add_borrow_path_explanation(
+        &self,
+        err: &mut Diagnostic,
+        location: Location,
+        borrow: &BorrowData<'tcx>,
+        use_span: Span,
+    ) {
+        if !self.infcx.tcx.sess.opts.unstable_opts.borrowck_explain {
+            return;
+        }
+
+        let borrow_region_vid = borrow.region;
+        let region_sub = self.regioncx.find_sub_region_live_at(borrow_region_vid, location);
+        let Some((path, _)) = self
+            .regioncx
+            .find_constraint_paths_between_regions(borrow_region_vid, |r| r == region_sub)
+        else {
+            return;
+        };
+
+        let borrowed = match self.describe_place(borrow.borrowed_place.as_ref()) {
+            Some(name) => format!("`{}`", name),
+            None => "the value".to_string(),
+        };
+        let borrow_span = self.retrieve_borrow_spans(borrow).var_or_use();
+        let mut steps = MultiSpan::from_span(borrow_span);
+        steps.push_span_label(borrow_span, format!("1: {} is borrowed here", borrowed));
+
+        // Steps are numbered in the order they are added. Constraints often share a span with
+        // an earlier step, in which case only the first one is kept.
+        let mut labeled = vec![borrow_span];
+        let mut add_step = |span: Span, label: String| {
+            if !span.is_dummy() && !labeled.contains(&span) {
+                labeled.push(span);
+                steps.push_span_label(span, format!("{}: {}", labeled.len(), label));
+            }
+        };
+
+        for constraint in &path {
+            let description = match constraint.category {
+                ConstraintCategory::Boring
+                | ConstraintCategory::BoringNoLocation
+                | ConstraintCategory::Internal => continue,
+                category => category.description().trim_end(),
+            };
+            let description = if description.is_empty() { "constraint" } else { description };
+            add_step(constraint.span, format!("the borrow flows through this {}", description));
+        }
+
+        add_step(use_span, "conflicting use while the borrow is live".to_string());
+
+        let cause =
+            find_use::find(&self.body, &self.regioncx, self.infcx.tcx, region_sub, location);
+        let later_use = match cause {
+            Some(Cause::LiveVar(local, later)) => Some((local, later, "used")),
+            Some(Cause::DropVar(local, later)) => Some((local, later, "dropped")),
+            None => None,
+        };
+        if let Some((local, later, action)) = later_use {
+            let user = match self.local_names[local] {
+                Some(name) => format!("`{}`", name),
+                None => "a temporary".to_string(),
+            };
+            add_step(
+                self.body.source_info(later).span,
+                format!("the borrow is kept alive because {} is {} here", user, action),
+            );
+        }
+
+        err.span_note(steps, "why the borrow is still live at the conflicting use");
+    }
+
     /// Determine how the borrow was later used.
     /// First span returned points to the location of the conflicting use
     /// Second span if `Some` is returned in the case of closures and points
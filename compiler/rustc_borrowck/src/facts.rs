@@ -8,6 +8,7 @@
 use rustc_middle::mir::Local;
 use rustc_middle::ty::{RegionVid, TyCtxt};
 use rustc_mir_dataflow::move_paths::MovePathIndex;
+use serde_json::Value;
 use std::error::Error;
 use std::fmt::Debug;
 use std::fs::{self, File};
@@ -37,12 +38,17 @@ fn write_to_dir(
         dir: impl AsRef<Path>,
         location_table: &LocationTable,
     ) -> Result<(), Box<dyn Error>>;
+
+    /// The same facts as `write_to_dir`, as a JSON object with one array of rows per relation.
+    fn to_json(&self, location_table: &LocationTable) -> Value;
 }
 
 impl AllFactsExt for AllFacts {
     /// Return
     fn enabled(tcx: TyCtxt<'_>) -> bool {
-        tcx.sess.opts.unstable_opts.nll_facts || tcx.sess.opts.unstable_opts.polonius
+        tcx.sess.opts.unstable_opts.nll_facts
+            || tcx.sess.opts.unstable_opts.nll_facts_json
+            || tcx.sess.opts.unstable_opts.polonius
     }
 
     fn write_to_dir(
@@ -89,6 +95,41 @@ macro_rules! write_facts_to_path {
         }
         Ok(())
     }
+
+    fn to_json(&self, location_table: &LocationTable) -> Value {
+        let mut relations = serde_json::Map::new();
+        macro_rules! insert_relations {
+            ($($field:ident,)*) => {
+                $(
+                    relations.insert(
+                        stringify!($field).to_string(),
+                        self.$field.iter().map(|row| row.to_json(location_table)).collect(),
+                    );
+                )*
+            }
+        }
+        insert_relations! {
+            loan_issued_at,
+            universal_region,
+            cfg_edge,
+            loan_killed_at,
+            subset_base,
+            loan_invalidated_at,
+            var_used_at,
+            var_defined_at,
+            var_dropped_at,
+            use_of_var_derefs_origin,
+            drop_of_var_derefs_origin,
+            child_path,
+            path_is_var,
+            path_assigned_at_base,
+            path_moved_at_base,
+            path_accessed_at_base,
+            known_placeholder_subset,
+            placeholder,
+        }
+        Value::Object(relations)
+    }
 }
 
 impl Atom for BorrowIndex {
@@ -128,6 +169,8 @@ fn write(
         out: &mut dyn Write,
         location_table: &LocationTable,
     ) -> Result<(), Box<dyn Error>>;
+
+    fn to_json(&self, location_table: &LocationTable) -> Value;
 }
 
 impl FactRow for RegionVid {
@@ -138,6 +181,10 @@ fn write(
     ) -> Result<(), Box<dyn Error>> {
         write_row(out, location_table, &[self])
     }
+
+    fn to_json(&self, location_table: &LocationTable) -> Value {
+        json_row(location_table, &[self])
+    }
 }
 
 impl<A, B> FactRow for (A, B)
@@ -152,6 +199,10 @@ fn write(
     ) -> Result<(), Box<dyn Error>> {
         write_row(out, location_table, &[&self.0, &self.1])
     }
+
+    fn to_json(&self, location_table: &LocationTable) -> Value {
+        json_row(location_table, &[&self.0, &self.1])
+    }
 }
 
 impl<A, B, C> FactRow for (A, B, C)
@@ -167,6 +218,10 @@ fn write(
     ) -> Result<(), Box<dyn Error>> {
         write_row(out, location_table, &[&self.0, &self.1, &self.2])
     }
+
+    fn to_json(&self, location_table: &LocationTable) -> Value {
+        json_row(location_table, &[&self.0, &self.1, &self.2])
+    }
 }
 
 impl<A, B, C, D> FactRow for (A, B, C, D)
@@ -183,6 +238,10 @@ fn write(
     ) -> Result<(), Box<dyn Error>> {
         write_row(out, location_table, &[&self.0, &self.1, &self.2, &self.3])
     }
+
+    fn to_json(&self, location_table: &LocationTable) -> Value {
+        json_row(location_table, &[&self.0, &self.1, &self.2, &self.3])
+    }
 }
 
 fn write_row(
@@ -197,6 +256,10 @@ fn write_row(
     Ok(())
 }
 
+fn json_row(location_table: &LocationTable, columns: &[&dyn FactCell]) -> Value {
+    columns.iter().map(|c| Value::String(c.to_string(location_table))).collect()
+}
+
 trait FactCell {
     fn to_string(&self, location_table: &LocationTable) -> String;
 }
@@ -11,10 +11,12 @@
     BasicBlock, Body, ClosureOutlivesSubject, ClosureRegionRequirements, LocalKind, Location,
     Promoted,
 };
-use rustc_middle::ty::{self, OpaqueHiddenType, Region, RegionVid};
+use rustc_middle::ty::{self, OpaqueHiddenType, Region, RegionVid, TyCtxt};
 use rustc_span::symbol::sym;
 use std::env;
+use std::error::Error;
 use std::fmt::Debug;
+use std::fs;
 use std::io;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -301,6 +303,12 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
     let (closure_region_requirements, nll_errors) =
         regioncx.solve(infcx, param_env, &body, polonius_output.clone());
 
+    if infcx.tcx.sess.opts.unstable_opts.nll_facts_json {
+        let all_facts = all_facts.as_ref().unwrap();
+        dump_facts_json(infcx.tcx, body, all_facts, location_table, &regioncx, &nll_errors)
+            .unwrap();
+    }
+
     if !nll_errors.is_empty() {
         // Suppress unhelpful extra errors in `infer_opaque_types`.
         infcx.set_tainted_by_errors(infcx.tcx.sess.delay_span_bug(
@@ -321,6 +329,33 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
     }
 }
 
+/// Writes the Polonius input facts, the results of region inference and the constraints blamed
+/// for each region error as `facts.json`, next to the files written by `-Znll-facts`.
+fn dump_facts_json<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    all_facts: &AllFacts,
+    location_table: &LocationTable,
+    regioncx: &RegionInferenceContext<'tcx>,
+    nll_errors: &RegionErrors<'tcx>,
+) -> Result<(), Box<dyn Error>> {
+    let def_id = body.source.def_id();
+    let dir_path = PathBuf::from(&tcx.sess.opts.unstable_opts.nll_facts_dir)
+        .join(tcx.def_path(def_id).to_filename_friendly_no_crate());
+    fs::create_dir_all(&dir_path)?;
+
+    let json = serde_json::json!({
+        "version": 1,
+        "def_path": tcx.def_path_str(def_id),
+        "facts": all_facts.to_json(location_table),
+        "region_inference": regioncx.region_inference_json(tcx, nll_errors),
+    });
+
+    let mut file = io::BufWriter::new(fs::File::create(dir_path.join("facts.json"))?);
+    serde_json::to_writer_pretty(&mut file, &json)?;
+    Ok(())
+}
+
 pub(super) fn dump_mir_results<'tcx>(
     infcx: &InferCtxt<'tcx>,
     body: &Body<'tcx>,
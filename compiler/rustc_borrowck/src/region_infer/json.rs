@@ -0,0 +1,152 @@
+#![deny(rustc::untranslatable_diagnostic)]
+#![deny(rustc::diagnostic_outside_of_impl)]
+//! With `-Znll-facts-json`, the state of region inference is dumped next to the
+//! Polonius input facts, so that external tools can explain how a region got
+//! its value and which constraints are to blame for each region error.
+
+use super::{OutlivesConstraint, RegionInferenceContext};
+use crate::diagnostics::{RegionErrorKind, RegionErrors};
+use crate::type_check::Locations;
+use rustc_infer::infer::NllRegionVariableOrigin;
+use rustc_middle::ty::{RegionVid, TyCtxt};
+use rustc_span::Span;
+use serde_json::{json, Value};
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// The inferred value of every region, the outlives constraint graph, and the constraint
+    /// path behind each of `errors`.
+    pub(crate) fn region_inference_json(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        errors: &RegionErrors<'tcx>,
+    ) -> Value {
+        let regions: Vec<_> = self
+            .regions()
+            .map(|region| {
+                let definition = &self.definitions[region];
+                json!({
+                    "region": format!("{:?}", region),
+                    "origin": format!("{:?}", definition.origin),
+                    "universe": definition.universe.as_u32(),
+                    "external_name": definition.external_name.map(|r| r.to_string()),
+                    "classification": self
+                        .universal_regions
+                        .region_classification(region)
+                        .map(|c| format!("{:?}", c)),
+                    "scc": self.constraint_sccs.scc(region).index(),
+                    "live_at": self.liveness_constraints.region_value_str(region),
+                    "value": self.region_value_str(region),
+                })
+            })
+            .collect();
+
+        let mut constraints: Vec<_> = self.constraints.outlives().iter().collect();
+        constraints.sort_by_key(|c| (c.sup, c.sub));
+        let outlives: Vec<_> = constraints.into_iter().map(|c| constraint_json(tcx, c)).collect();
+
+        let errors: Vec<_> = errors.iter().map(|error| self.error_json(tcx, error)).collect();
+
+        json!({
+            "regions": regions,
+            "outlives": outlives,
+            "errors": errors,
+        })
+    }
+
+    fn error_json(&self, tcx: TyCtxt<'tcx>, error: &RegionErrorKind<'tcx>) -> Value {
+        match error {
+            RegionErrorKind::TypeTestError { type_test } => json!({
+                "kind": "type_test",
+                "generic_kind": type_test.generic_kind.to_string(),
+                "lower_bound": format!("{:?}", type_test.lower_bound),
+                "span": span_json(tcx, type_test.span),
+            }),
+            RegionErrorKind::UnexpectedHiddenRegion { span, hidden_ty, key, member_region } => {
+                json!({
+                    "kind": "unexpected_hidden_region",
+                    "opaque": tcx.def_path_str(key.def_id.to_def_id()),
+                    "hidden_ty": hidden_ty.to_string(),
+                    "member_region": member_region.to_string(),
+                    "span": span_json(tcx, *span),
+                })
+            }
+            RegionErrorKind::BoundUniversalRegionError {
+                longer_fr,
+                error_element,
+                placeholder,
+            } => {
+                let error_vid = self.region_from_element(*longer_fr, error_element);
+                json!({
+                    "kind": "bound_universal_region",
+                    "longer": format!("{:?}", longer_fr),
+                    "shorter": format!("{:?}", error_vid),
+                    "error_element": format!("{:?}", error_element),
+                    "blame": self.blame_json(
+                        tcx,
+                        *longer_fr,
+                        NllRegionVariableOrigin::Placeholder(*placeholder),
+                        |r| r == error_vid,
+                    ),
+                })
+            }
+            RegionErrorKind::RegionError { fr_origin, longer_fr, shorter_fr, is_reported } => {
+                json!({
+                    "kind": "region",
+                    "longer": format!("{:?}", longer_fr),
+                    "shorter": format!("{:?}", shorter_fr),
+                    "reported": is_reported,
+                    "blame": self.blame_json(tcx, *longer_fr, *fr_origin, |r| {
+                        self.provides_universal_region(r, *longer_fr, *shorter_fr)
+                    }),
+                })
+            }
+        }
+    }
+
+    /// The shortest constraint path from `from_region` to a region satisfying `target_test`,
+    /// along with the constraint on that path that diagnostics point at.
+    fn blame_json(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        from_region: RegionVid,
+        from_region_origin: NllRegionVariableOrigin,
+        target_test: impl Fn(RegionVid) -> bool,
+    ) -> Value {
+        let Some((path, target)) =
+            self.find_constraint_paths_between_regions(from_region, &target_test)
+        else {
+            return Value::Null;
+        };
+        let (best, _) = self.best_blame_constraint(from_region, from_region_origin, target_test);
+
+        json!({
+            "target": format!("{:?}", target),
+            "path": path.iter().map(|c| constraint_json(tcx, c)).collect::<Vec<_>>(),
+            "best": {
+                "constraint": constraint_json(tcx, &best.outlives_constraint),
+                "category": format!("{:?}", best.category),
+                "from_closure": best.from_closure,
+            },
+        })
+    }
+}
+
+fn constraint_json<'tcx>(tcx: TyCtxt<'tcx>, constraint: &OutlivesConstraint<'tcx>) -> Value {
+    let OutlivesConstraint { sup, sub, locations, span, category, from_closure, .. } = constraint;
+    let locations = match locations {
+        Locations::All(span) => json!({ "all": span_json(tcx, *span) }),
+        Locations::Single(loc) => json!({ "single": format!("{:?}", loc) }),
+    };
+    json!({
+        "sup": format!("{:?}", sup),
+        "sub": format!("{:?}", sub),
+        "category": format!("{:?}", category),
+        "locations": locations,
+        "span": span_json(tcx, *span),
+        "from_closure": from_closure,
+    })
+}
+
+fn span_json(tcx: TyCtxt<'_>, span: Span) -> String {
+    tcx.sess.source_map().span_to_embeddable_string(span)
+}
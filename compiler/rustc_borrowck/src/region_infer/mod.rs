@@ -39,6 +39,7 @@
 
 mod dump_mir;
 mod graphviz;
+mod json;
 mod opaque_types;
 mod reverse_sccs;
 
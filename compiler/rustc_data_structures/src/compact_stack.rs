@@ -0,0 +1,122 @@
+//! Compaction of the long stacks that are reported for query cycles and trait overflows, where
+//! a few frames usually repeat many times, like `Vec<Vec<T>>: Trait` requiring `Vec<T>: Trait`
+//! requiring `T: Trait`.
+//!
+//! Whether two frames are the same is decided by a key, e.g. the name of a query or the impl a
+//! requirement comes from, as the arguments of the frames usually differ between repetitions.
+
+use rustc_graphviz as dot;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+/// Repeating segments longer than this are not looked for.
+const MAX_SEGMENT_LEN: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StackSegment {
+    /// A frame that is not part of a repeating segment.
+    Frame(usize),
+    /// The `len` frames from `start` on, followed by the same frames `more` more times in a row.
+    Repeated { start: usize, len: usize, more: usize },
+}
+
+/// Splits the stack with the given frame keys into single frames and repeating segments.
+///
+/// From the first frame on, the shortest segment that covers the most frames by repeating is
+/// taken, so `[a, b, a, b, a, b, c]` is split into `[a, b]` repeated 2 more times and `[c]`.
+pub fn compact_stack<K: PartialEq>(keys: &[K]) -> Vec<StackSegment> {
+    let mut segments = vec![];
+    let mut start = 0;
+    while start < keys.len() {
+        let mut best = None;
+        for len in 1..=MAX_SEGMENT_LEN.min((keys.len() - start) / 2) {
+            // The number of frames after the first occurrence that repeat it.
+            let repeating = keys[start + len..]
+                .iter()
+                .zip(&keys[start..])
+                .take_while(|(key, previous)| key == previous)
+                .count();
+            let more = repeating / len;
+            let covers_more = best.map_or(true, |(best_len, best_more)| {
+                (more + 1) * len > (best_more + 1) * best_len
+            });
+            if more > 0 && covers_more {
+                best = Some((len, more));
+            }
+        }
+
+        match best {
+            Some((len, more)) => {
+                segments.push(StackSegment::Repeated { start, len, more });
+                start += len * (more + 1);
+            }
+            None => {
+                segments.push(StackSegment::Frame(start));
+                start += 1;
+            }
+        }
+    }
+    segments
+}
+
+/// A stack as a graph, with a node for every frame and an edge from every frame to the next one,
+/// as well as from the last frame to the first one if the stack is a cycle.
+pub struct StackGraph<'a> {
+    pub name: &'a str,
+    pub labels: &'a [String],
+    pub is_cycle: bool,
+}
+
+impl StackGraph<'_> {
+    /// Writes the graph to `path` in the graphviz format.
+    pub fn write_graphviz(&self, path: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        dot::render(self, &mut file)
+    }
+}
+
+impl<'a> dot::Labeller<'a> for StackGraph<'_> {
+    type Node = usize;
+    type Edge = (usize, usize);
+
+    fn graph_id(&self) -> dot::Id<'_> {
+        dot::Id::new(self.name).unwrap()
+    }
+
+    fn node_id(&self, index: &Self::Node) -> dot::Id<'_> {
+        dot::Id::new(format!("frame_{}", index)).unwrap()
+    }
+
+    fn node_label(&self, index: &Self::Node) -> dot::LabelText<'_> {
+        dot::LabelText::LabelStr(format!("#{} {}", index, self.labels[*index]).into())
+    }
+}
+
+impl<'a> dot::GraphWalk<'a> for StackGraph<'_> {
+    type Node = usize;
+    type Edge = (usize, usize);
+
+    fn nodes(&self) -> dot::Nodes<'_, Self::Node> {
+        (0..self.labels.len()).collect()
+    }
+
+    fn edges(&self) -> dot::Edges<'_, Self::Edge> {
+        let mut edges: Vec<_> = (1..self.labels.len()).map(|i| (i - 1, i)).collect();
+        if self.is_cycle && !self.labels.is_empty() {
+            edges.push((self.labels.len() - 1, 0));
+        }
+        edges.into()
+    }
+
+    fn source(&self, (s, _): &Self::Edge) -> Self::Node {
+        *s
+    }
+
+    fn target(&self, (_, t): &Self::Edge) -> Self::Node {
+        *t
+    }
+}
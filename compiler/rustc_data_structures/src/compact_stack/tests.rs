@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn no_repetition() {
+    assert_eq!(
+        compact_stack(&["a", "b", "c"]),
+        vec![StackSegment::Frame(0), StackSegment::Frame(1), StackSegment::Frame(2)]
+    );
+    assert_eq!(compact_stack::<&str>(&[]), vec![]);
+}
+
+#[test]
+fn repeated_segments() {
+    assert_eq!(
+        compact_stack(&["x", "a", "b", "a", "b", "a", "b", "c"]),
+        vec![
+            StackSegment::Frame(0),
+            StackSegment::Repeated { start: 1, len: 2, more: 2 },
+            StackSegment::Frame(7),
+        ]
+    );
+    // The shortest segment is preferred when several cover the same frames.
+    assert_eq!(
+        compact_stack(&["a", "a", "a", "a"]),
+        vec![StackSegment::Repeated { start: 0, len: 1, more: 3 }]
+    );
+    // A trailing partial repetition is left as single frames.
+    assert_eq!(
+        compact_stack(&["a", "b", "a", "b", "a"]),
+        vec![StackSegment::Repeated { start: 0, len: 2, more: 1 }, StackSegment::Frame(4)]
+    );
+}
+
+#[test]
+fn graphviz() {
+    let labels = vec!["`a`".to_string(), "`b`".to_string()];
+    let graph = StackGraph { name: "cycle", labels: &labels, is_cycle: true };
+    let mut dot = Vec::new();
+    dot::render(&graph, &mut dot).unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(dot.starts_with("digraph cycle {"));
+    assert!(dot.contains("frame_0 -> frame_1"));
+    assert!(dot.contains("frame_1 -> frame_0"));
+    assert!(dot.contains("#1 `b`"));
+}
@@ -0,0 +1,115 @@
+//! Finding the natural loops of a control-flow graph.
+//!
+//! A natural loop is entered through a single node, its header, which dominates every other
+//! node of the loop. It is found from its back edges, the edges from a node of the loop to the
+//! header. Cycles that can be entered through several nodes (irreducible control flow) have no
+//! such header, and are not found.
+
+use super::dominators::Dominators;
+use super::iterate::{NodeStatus, TriColorDepthFirstSearch, TriColorVisitor};
+use super::ControlFlowGraph;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::{Idx, IndexVec};
+use std::ops::ControlFlow;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Debug)]
+pub struct Loop<N: Idx> {
+    /// The node through which the loop is entered.
+    pub header: N,
+    /// The nodes of the loop with an edge back to the header, in ascending order.
+    pub latches: Vec<N>,
+    /// The nodes of the loop, including the header, in ascending order.
+    pub nodes: Vec<N>,
+    contains: BitSet<N>,
+}
+
+impl<N: Idx> Loop<N> {
+    pub fn contains(&self, node: N) -> bool {
+        self.contains.contains(node)
+    }
+}
+
+/// Returns the natural loops of `graph`, one per header, so the loops of back edges to the same
+/// header are merged. Loops that are nested in another loop come before it.
+pub fn loops<G: ControlFlowGraph>(
+    graph: &G,
+    dominators: &Dominators<G::Node>,
+) -> Vec<Loop<G::Node>> {
+    let mut latches: IndexVec<G::Node, Vec<G::Node>> =
+        IndexVec::from_elem_n(Vec::new(), graph.num_nodes());
+    for node in (0..graph.num_nodes()).map(G::Node::new) {
+        if !dominators.is_reachable(node) {
+            continue;
+        }
+        for successor in graph.successors(node) {
+            if dominators.is_dominated_by(node, successor) {
+                latches[successor].push(node);
+            }
+        }
+    }
+
+    let mut loops = vec![];
+    for (header, mut latches) in latches.into_iter_enumerated() {
+        if latches.is_empty() {
+            continue;
+        }
+        latches.sort();
+        latches.dedup();
+
+        // Every node that reaches a latch without going through the header is in the loop, as
+        // the header dominates it.
+        let mut contains = BitSet::new_empty(graph.num_nodes());
+        contains.insert(header);
+        let mut stack: Vec<_> =
+            latches.iter().copied().filter(|&latch| contains.insert(latch)).collect();
+        while let Some(node) = stack.pop() {
+            for predecessor in graph.predecessors(node) {
+                if dominators.is_reachable(predecessor) && contains.insert(predecessor) {
+                    stack.push(predecessor);
+                }
+            }
+        }
+
+        let nodes = contains.iter().collect();
+        loops.push(Loop { header, latches, nodes, contains });
+    }
+
+    // A nested loop has fewer nodes than the loops it is nested in, as their headers are not in it.
+    loops.sort_by_key(|l| l.nodes.len());
+    loops
+}
+
+/// Returns `true` if every cycle of `graph` that is reachable from the start node goes through
+/// the back edge of a natural loop, i.e. if the graph has no irreducible control flow.
+pub fn is_reducible<G: ControlFlowGraph>(graph: &G, dominators: &Dominators<G::Node>) -> bool {
+    TriColorDepthFirstSearch::new(graph)
+        .run_from_start(&mut NonLoopCycleDetector { dominators })
+        .is_none()
+}
+
+/// Looks for a cycle that remains once the back edges of the natural loops are removed.
+struct NonLoopCycleDetector<'a, N: Idx> {
+    dominators: &'a Dominators<N>,
+}
+
+impl<G: ControlFlowGraph> TriColorVisitor<G> for NonLoopCycleDetector<'_, G::Node> {
+    type BreakVal = ();
+
+    fn node_examined(
+        &mut self,
+        _node: G::Node,
+        prior_status: Option<NodeStatus>,
+    ) -> ControlFlow<Self::BreakVal> {
+        match prior_status {
+            Some(NodeStatus::Visited) => ControlFlow::BREAK,
+            _ => ControlFlow::CONTINUE,
+        }
+    }
+
+    fn ignore_edge(&mut self, source: G::Node, target: G::Node) -> bool {
+        self.dominators.is_dominated_by(source, target)
+    }
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+use super::super::dominators::dominators;
+use super::super::tests::TestGraph;
+
+fn loops_of(graph: &TestGraph) -> Vec<(usize, Vec<usize>, Vec<usize>)> {
+    loops(graph, &dominators(graph)).into_iter().map(|l| (l.header, l.latches, l.nodes)).collect()
+}
+
+#[test]
+fn diamond() {
+    let graph = TestGraph::new(0, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+    assert!(loops_of(&graph).is_empty());
+}
+
+#[test]
+fn self_loop() {
+    let graph = TestGraph::new(0, &[(0, 1), (1, 1), (1, 2)]);
+    assert_eq!(loops_of(&graph), vec![(1, vec![1], vec![1])]);
+}
+
+#[test]
+fn nested() {
+    // 1 is the header of the outer loop, 2 the header of the inner one.
+    let graph = TestGraph::new(0, &[(0, 1), (1, 2), (2, 3), (3, 2), (3, 4), (4, 1), (1, 5)]);
+    assert_eq!(loops_of(&graph), vec![(2, vec![3], vec![2, 3]), (1, vec![4], vec![1, 2, 3, 4])],);
+}
+
+#[test]
+fn merged_latches() {
+    // Both `continue`s of the loop are back edges to its header.
+    let graph = TestGraph::new(0, &[(0, 1), (1, 2), (1, 3), (2, 1), (3, 1), (3, 4)]);
+    assert_eq!(loops_of(&graph), vec![(1, vec![2, 3], vec![1, 2, 3])]);
+}
+
+#[test]
+fn irreducible() {
+    // The cycle between 1 and 2 can be entered through both of them.
+    let graph = TestGraph::new(0, &[(0, 1), (0, 2), (1, 2), (2, 1)]);
+    assert!(loops_of(&graph).is_empty());
+    assert!(!is_reducible(&graph, &dominators(&graph)));
+}
+
+#[test]
+fn reducible() {
+    let graph = TestGraph::new(0, &[(0, 1), (1, 2), (2, 3), (3, 2), (3, 1), (1, 4), (0, 4)]);
+    assert!(is_reducible(&graph, &dominators(&graph)));
+}
+
+#[test]
+fn unreachable() {
+    // The cycle between 2 and 3 cannot be reached from the start node.
+    let graph = TestGraph::new(0, &[(0, 1), (2, 3), (3, 2), (3, 1)]);
+    assert!(loops_of(&graph).is_empty());
+}
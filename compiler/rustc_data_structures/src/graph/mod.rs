@@ -3,6 +3,7 @@
 pub mod dominators;
 pub mod implementation;
 pub mod iterate;
+pub mod loops;
 mod reference;
 pub mod scc;
 pub mod vec_graph;
@@ -49,6 +49,7 @@ pub fn cold_path<F: FnOnce() -> R, R>(f: F) -> R {
 pub mod base_n;
 pub mod binary_search_util;
 pub mod captures;
+pub mod compact_stack;
 pub mod flock;
 pub mod functor;
 pub mod fx;
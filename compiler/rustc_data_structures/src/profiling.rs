@@ -88,6 +88,7 @@
 use std::collections::hash_map::Entry;
 use std::error::Error;
 use std::fs;
+use std::io;
 use std::path::Path;
 use std::process;
 use std::sync::Arc;
@@ -98,6 +99,10 @@
 use parking_lot::RwLock;
 use smallvec::SmallVec;
 
+mod event_log;
+
+use event_log::{EventKind, EventLog, OpenInterval};
+
 bitflags::bitflags! {
     struct EventFilter: u32 {
         const GENERIC_ACTIVITIES  = 1 << 0;
@@ -142,6 +147,7 @@ struct EventFilter: u32 {
 ];
 
 /// Something that uniquely identifies a query invocation.
+#[derive(Clone, Copy)]
 pub struct QueryInvocationId(pub u32);
 
 /// A reference to the SelfProfiler. It can be cloned and sent across thread
@@ -238,9 +244,15 @@ pub fn verbose_generic_activity_with_arg<'a, A>(
     #[inline(always)]
     pub fn generic_activity(&self, event_label: &'static str) -> TimingGuard<'_> {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
-            let event_id = EventId::from_label(event_label);
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            let label = profiler.get_or_alloc_cached_string(event_label);
+            let event_id = EventId::from_label(label);
+            TimingGuard::start_logged(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                EventKind::GenericActivity,
+                Some(event_label),
+            )
         })
     }
 
@@ -249,7 +261,13 @@ pub fn generic_activity(&self, event_label: &'static str) -> TimingGuard<'_> {
     #[inline(always)]
     pub fn generic_activity_with_event_id(&self, event_id: EventId) -> TimingGuard<'_> {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_logged(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                EventKind::GenericActivity,
+                None,
+            )
         })
     }
 
@@ -265,15 +283,22 @@ pub fn generic_activity_with_arg<A>(
         A: Borrow<str> + Into<String>,
     {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
-            let builder = EventIdBuilder::new(&profiler.profiler);
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
-            let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
-                let event_arg = profiler.get_or_alloc_cached_string(event_arg);
-                builder.from_label_and_arg(event_label, event_arg)
-            } else {
-                builder.from_label(event_label)
-            };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            let label = profiler.get_or_alloc_cached_string(event_label);
+            let event_id = profiler.event_id(|builder| {
+                if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
+                    let event_arg = profiler.get_or_alloc_cached_string(event_arg);
+                    builder.from_label_and_arg(label, event_arg)
+                } else {
+                    builder.from_label(label)
+                }
+            });
+            TimingGuard::start_logged(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                EventKind::GenericActivity,
+                Some(event_label),
+            )
         })
     }
 
@@ -308,32 +333,39 @@ pub fn generic_activity_with_arg_recorder<F>(
     {
         // Ensure this event will only be recorded when self-profiling is turned on.
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
-            let builder = EventIdBuilder::new(&profiler.profiler);
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
+            let label = profiler.get_or_alloc_cached_string(event_label);
 
             // Ensure the closure to create event arguments will only be called when argument
             // recording is turned on.
-            let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
-                // Set up the builder and call the user-provided closure to record potentially
-                // costly event arguments.
-                let mut recorder = EventArgRecorder { profiler, args: SmallVec::new() };
-                f(&mut recorder);
-
-                // It is expected that the closure will record at least one argument. If that
-                // doesn't happen, it's a bug: we've been explicitly called in order to record
-                // arguments, so we fail loudly when there are none to record.
-                if recorder.args.is_empty() {
-                    panic!(
-                        "The closure passed to `generic_activity_with_arg_recorder` needs to \
-                         record at least one argument"
-                    );
+            let event_id = profiler.event_id(|builder| {
+                if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
+                    // Set up the builder and call the user-provided closure to record potentially
+                    // costly event arguments.
+                    let mut recorder = EventArgRecorder { profiler, args: SmallVec::new() };
+                    f(&mut recorder);
+
+                    // It is expected that the closure will record at least one argument. If that
+                    // doesn't happen, it's a bug: we've been explicitly called in order to record
+                    // arguments, so we fail loudly when there are none to record.
+                    if recorder.args.is_empty() {
+                        panic!(
+                            "The closure passed to `generic_activity_with_arg_recorder` needs to \
+                             record at least one argument"
+                        );
+                    }
+
+                    builder.from_label_and_args(label, &recorder.args)
+                } else {
+                    builder.from_label(label)
                 }
-
-                builder.from_label_and_args(event_label, &recorder.args)
-            } else {
-                builder.from_label(event_label)
-            };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            });
+            TimingGuard::start_logged(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                EventKind::GenericActivity,
+                Some(event_label),
+            )
         })
     }
 
@@ -347,18 +379,21 @@ pub fn artifact_size<A>(&self, artifact_kind: &str, artifact_name: A, size: u64)
         A: Borrow<str> + Into<String>,
     {
         drop(self.exec(EventFilter::ARTIFACT_SIZES, |profiler| {
-            let builder = EventIdBuilder::new(&profiler.profiler);
-            let event_label = profiler.get_or_alloc_cached_string(artifact_kind);
-            let event_arg = profiler.get_or_alloc_cached_string(artifact_name);
-            let event_id = builder.from_label_and_arg(event_label, event_arg);
-            let thread_id = get_thread_id();
-
-            profiler.profiler.record_integer_event(
-                profiler.artifact_size_event_kind,
-                event_id,
-                thread_id,
-                size,
-            );
+            // Artifact sizes are only part of the raw profile data.
+            if let Some(raw_profiler) = &profiler.profiler {
+                let builder = EventIdBuilder::new(raw_profiler);
+                let event_label = profiler.get_or_alloc_cached_string(artifact_kind);
+                let event_arg = profiler.get_or_alloc_cached_string(artifact_name);
+                let event_id = builder.from_label_and_arg(event_label, event_arg);
+                let thread_id = get_thread_id();
+
+                raw_profiler.record_integer_event(
+                    profiler.artifact_size_event_kind,
+                    event_id,
+                    thread_id,
+                    size,
+                );
+            }
 
             TimingGuard::none()
         }))
@@ -371,18 +406,25 @@ pub fn generic_activity_with_args(
         event_args: &[String],
     ) -> TimingGuard<'_> {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
-            let builder = EventIdBuilder::new(&profiler.profiler);
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
-            let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
-                let event_args: Vec<_> = event_args
-                    .iter()
-                    .map(|s| profiler.get_or_alloc_cached_string(&s[..]))
-                    .collect();
-                builder.from_label_and_args(event_label, &event_args)
-            } else {
-                builder.from_label(event_label)
-            };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            let label = profiler.get_or_alloc_cached_string(event_label);
+            let event_id = profiler.event_id(|builder| {
+                if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
+                    let event_args: Vec<_> = event_args
+                        .iter()
+                        .map(|s| profiler.get_or_alloc_cached_string(&s[..]))
+                        .collect();
+                    builder.from_label_and_args(label, &event_args)
+                } else {
+                    builder.from_label(label)
+                }
+            });
+            TimingGuard::start_logged(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                EventKind::GenericActivity,
+                Some(event_label),
+            )
         })
     }
 
@@ -391,18 +433,39 @@ pub fn generic_activity_with_args(
     #[inline(always)]
     pub fn query_provider(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::QUERY_PROVIDERS, |profiler| {
-            TimingGuard::start(profiler, profiler.query_event_kind, EventId::INVALID)
+            TimingGuard::start_logged(
+                profiler,
+                profiler.query_event_kind,
+                EventId::INVALID,
+                EventKind::Query,
+                None,
+            )
         })
     }
 
     /// Record a query in-memory cache hit.
     #[inline(always)]
     pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
-        self.instant_query_event(
-            |profiler| profiler.query_cache_hit_event_kind,
-            query_invocation_id,
-            EventFilter::QUERY_CACHE_HITS,
-        );
+        #[inline(never)]
+        #[cold]
+        fn cold_call(profiler_ref: &SelfProfilerRef, query_invocation_id: QueryInvocationId) {
+            let profiler = profiler_ref.profiler.as_ref().unwrap();
+            if let Some(raw_profiler) = &profiler.profiler {
+                let event_id = StringId::new_virtual(query_invocation_id.0);
+                raw_profiler.record_instant_event(
+                    profiler.query_cache_hit_event_kind,
+                    EventId::from_virtual(event_id),
+                    get_thread_id(),
+                );
+            }
+            if let Some(event_log) = &profiler.event_log {
+                event_log.record_cache_hit(query_invocation_id.0);
+            }
+        }
+
+        if self.event_filter_mask.contains(EventFilter::QUERY_CACHE_HITS) {
+            cold_call(self, query_invocation_id);
+        }
     }
 
     /// Start profiling a query being blocked on a concurrent execution.
@@ -411,7 +474,13 @@ pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
     #[inline(always)]
     pub fn query_blocked(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::QUERY_BLOCKED, |profiler| {
-            TimingGuard::start(profiler, profiler.query_blocked_event_kind, EventId::INVALID)
+            TimingGuard::start_logged(
+                profiler,
+                profiler.query_blocked_event_kind,
+                EventId::INVALID,
+                EventKind::QueryBlocked,
+                None,
+            )
         })
     }
 
@@ -421,10 +490,12 @@ pub fn query_blocked(&self) -> TimingGuard<'_> {
     #[inline(always)]
     pub fn incr_cache_loading(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::INCR_CACHE_LOADS, |profiler| {
-            TimingGuard::start(
+            TimingGuard::start_logged(
                 profiler,
                 profiler.incremental_load_result_event_kind,
                 EventId::INVALID,
+                EventKind::IncrementalLoadResult,
+                None,
             )
         })
     }
@@ -434,35 +505,16 @@ pub fn incr_cache_loading(&self) -> TimingGuard<'_> {
     #[inline(always)]
     pub fn incr_result_hashing(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::INCR_RESULT_HASHING, |profiler| {
-            TimingGuard::start(
+            TimingGuard::start_logged(
                 profiler,
                 profiler.incremental_result_hashing_event_kind,
                 EventId::INVALID,
+                EventKind::IncrementalResultHashing,
+                None,
             )
         })
     }
 
-    #[inline(always)]
-    fn instant_query_event(
-        &self,
-        event_kind: fn(&SelfProfiler) -> StringId,
-        query_invocation_id: QueryInvocationId,
-        event_filter: EventFilter,
-    ) {
-        drop(self.exec(event_filter, |profiler| {
-            let event_id = StringId::new_virtual(query_invocation_id.0);
-            let thread_id = get_thread_id();
-
-            profiler.profiler.record_instant_event(
-                event_kind(profiler),
-                EventId::from_virtual(event_id),
-                thread_id,
-            );
-
-            TimingGuard::none()
-        }));
-    }
-
     pub fn with_profiler(&self, f: impl FnOnce(&SelfProfiler)) {
         if let Some(profiler) = &self.profiler {
             f(profiler)
@@ -490,6 +542,22 @@ pub fn llvm_recording_enabled(&self) -> bool {
     pub fn get_self_profiler(&self) -> Option<Arc<SelfProfiler>> {
         self.profiler.clone()
     }
+
+    /// Prints the activities and queries that took the most time to stderr, for
+    /// `-Z self-profile-summary`.
+    pub fn print_summary(&self) {
+        if let Some(event_log) = self.profiler.as_ref().and_then(|p| p.event_log.as_ref()) {
+            event_log.print_summary();
+        }
+    }
+
+    /// Writes the recorded events as a Chrome trace, for `-Z self-profile-chrome-trace`.
+    pub fn write_chrome_trace(&self) -> io::Result<()> {
+        match self.profiler.as_ref().and_then(|p| p.event_log.as_ref()) {
+            Some(event_log) => event_log.write_chrome_trace(),
+            None => Ok(()),
+        }
+    }
 }
 
 /// A helper for recording costly arguments to self-profiling events. Used with
@@ -520,7 +588,9 @@ pub fn record_arg<A>(&mut self, event_arg: A)
 }
 
 pub struct SelfProfiler {
-    profiler: Profiler,
+    /// Writes the raw profile data for `-Z self-profile`. This is `None` if only the events kept
+    /// in memory for `-Z self-profile-summary` or `-Z self-profile-chrome-trace` are needed.
+    profiler: Option<Profiler>,
     event_filter_mask: EventFilter,
 
     string_cache: RwLock<FxHashMap<String, StringId>>,
@@ -532,6 +602,9 @@ pub struct SelfProfiler {
     query_blocked_event_kind: StringId,
     query_cache_hit_event_kind: StringId,
     artifact_size_event_kind: StringId,
+
+    /// The events kept in memory for `-Z self-profile-summary` and `-Z self-profile-chrome-trace`.
+    event_log: Option<EventLog>,
 }
 
 impl SelfProfiler {
@@ -540,6 +613,8 @@ pub fn new(
         crate_name: Option<&str>,
         event_filters: Option<&[String]>,
         counter_name: &str,
+        write_raw_profile: bool,
+        keep_events_in_memory: bool,
     ) -> Result<SelfProfiler, Box<dyn Error + Send + Sync>> {
         fs::create_dir_all(output_directory)?;
 
@@ -548,19 +623,24 @@ pub fn new(
         // length can behave as a source of entropy for heap addresses, when
         // ASLR is disabled and the heap is otherwise determinic.
         let pid: u32 = process::id();
-        let filename = format!("{}-{:07}.rustc_profile", crate_name, pid);
-        let path = output_directory.join(&filename);
-        let profiler =
-            Profiler::with_counter(&path, measureme::counters::Counter::by_name(counter_name)?)?;
-
-        let query_event_kind = profiler.alloc_string("Query");
-        let generic_activity_event_kind = profiler.alloc_string("GenericActivity");
-        let incremental_load_result_event_kind = profiler.alloc_string("IncrementalLoadResult");
-        let incremental_result_hashing_event_kind =
-            profiler.alloc_string("IncrementalResultHashing");
-        let query_blocked_event_kind = profiler.alloc_string("QueryBlocked");
-        let query_cache_hit_event_kind = profiler.alloc_string("QueryCacheHit");
-        let artifact_size_event_kind = profiler.alloc_string("ArtifactSize");
+        let profiler = if write_raw_profile {
+            let filename = format!("{}-{:07}.rustc_profile", crate_name, pid);
+            let path = output_directory.join(&filename);
+            let counter = measureme::counters::Counter::by_name(counter_name)?;
+            Some(Profiler::with_counter(&path, counter)?)
+        } else {
+            None
+        };
+
+        let alloc_string =
+            |s: &str| profiler.as_ref().map_or(StringId::INVALID, |p| p.alloc_string(s));
+        let query_event_kind = alloc_string("Query");
+        let generic_activity_event_kind = alloc_string("GenericActivity");
+        let incremental_load_result_event_kind = alloc_string("IncrementalLoadResult");
+        let incremental_result_hashing_event_kind = alloc_string("IncrementalResultHashing");
+        let query_blocked_event_kind = alloc_string("QueryBlocked");
+        let query_cache_hit_event_kind = alloc_string("QueryCacheHit");
+        let artifact_size_event_kind = alloc_string("ArtifactSize");
 
         let mut event_filter_mask = EventFilter::empty();
 
@@ -595,6 +675,13 @@ pub fn new(
             event_filter_mask = EventFilter::DEFAULT;
         }
 
+        let event_log = keep_events_in_memory.then(|| {
+            // The summary reports cache hits per query.
+            event_filter_mask |= EventFilter::QUERY_CACHE_HITS;
+            let filename = format!("{}-{:07}.chrome_trace.json", crate_name, pid);
+            EventLog::new(output_directory.join(filename))
+        });
+
         Ok(SelfProfiler {
             profiler,
             event_filter_mask,
@@ -606,13 +693,14 @@ pub fn new(
             query_blocked_event_kind,
             query_cache_hit_event_kind,
             artifact_size_event_kind,
+            event_log,
         })
     }
 
     /// Allocates a new string in the profiling data. Does not do any caching
     /// or deduplication.
     pub fn alloc_string<STR: SerializableString + ?Sized>(&self, s: &STR) -> StringId {
-        self.profiler.alloc_string(s)
+        self.profiler.as_ref().map_or(StringId::INVALID, |profiler| profiler.alloc_string(s))
     }
 
     /// Gets a `StringId` for the given string. This method makes sure that
@@ -622,6 +710,10 @@ pub fn get_or_alloc_cached_string<A>(&self, s: A) -> StringId
     where
         A: Borrow<str> + Into<String>,
     {
+        if self.profiler.is_none() {
+            return StringId::INVALID;
+        }
+
         // Only acquire a read-lock first since we assume that the string is
         // already present in the common case.
         {
@@ -638,36 +730,66 @@ pub fn get_or_alloc_cached_string<A>(&self, s: A) -> StringId
         match string_cache.entry(s.into()) {
             Entry::Occupied(e) => *e.get(),
             Entry::Vacant(e) => {
-                let string_id = self.profiler.alloc_string(&e.key()[..]);
+                let string_id = self.alloc_string(&e.key()[..]);
                 *e.insert(string_id)
             }
         }
     }
 
     pub fn map_query_invocation_id_to_string(&self, from: QueryInvocationId, to: StringId) {
-        let from = StringId::new_virtual(from.0);
-        self.profiler.map_virtual_to_concrete_string(from, to);
+        if let Some(profiler) = &self.profiler {
+            let from = StringId::new_virtual(from.0);
+            profiler.map_virtual_to_concrete_string(from, to);
+        }
     }
 
     pub fn bulk_map_query_invocation_id_to_single_string<I>(&self, from: I, to: StringId)
     where
         I: Iterator<Item = QueryInvocationId> + ExactSizeIterator,
     {
-        let from = from.map(|qid| StringId::new_virtual(qid.0));
-        self.profiler.bulk_map_virtual_to_single_concrete_string(from, to);
+        if let Some(profiler) = &self.profiler {
+            let from = from.map(|qid| StringId::new_virtual(qid.0));
+            profiler.bulk_map_virtual_to_single_concrete_string(from, to);
+        }
     }
 
     pub fn query_key_recording_enabled(&self) -> bool {
         self.event_filter_mask.contains(EventFilter::QUERY_KEYS)
     }
 
-    pub fn event_id_builder(&self) -> EventIdBuilder<'_> {
-        EventIdBuilder::new(&self.profiler)
+    /// Whether events are kept in memory for `-Z self-profile-summary` or
+    /// `-Z self-profile-chrome-trace`.
+    pub fn event_log_enabled(&self) -> bool {
+        self.event_log.is_some()
+    }
+
+    /// Records the name of the query an invocation belongs to in the in-memory events. Returns
+    /// `true` if the key of the invocation should be recorded as well, with `record_query_key`.
+    pub fn record_query_invocation(&self, id: QueryInvocationId, query_name: &'static str) -> bool {
+        self.event_log.as_ref().map_or(false, |log| log.record_query_invocation(id.0, query_name))
+    }
+
+    pub fn record_query_key(&self, id: QueryInvocationId, query_key: String) {
+        if let Some(event_log) = &self.event_log {
+            event_log.record_query_key(id.0, query_key);
+        }
+    }
+
+    /// Returns `None` if no raw profile data is written, in which case there is no need to build
+    /// event ids.
+    pub fn event_id_builder(&self) -> Option<EventIdBuilder<'_>> {
+        self.profiler.as_ref().map(EventIdBuilder::new)
+    }
+
+    /// Builds the id of an event with `f`, or returns `EventId::INVALID` if no raw profile data
+    /// is written.
+    fn event_id(&self, f: impl FnOnce(EventIdBuilder<'_>) -> EventId) -> EventId {
+        self.event_id_builder().map_or(EventId::INVALID, f)
     }
 }
 
 #[must_use]
-pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>);
+pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>, Option<OpenInterval<'a>>);
 
 impl<'a> TimingGuard<'a> {
     #[inline]
@@ -676,27 +798,45 @@ pub fn start(
         event_kind: StringId,
         event_id: EventId,
     ) -> TimingGuard<'a> {
-        let thread_id = get_thread_id();
-        let raw_profiler = &profiler.profiler;
-        let timing_guard =
-            raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id);
-        TimingGuard(Some(timing_guard))
+        let timing_guard = profiler.profiler.as_ref().map(|raw_profiler| {
+            let thread_id = get_thread_id();
+            raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id)
+        });
+        TimingGuard(timing_guard, None)
+    }
+
+    /// Like `start`, but also keeps the event in memory if the profiler does.
+    #[inline]
+    fn start_logged(
+        profiler: &'a SelfProfiler,
+        event_kind: StringId,
+        event_id: EventId,
+        kind: EventKind,
+        label: Option<&'static str>,
+    ) -> TimingGuard<'a> {
+        let interval = profiler.event_log.as_ref().map(|log| log.start_interval(kind, label));
+        let TimingGuard(timing_guard, _) = TimingGuard::start(profiler, event_kind, event_id);
+        TimingGuard(timing_guard, interval)
     }
 
     #[inline]
     pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
-        if let Some(guard) = self.0 {
+        let TimingGuard(guard, interval) = self;
+        if let Some(guard) = guard {
             cold_path(|| {
                 let event_id = StringId::new_virtual(query_invocation_id.0);
                 let event_id = EventId::from_virtual(event_id);
                 guard.finish_with_override_event_id(event_id);
             });
         }
+        if let Some(mut interval) = interval {
+            interval.set_query_invocation_id(query_invocation_id.0);
+        }
     }
 
     #[inline]
     pub fn none() -> TimingGuard<'a> {
-        TimingGuard(None)
+        TimingGuard(None, None)
     }
 
     #[inline(always)]
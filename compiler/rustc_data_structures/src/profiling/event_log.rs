@@ -0,0 +1,382 @@
+//! Self-profiling events kept in memory, for `-Z self-profile-summary` and
+//! `-Z self-profile-chrome-trace`.
+//!
+//! The raw event data written by `measureme` needs the tools from the `measureme`
+//! project to be read back. When a summary or a Chrome trace is requested, the
+//! interval events are additionally logged here, so that they can be reported
+//! by the compiler itself at the end of the compilation session.
+//!
+//! Query provider events only know the `QueryInvocationId` of the query they
+//! belong to, just like in the `measureme` data. The query names are recorded
+//! later, when the query strings are allocated in bulk. Query keys are only
+//! formatted for the few invocations that are reported individually.
+
+use crate::fx::{FxHashMap, FxHashSet};
+
+use parking_lot::Mutex;
+use serde_json::json;
+use std::cmp::Reverse;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::{duration_to_secs_str, get_thread_id};
+
+/// The number of items and query invocations listed in the summary.
+const SUMMARY_LEN: usize = 25;
+const EXPENSIVE_INVOCATIONS_LEN: usize = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum EventKind {
+    GenericActivity,
+    Query,
+    QueryBlocked,
+    IncrementalLoadResult,
+    IncrementalResultHashing,
+}
+
+impl EventKind {
+    fn name(self) -> &'static str {
+        match self {
+            EventKind::GenericActivity => "GenericActivity",
+            EventKind::Query => "Query",
+            EventKind::QueryBlocked => "QueryBlocked",
+            EventKind::IncrementalLoadResult => "IncrementalLoadResult",
+            EventKind::IncrementalResultHashing => "IncrementalResultHashing",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum EventLabel {
+    Activity(&'static str),
+    /// A query invocation, see `QueryInvocationId`.
+    Query(u32),
+    Unknown,
+}
+
+struct Interval {
+    kind: EventKind,
+    label: EventLabel,
+    thread_id: u32,
+    /// Nanoseconds since the creation of the `EventLog`.
+    start: u64,
+    end: u64,
+}
+
+impl Interval {
+    fn duration(&self) -> u64 {
+        self.end - self.start
+    }
+}
+
+pub(super) struct EventLog {
+    start: Instant,
+    chrome_trace_path: PathBuf,
+    intervals: Mutex<Vec<Interval>>,
+    cache_hits: Mutex<FxHashMap<u32, u32>>,
+    query_names: Mutex<FxHashMap<u32, &'static str>>,
+    query_keys: Mutex<FxHashMap<u32, String>>,
+    /// The query invocations with the highest self time, along with the number of intervals they
+    /// were computed from. Recomputed when intervals were logged since, as later queries may take
+    /// the place of earlier ones.
+    expensive_invocations: Mutex<(usize, FxHashSet<u32>)>,
+}
+
+/// An interval event that is logged when dropped.
+pub(super) struct OpenInterval<'a> {
+    log: &'a EventLog,
+    kind: EventKind,
+    label: EventLabel,
+    thread_id: u32,
+    start: u64,
+}
+
+impl OpenInterval<'_> {
+    pub(super) fn set_query_invocation_id(&mut self, query_invocation_id: u32) {
+        self.label = EventLabel::Query(query_invocation_id);
+    }
+}
+
+impl Drop for OpenInterval<'_> {
+    fn drop(&mut self) {
+        let end = self.log.now();
+        self.log.intervals.lock().push(Interval {
+            kind: self.kind,
+            label: self.label,
+            thread_id: self.thread_id,
+            start: self.start,
+            end,
+        });
+    }
+}
+
+/// The costs attributed to an activity or a query, summed over all its invocations.
+#[derive(Default)]
+struct ItemStats {
+    self_time: u64,
+    invocations: u64,
+    cache_hits: u64,
+    incremental_load_time: u64,
+    incremental_hashing_time: u64,
+}
+
+impl EventLog {
+    pub(super) fn new(chrome_trace_path: PathBuf) -> EventLog {
+        EventLog {
+            start: Instant::now(),
+            chrome_trace_path,
+            intervals: Default::default(),
+            cache_hits: Default::default(),
+            query_names: Default::default(),
+            query_keys: Default::default(),
+            expensive_invocations: Default::default(),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+
+    pub(super) fn start_interval(
+        &self,
+        kind: EventKind,
+        label: Option<&'static str>,
+    ) -> OpenInterval<'_> {
+        OpenInterval {
+            log: self,
+            kind,
+            label: label.map_or(EventLabel::Unknown, EventLabel::Activity),
+            thread_id: get_thread_id(),
+            start: self.now(),
+        }
+    }
+
+    pub(super) fn record_cache_hit(&self, query_invocation_id: u32) {
+        *self.cache_hits.lock().entry(query_invocation_id).or_default() += 1;
+    }
+
+    /// Records the name of the query that `query_invocation_id` belongs to. Returns `true` if the
+    /// invocation is reported individually, in which case its key should be recorded as well.
+    pub(super) fn record_query_invocation(
+        &self,
+        query_invocation_id: u32,
+        query_name: &'static str,
+    ) -> bool {
+        self.query_names.lock().insert(query_invocation_id, query_name);
+
+        let intervals = self.intervals.lock();
+        let mut expensive_invocations = self.expensive_invocations.lock();
+        let (interval_count, invocations) = &mut *expensive_invocations;
+        if *interval_count != intervals.len() {
+            *interval_count = intervals.len();
+            *invocations = Self::expensive_invocations(&intervals);
+        }
+        invocations.contains(&query_invocation_id)
+    }
+
+    pub(super) fn record_query_key(&self, query_invocation_id: u32, query_key: String) {
+        self.query_keys.lock().insert(query_invocation_id, query_key);
+    }
+
+    fn expensive_invocations(intervals: &[Interval]) -> FxHashSet<u32> {
+        let (self_times, _) = self_times(intervals);
+
+        let mut invocations: Vec<_> = intervals
+            .iter()
+            .zip(self_times)
+            .filter_map(|(interval, self_time)| match (interval.kind, interval.label) {
+                (EventKind::Query, EventLabel::Query(id)) => Some((self_time, id)),
+                _ => None,
+            })
+            .collect();
+        invocations.sort_unstable_by_key(|&(self_time, id)| (Reverse(self_time), id));
+        invocations.into_iter().take(EXPENSIVE_INVOCATIONS_LEN).map(|(_, id)| id).collect()
+    }
+
+    fn label_name(&self, label: EventLabel) -> &'static str {
+        match label {
+            EventLabel::Activity(name) => name,
+            EventLabel::Query(id) => {
+                self.query_names.lock().get(&id).copied().unwrap_or("<unknown query>")
+            }
+            EventLabel::Unknown => "<unknown>",
+        }
+    }
+
+    /// Prints the activities and queries with the highest self time, along with their cache hits
+    /// and the time spent on loading and hashing their results for incremental compilation, and
+    /// the most expensive query invocations.
+    pub(super) fn print_summary(&self) {
+        let intervals = self.intervals.lock();
+        let (self_times, parents) = self_times(&intervals);
+
+        let mut items: FxHashMap<&'static str, ItemStats> = FxHashMap::default();
+        for (index, interval) in intervals.iter().enumerate() {
+            match interval.kind {
+                EventKind::GenericActivity | EventKind::Query => {
+                    let item = items.entry(self.label_name(interval.label)).or_default();
+                    item.self_time += self_times[index];
+                    item.invocations += 1;
+                }
+                EventKind::IncrementalLoadResult => {
+                    let item = items.entry(self.label_name(interval.label)).or_default();
+                    item.incremental_load_time += interval.duration();
+                }
+                EventKind::IncrementalResultHashing => {
+                    // Results are hashed within the provider of the query they belong to.
+                    if let Some(parent) = parents[index] {
+                        let item =
+                            items.entry(self.label_name(intervals[parent].label)).or_default();
+                        item.incremental_hashing_time += interval.duration();
+                    }
+                }
+                EventKind::QueryBlocked => {}
+            }
+        }
+        for (&id, &hits) in self.cache_hits.lock().iter() {
+            items.entry(self.label_name(EventLabel::Query(id))).or_default().cache_hits +=
+                hits as u64;
+        }
+
+        let total_self_time: u64 = self_times.iter().sum();
+        let mut items: Vec<_> = items.into_iter().collect();
+        items.sort_unstable_by_key(|&(name, ref stats)| (Reverse(stats.self_time), name));
+
+        let secs = |nanos: u64| duration_to_secs_str(Duration::from_nanos(nanos));
+        let name_width =
+            items.iter().take(SUMMARY_LEN).map(|(name, _)| name.len()).max().unwrap_or(0).max(4);
+
+        eprintln!(
+            "self-profile: top {} of {} items by self time, total self time: {}s",
+            SUMMARY_LEN.min(items.len()),
+            items.len(),
+            secs(total_self_time),
+        );
+        eprintln!(
+            "{:<name_width$}  {:>9}  {:>7}  {:>11}  {:>10}  {:>10}  {:>13}",
+            "Item",
+            "Self time",
+            "% total",
+            "Invocations",
+            "Cache hits",
+            "Incr. load",
+            "Incr. hashing",
+        );
+        for (name, stats) in items.iter().take(SUMMARY_LEN) {
+            let percent = if total_self_time == 0 {
+                0.0
+            } else {
+                stats.self_time as f64 * 100.0 / total_self_time as f64
+            };
+            eprintln!(
+                "{:<name_width$}  {:>9}  {:>6.2}%  {:>11}  {:>10}  {:>10}  {:>13}",
+                name,
+                secs(stats.self_time),
+                percent,
+                stats.invocations,
+                stats.cache_hits,
+                secs(stats.incremental_load_time),
+                secs(stats.incremental_hashing_time),
+            );
+        }
+
+        let query_keys = self.query_keys.lock();
+        let mut invocations: Vec<_> = intervals
+            .iter()
+            .zip(&self_times)
+            .filter_map(|(interval, &self_time)| match (interval.kind, interval.label) {
+                (EventKind::Query, EventLabel::Query(id)) => {
+                    query_keys.get(&id).map(|key| (self_time, id, key))
+                }
+                _ => None,
+            })
+            .collect();
+        if invocations.is_empty() {
+            return;
+        }
+        invocations.sort_unstable_by_key(|&(self_time, id, _)| (Reverse(self_time), id));
+
+        eprintln!();
+        eprintln!("self-profile: most expensive query invocations by self time");
+        for (self_time, id, key) in invocations.into_iter().take(EXPENSIVE_INVOCATIONS_LEN) {
+            eprintln!(
+                "{:>9}  {}({})",
+                secs(self_time),
+                self.label_name(EventLabel::Query(id)),
+                key
+            );
+        }
+    }
+
+    /// Writes all interval events in the Chrome trace event format, which can be loaded into
+    /// `chrome://tracing`, Perfetto or Speedscope.
+    pub(super) fn write_chrome_trace(&self) -> io::Result<()> {
+        let intervals = self.intervals.lock();
+        let query_keys = self.query_keys.lock();
+        let pid = std::process::id();
+
+        let events: Vec<_> = intervals
+            .iter()
+            .map(|interval| {
+                let mut event = json!({
+                    "name": self.label_name(interval.label),
+                    "cat": interval.kind.name(),
+                    "ph": "X",
+                    "ts": interval.start as f64 / 1000.0,
+                    "dur": interval.duration() as f64 / 1000.0,
+                    "pid": pid,
+                    "tid": interval.thread_id,
+                });
+                if let EventLabel::Query(id) = interval.label {
+                    if let Some(key) = query_keys.get(&id) {
+                        event["args"] = json!({ "key": key });
+                    }
+                }
+                event
+            })
+            .collect();
+
+        let mut file = BufWriter::new(File::create(&self.chrome_trace_path)?);
+        serde_json::to_writer(
+            &mut file,
+            &json!({ "traceEvents": events, "displayTimeUnit": "ms" }),
+        )?;
+        file.flush()
+    }
+}
+
+/// Computes the self time of every interval, i.e. its duration minus the duration of the
+/// intervals directly nested in it on the same thread, and the index of the interval each
+/// interval is directly nested in.
+fn self_times(intervals: &[Interval]) -> (Vec<u64>, Vec<Option<usize>>) {
+    let mut self_times: Vec<_> = intervals.iter().map(Interval::duration).collect();
+    let mut parents = vec![None; intervals.len()];
+
+    let mut order: Vec<_> = (0..intervals.len()).collect();
+    order.sort_unstable_by_key(|&i| {
+        let interval = &intervals[i];
+        (interval.thread_id, interval.start, Reverse(interval.end))
+    });
+
+    let mut stack: Vec<usize> = vec![];
+    let mut current_thread = None;
+    for index in order {
+        let interval = &intervals[index];
+        if current_thread != Some(interval.thread_id) {
+            stack.clear();
+            current_thread = Some(interval.thread_id);
+        }
+        while stack.last().map_or(false, |&top| intervals[top].end <= interval.start) {
+            stack.pop();
+        }
+        if let Some(&parent) = stack.last() {
+            self_times[parent] = self_times[parent].saturating_sub(interval.duration());
+            parents[index] = Some(parent);
+        }
+        stack.push(index);
+    }
+
+    (self_times, parents)
+}
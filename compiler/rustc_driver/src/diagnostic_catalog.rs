@@ -0,0 +1,161 @@
+//! The catalog of diagnostics printed by `--print diagnostic-catalog`, and the coverage of its
+//! messages by a translation, printed by `--print translation-coverage`.
+//!
+//! The catalog is a single JSON object listing every diagnostic rustc (and the lint tools
+//! registered by the driver, like Clippy) can emit, so that tools can triage, suppress and link
+//! diagnostics without scraping their text:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "diagnostics": [
+//!     {
+//!       "id": "unused_variables",
+//!       "kind": "lint",
+//!       "default_level": "warn",
+//!       "groups": ["unused"],
+//!       "edition": null,
+//!       "explanation": "detect variables which are not used in any way",
+//!       "url": "https://doc.rust-lang.org/rustc/lints/listing/warn-by-default.html#unused-variables"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Each diagnostic has one of three kinds, which determine the form of its `id`:
+//!
+//! * `error_code`: an error code, like `E0308`. The `explanation` is the text shown by
+//!   `rustc --explain`. Error codes are never reused, even once they are no longer emitted.
+//! * `lint`: a lint name, as used in `#[allow(..)]`, like `unused_variables` or
+//!   `clippy::needless_return`. `groups` lists the lint groups the lint belongs to, `edition`
+//!   the edition from which the lint has a different default level, if any, and `explanation`
+//!   is the description shown by `rustc -W help`.
+//! * `message`: the Fluent identifier of a diagnostic message, like `borrowck_move_unsized`.
+//!   Identifiers always start with the name of the crate emitting them, which is the only
+//!   element of `groups`. The `explanation` is the "en-US" message in Fluent syntax, and
+//!   `attributes` holds the sub-messages used for its labels, notes and suggestions. The level
+//!   of a message depends on where it is emitted, so its `default_level` is `null`.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::{LanguageIdentifier, TranslationCoverage};
+use rustc_lint::LintStore;
+use rustc_session::lint::{Lint, LintId};
+use serde_json::{json, Value};
+
+const DIAGNOSTIC_CATALOG_VERSION: u32 = 1;
+
+pub(crate) fn print_diagnostic_catalog(lint_store: &LintStore) {
+    let mut diagnostics = vec![];
+
+    for &(code, explanation) in rustc_error_codes::DIAGNOSTICS {
+        diagnostics.push(json!({
+            "id": code,
+            "kind": "error_code",
+            "default_level": "error",
+            "groups": [],
+            "edition": null,
+            "explanation": explanation,
+            "url": format!("https://doc.rust-lang.org/error-index.html#{code}"),
+        }));
+    }
+
+    let mut lint_groups: FxHashMap<LintId, Vec<&'static str>> = FxHashMap::default();
+    for (group, lints, _) in lint_store.get_lint_groups() {
+        for lint in lints {
+            lint_groups.entry(lint).or_default().push(group);
+        }
+    }
+    let mut lints: Vec<&'static Lint> = lint_store.get_lints().to_vec();
+    lints.sort_by_key(|lint| lint.name);
+    for lint in lints {
+        let mut groups = lint_groups.remove(&LintId::of(lint)).unwrap_or_default();
+        groups.sort_unstable();
+        diagnostics.push(json!({
+            "id": lint.name_lower(),
+            "kind": "lint",
+            "default_level": lint.default_level.as_str(),
+            "groups": groups,
+            "edition": lint.edition_lint_opts.map(|(edition, level)| json!({
+                "edition": edition.to_string(),
+                "default_level": level.as_str(),
+            })),
+            "explanation": lint.desc,
+            "url": lint_url(lint),
+        }));
+    }
+
+    for message in rustc_errors::default_locale_catalog() {
+        let attributes: serde_json::Map<String, Value> = message
+            .attributes
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), Value::String(value)))
+            .collect();
+        diagnostics.push(json!({
+            "id": message.id,
+            "kind": "message",
+            "default_level": null,
+            "groups": [message.resource],
+            "edition": null,
+            "explanation": message.value,
+            "attributes": attributes,
+        }));
+    }
+
+    let catalog = json!({
+        "version": DIAGNOSTIC_CATALOG_VERSION,
+        "diagnostics": diagnostics,
+    });
+    println!("{}", serde_json::to_string_pretty(&catalog).unwrap());
+}
+
+/// The documentation of lints built into rustc, which is organized by their default level.
+fn lint_url(lint: &Lint) -> Option<String> {
+    if lint.is_plugin || lint.name.contains("::") {
+        return None;
+    }
+    let page = match lint.default_level.as_str() {
+        "allow" => "allowed-by-default",
+        "warn" => "warn-by-default",
+        _ => "deny-by-default",
+    };
+    Some(format!(
+        "https://doc.rust-lang.org/rustc/lints/listing/{page}.html#{}",
+        lint.name_lower().replace('_', "-")
+    ))
+}
+
+/// Prints how many messages of each Fluent resource are translated in `locale`, followed by the
+/// untranslated messages and the translated messages that rustc doesn't know about:
+///
+/// ```text
+/// translation coverage of `es`: 27 of 1834 messages (1.47%)
+/// driver: 8 of 8 messages (100.00%)
+/// parse: 19 of 351 messages (5.41%)
+/// missing: parse_unexpected_token_after_not
+/// missing: parse_incorrect_semicolon.help
+/// unknown: parse_removed_message
+/// ```
+pub(crate) fn print_translation_coverage(
+    locale: &LanguageIdentifier,
+    coverage: &TranslationCoverage,
+) {
+    let percent = |complete: usize, total: usize| {
+        if total == 0 { 100.0 } else { complete as f64 * 100.0 / total as f64 }
+    };
+
+    let total: usize = coverage.resources.iter().map(|&(_, total, _)| total).sum();
+    let complete: usize = coverage.resources.iter().map(|&(.., complete)| complete).sum();
+    println!(
+        "translation coverage of `{locale}`: {complete} of {total} messages ({:.2}%)",
+        percent(complete, total)
+    );
+    for &(resource, total, complete) in &coverage.resources {
+        println!("{resource}: {complete} of {total} messages ({:.2}%)", percent(complete, total));
+    }
+    for missing in &coverage.missing {
+        println!("missing: {missing}");
+    }
+    for unknown in &coverage.unknown {
+        println!("unknown: {unknown}");
+    }
+}
@@ -32,6 +32,7 @@
 use rustc_metadata::locator;
 use rustc_save_analysis as save;
 use rustc_save_analysis::DumpHandler;
+use rustc_session::config::MirOptStatsFormat;
 use rustc_session::config::{nightly_options, CG_OPTIONS, Z_OPTIONS};
 use rustc_session::config::{ErrorOutputType, Input, OutputType, PrintRequest, TrimmedDefPaths};
 use rustc_session::cstore::MetadataLoader;
@@ -57,12 +58,14 @@
 use std::time::Instant;
 
 pub mod args;
+mod diagnostic_catalog;
+mod lint_levels;
 pub mod pretty;
 mod session_diagnostics;
 
 use crate::session_diagnostics::{
-    RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch, RLinkRustcVersionMismatch,
-    RLinkWrongFileType, RlinkNotAFile, RlinkUnableToRead,
+    ChromeTraceWriteFail, RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch,
+    RLinkRustcVersionMismatch, RLinkWrongFileType, RlinkNotAFile, RlinkUnableToRead,
 };
 
 /// Exit status code used for successful compilation and help output.
@@ -264,6 +267,7 @@ fn run_compiler(
                         compiler.output_dir(),
                         compiler.output_file(),
                         compiler.temps_dir(),
+                        compiler.register_lints(),
                     );
 
                     if should_stop == Compilation::Stop {
@@ -293,6 +297,7 @@ fn run_compiler(
             compiler.output_dir(),
             compiler.output_file(),
             compiler.temps_dir(),
+            compiler.register_lints(),
         )
         .and_then(|| {
             list_metadata(sess, &*compiler.codegen_backend().metadata_loader(), compiler.input())
@@ -398,6 +403,14 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if let Some(format) = sess.opts.unstable_opts.mir_opt_stats {
+                let crate_name = queries.crate_name()?.peek().to_string();
+                match format {
+                    MirOptStatsFormat::Human => sess.code_stats.print_mir_opt_stats(&crate_name),
+                    MirOptStatsFormat::Json => print_mir_opt_stats_json(sess, &crate_name),
+                }
+            }
+
             let linker = queries.linker()?;
             Ok(Some(linker))
         })?;
@@ -411,6 +424,16 @@ fn run_compiler(
             sess.print_perf_stats();
         }
 
+        if sess.opts.unstable_opts.self_profile_summary {
+            sess.prof.print_summary();
+        }
+
+        if sess.opts.unstable_opts.self_profile_chrome_trace {
+            if let Err(err) = sess.prof.write_chrome_trace() {
+                sess.emit_warning(ChromeTraceWriteFail { err });
+            }
+        }
+
         if sess.opts.unstable_opts.print_fuel.is_some() {
             eprintln!(
                 "Fuel used by {}: {}",
@@ -430,6 +453,30 @@ fn make_output(matches: &getopts::Matches) -> (Option<PathBuf>, Option<PathBuf>)
     (odir, ofile)
 }
 
+/// Prints the statistics collected by `-Z mir-opt-stats=json` to stdout.
+fn print_mir_opt_stats_json(sess: &Session, crate_name: &str) {
+    let passes: Vec<_> = sess
+        .code_stats
+        .mir_pass_stats()
+        .into_iter()
+        .map(|(name, stats)| {
+            serde_json::json!({
+                "name": name,
+                "runs": stats.runs,
+                "statements_before": stats.statements_before,
+                "statements_after": stats.statements_after,
+                "locals_before": stats.locals_before,
+                "locals_after": stats.locals_after,
+                "blocks_before": stats.blocks_before,
+                "blocks_after": stats.blocks_after,
+                "seconds": stats.time.as_secs_f64(),
+            })
+        })
+        .collect();
+    let stats = serde_json::json!({ "crate": crate_name, "passes": passes });
+    println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+}
+
 // Extract input (string or file and optional path) from matches.
 fn make_input(
     error_format: ErrorOutputType,
@@ -629,6 +676,7 @@ fn print_crate_info(
     odir: &Option<PathBuf>,
     ofile: &Option<PathBuf>,
     temps_dir: &Option<PathBuf>,
+    register_lints: &Option<Box<dyn Fn(&Session, &mut LintStore) + Send + Sync>>,
 ) -> Compilation {
     use rustc_session::config::PrintRequest::*;
     // NativeStaticLibs and LinkArgs are special - printed during linking
@@ -743,6 +791,40 @@ fn print_crate_info(
                     }
                 }
             }
+            DiagnosticCatalog => {
+                let mut lint_store = rustc_lint::new_lint_store(sess.enable_internal_lints());
+                if let Some(register_lints) = register_lints {
+                    register_lints(sess, &mut lint_store);
+                }
+                diagnostic_catalog::print_diagnostic_catalog(&lint_store);
+            }
+            TranslationCoverage => {
+                // `-Z translate-lang` is required by the print request.
+                let locale = sess.opts.unstable_opts.translate_lang.as_ref().unwrap();
+                let coverage = rustc_errors::translation_coverage(
+                    sess.opts.maybe_sysroot.clone(),
+                    rustc_session::filesearch::sysroot_candidates().to_vec(),
+                    locale,
+                    sess.opts.unstable_opts.translate_additional_ftl.as_deref(),
+                )
+                .unwrap_or_else(|e| {
+                    early_error(
+                        sess.opts.error_format,
+                        &format!("failed to load fluent bundle: {e}"),
+                    )
+                });
+                diagnostic_catalog::print_translation_coverage(locale, &coverage);
+            }
+            LintLevels => {
+                let input = input.unwrap_or_else(|| {
+                    early_error(ErrorOutputType::default(), "no input file provided")
+                });
+                let mut lint_store = rustc_lint::new_lint_store(sess.enable_internal_lints());
+                if let Some(register_lints) = register_lints {
+                    register_lints(sess, &mut lint_store);
+                }
+                lint_levels::print_lint_levels(sess, &lint_store, input, attrs.as_ref().unwrap());
+            }
         }
     }
     Compilation::Stop
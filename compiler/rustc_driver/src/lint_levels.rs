@@ -0,0 +1,103 @@
+//! The lint levels printed by `--print lint-levels`.
+//!
+//! Every lint known to rustc and to the lint tools registered by the driver is printed with its
+//! level at the crate root and where that level comes from, one lint per line:
+//!
+//! ```text
+//! unsafe_code: deny (lint configuration file, line 1)
+//!     allow in `crate::ffi` (lint configuration file, line 2)
+//! unused_variables: warn (default)
+//! ```
+//!
+//! The indented lines list the entries of the `-Z lint-config` file that change the level of the
+//! lint in some modules or files only. Lint attributes on items other than the crate root are
+//! not taken into account, as the crate is not parsed past its root attributes.
+
+use rustc_ast as ast;
+use rustc_lint::LintStore;
+use rustc_middle::lint::LintLevelSource;
+use rustc_session::config::Input;
+use rustc_session::lint::{Level, LintId};
+use rustc_session::lint_config::{LintConfigEntry, LintConfigScope};
+use rustc_session::Session;
+
+pub(crate) fn print_lint_levels(
+    sess: &Session,
+    lint_store: &LintStore,
+    input: &Input,
+    crate_attrs: &ast::AttrVec,
+) {
+    // The levels of feature-gated lints depend on the features of the crate.
+    let krate = ast::Crate {
+        attrs: crate_attrs.clone(),
+        items: vec![],
+        spans: Default::default(),
+        id: ast::CRATE_NODE_ID,
+        is_placeholder: false,
+    };
+    let (krate, features) = rustc_expand::config::features(sess, krate, ast::CRATE_NODE_ID);
+    sess.init_features(features);
+
+    let root_file = sess
+        .source_map()
+        .get_source_file(&input.source_name())
+        .map(|file| file.name.prefer_remapped().to_string());
+    let mut levels = rustc_lint::crate_root_lint_levels(sess, lint_store, &krate.attrs, root_file);
+    levels.sort_by_key(|(lint, _)| lint.name_lower());
+
+    let scoped_entries: Vec<&LintConfigEntry> = sess
+        .opts
+        .lint_config
+        .entries
+        .iter()
+        .filter(|entry| entry.scope != LintConfigScope::Crate)
+        .collect();
+
+    for (lint, (level, src)) in levels {
+        let name = lint.name_lower();
+        println!("{name}: {} ({})", level.as_str(), describe_source(sess, &src));
+        for entry in &scoped_entries {
+            let applies = lint_store
+                .find_lints(&entry.lint_name)
+                .map_or(false, |ids| ids.contains(&LintId::of(lint)));
+            if !applies {
+                continue;
+            }
+            let scope = match &entry.scope {
+                LintConfigScope::Crate => unreachable!(),
+                LintConfigScope::Module(path) => format!("`crate::{}`", path.join("::")),
+                LintConfigScope::File(pattern) => format!("`{pattern}`"),
+            };
+            println!(
+                "    {} in {scope} (lint configuration file, line {})",
+                entry.level.as_str(),
+                entry.line
+            );
+        }
+    }
+}
+
+fn describe_source(sess: &Session, src: &LintLevelSource) -> String {
+    match *src {
+        LintLevelSource::Default => "default".to_string(),
+        LintLevelSource::CommandLine(name, level) => {
+            let flag = match level {
+                Level::Allow => "-A",
+                Level::Warn => "-W",
+                Level::Deny => "-D",
+                Level::Forbid => "-F",
+                Level::ForceWarn(_) => "--force-warn",
+                Level::Expect(_) => {
+                    unreachable!("the expect level does not have a commandline flag")
+                }
+            };
+            format!("command line `{flag} {}`", name.as_str().replace('_', "-"))
+        }
+        LintLevelSource::ConfigFile { line, .. } => {
+            format!("lint configuration file, line {line}")
+        }
+        LintLevelSource::Node { span, .. } => {
+            format!("attribute at {}", sess.source_map().span_to_diagnostic_string(span))
+        }
+    }
+}
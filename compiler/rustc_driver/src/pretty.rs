@@ -1,8 +1,9 @@
 //! The various pretty-printing routines.
 
-use crate::session_diagnostics::UnprettyDumpFail;
+use crate::session_diagnostics::{UnprettyDumpFail, UnprettySmirJsonUnavailable};
 use rustc_ast as ast;
 use rustc_ast_pretty::pprust;
+use rustc_data_structures::AtomicRef;
 use rustc_errors::ErrorGuaranteed;
 use rustc_hir as hir;
 use rustc_hir_pretty as pprust_hir;
@@ -16,6 +17,7 @@
 
 use std::cell::Cell;
 use std::fmt::Write;
+use std::io;
 use std::path::Path;
 
 pub use self::PpMode::*;
@@ -358,6 +360,17 @@ fn get_source(input: &Input, sess: &Session) -> (String, FileName) {
     (src, src_name)
 }
 
+fn default_write_smir_json(tcx: TyCtxt<'_>, _: &mut dyn io::Write) -> io::Result<()> {
+    tcx.sess.emit_fatal(UnprettySmirJsonUnavailable)
+}
+
+pub type WriteSmirJson = for<'tcx> fn(TyCtxt<'tcx>, &mut dyn io::Write) -> io::Result<()>;
+
+/// Writes the stable MIR of the local crate for `-Zunpretty=smir-json`. `rustc_smir` depends on
+/// this crate, so it is set by the `rustc` binary, and drivers that don't set it reject the flag.
+pub static WRITE_SMIR_JSON: AtomicRef<WriteSmirJson> =
+    AtomicRef::new(&(default_write_smir_json as WriteSmirJson));
+
 fn write_or_print(out: &str, ofile: Option<&Path>, sess: &Session) {
     match ofile {
         None => print!("{}", out),
@@ -500,6 +513,12 @@ fn print_with_analysis(
             String::from_utf8(out).unwrap()
         }
 
+        SmirJson => {
+            let mut out = Vec::new();
+            (*WRITE_SMIR_JSON)(tcx, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        }
+
         ThirTree => {
             let mut out = String::new();
             abort_on_err(rustc_hir_analysis::check_crate(tcx), tcx.sess);
@@ -515,6 +534,17 @@ fn print_with_analysis(
             out
         }
 
+        ThirJson => {
+            abort_on_err(rustc_hir_analysis::check_crate(tcx), tcx.sess);
+            debug!("printing THIR as JSON");
+            let bodies: Vec<_> = tcx
+                .hir()
+                .body_owners()
+                .map(|did| tcx.thir_json(ty::WithOptConstParam::unknown(did)).as_str())
+                .collect();
+            format!("[\n{}\n]\n", bodies.join(",\n"))
+        }
+
         _ => unreachable!(),
     };
 
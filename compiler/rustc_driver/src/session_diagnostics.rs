@@ -38,3 +38,13 @@ pub(crate) struct UnprettyDumpFail {
     pub path: String,
     pub err: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(driver_unpretty_smir_json_unavailable)]
+pub(crate) struct UnprettySmirJsonUnavailable;
+
+#[derive(Diagnostic)]
+#[diag(driver_chrome_trace_write_fail)]
+pub(crate) struct ChromeTraceWriteFail {
+    pub err: std::io::Error,
+}
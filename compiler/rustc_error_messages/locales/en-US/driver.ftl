@@ -11,3 +11,7 @@ driver_rlink_rustc_version_mismatch = .rlink file was produced by rustc version
 driver_rlink_no_a_file = rlink must be a file
 
 driver_unpretty_dump_fail = pretty-print failed to write `{$path}` due to error `{$err}`
+
+driver_unpretty_smir_json_unavailable = `-Zunpretty=smir-json` is not supported by this driver
+
+driver_chrome_trace_write_fail = failed to write the self-profile Chrome trace: {$err}
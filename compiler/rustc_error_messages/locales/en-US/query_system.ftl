@@ -14,6 +14,16 @@ query_system_cycle_stack_single = ...which immediately requires {$stack_bottom}
 
 query_system_cycle_stack_middle = ...which requires {$desc}...
 
+query_system_cycle_stack_repeat = ...and the last {$len ->
+        [one] query repeats
+       *[other] {$len} queries repeat
+    } {$more} more {$more ->
+        [one] time
+       *[other] times
+    }...
+
+query_system_cycle_graph_write_failed = failed to write the cycle graph to `{$path}`: {$error}
+
 query_system_cycle_stack_multiple = ...which again requires {$stack_bottom}, completing the cycle
 
 query_system_cycle_recursive_ty_alias = type aliases cannot be recursive
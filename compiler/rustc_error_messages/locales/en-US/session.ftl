@@ -47,6 +47,17 @@ session_split_debuginfo_unstable_platform = `-Csplit-debuginfo={$debuginfo}` is
 
 session_file_is_not_writeable = output file {$file} is not writeable -- check its permissions
 
+session_apply_suggestions_failed = failed to apply suggestions to `{$path}`: {$err}
+
+session_conflicting_suggestions_skipped =
+    skipped {$count} machine-applicable {$count ->
+        [one] suggestion
+        *[other] suggestions
+    } that conflict with suggestions applied before them
+    .note = compile the fixed source files again to apply the remaining suggestions
+
+session_write_diagnostic_baseline_failed = failed to write the diagnostic baseline to `{$path}`: {$err}
+
 session_crate_name_does_not_match = `--crate-name` and `#[crate_name]` are required to match, but `{$s}` != `{$name}`
 
 session_crate_name_invalid = crate names cannot start with a `-`, but `{$s}` has a leading hyphen
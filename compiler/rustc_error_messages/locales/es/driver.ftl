@@ -0,0 +1,15 @@
+driver_rlink_unable_to_read = no se pudo leer el archivo rlink: `{$err}`
+
+driver_rlink_wrong_file_type = La entrada no parece ser un archivo .rlink
+
+driver_rlink_empty_version_number = La entrada no contiene un número de versión
+
+driver_rlink_encoding_version_mismatch = el archivo .rlink se generó con la versión de codificación `{$version_array}`, pero la versión actual es `{$rlink_version}`
+
+driver_rlink_rustc_version_mismatch = el archivo .rlink se generó con la versión de rustc `{$rustc_version}`, pero la versión actual es `{$current_version}`
+
+driver_rlink_no_a_file = rlink debe ser un archivo
+
+driver_unpretty_dump_fail = la impresión formateada no pudo escribir `{$path}` debido al error `{$err}`
+
+driver_chrome_trace_write_fail = no se pudo escribir la traza de Chrome del perfilado: {$err}
@@ -0,0 +1,55 @@
+parse_struct_literal_body_without_path =
+    cuerpo de literal de estructura sin ruta
+    .suggestion = quizás olvidó añadir el literal de estructura dentro del bloque
+
+parse_maybe_report_ambiguous_plus =
+    `+` ambiguo en un tipo
+    .suggestion = use paréntesis para eliminar la ambigüedad
+
+parse_maybe_recover_from_bad_type_plus =
+    se esperaba una ruta a la izquierda de `+`, no `{$ty}`
+
+parse_add_paren = intente añadir paréntesis
+
+parse_forgot_paren = ¿quizás olvidó los paréntesis?
+
+parse_expect_path = se esperaba una ruta
+
+parse_maybe_recover_from_bad_qpath_stage_2 =
+    faltan los corchetes angulares en la ruta del elemento asociado
+    .suggestion = pruebe: `{$ty}`
+
+parse_incorrect_semicolon =
+    se esperaba un elemento, se encontró `;`
+    .suggestion = elimine este punto y coma
+    .help = las declaraciones {$name} no van seguidas de un punto y coma
+
+parse_incorrect_use_of_await =
+    uso incorrecto de `await`
+    .parentheses_suggestion = `await` no es una llamada a un método, elimine los paréntesis
+    .postfix_suggestion = `await` es una operación postfija
+
+parse_in_in_typo =
+    se esperaba un iterable, se encontró la palabra clave `in`
+    .suggestion = elimine el `in` duplicado
+
+parse_invalid_variable_declaration =
+    declaración de variable no válida
+
+parse_switch_mut_let_order =
+    intercambie el orden de `mut` y `let`
+parse_missing_let_before_mut = falta una palabra clave
+parse_use_let_not_auto = escriba `let` en lugar de `auto` para introducir una nueva variable
+parse_use_let_not_var = escriba `let` en lugar de `var` para introducir una nueva variable
+
+parse_invalid_comparison_operator = operador de comparación `{$invalid}` no válido
+    .use_instead = `{$invalid}` no es un operador de comparación válido, use `{$correct}`
+    .spaceship_operator_invalid = `<=>` no es un operador de comparación válido, use `std::cmp::Ordering`
+
+parse_invalid_logical_operator = `{$incorrect}` no es un operador lógico
+    .note = a diferencia de, por ejemplo, Python y PHP, se usan `&&` y `||` como operadores lógicos
+    .use_amp_amp_for_conjunction = use `&&` para realizar una conjunción lógica
+    .use_pipe_pipe_for_disjunction = use `||` para realizar una disyunción lógica
+
+parse_tilde_is_not_unary_operator = `~` no puede usarse como operador unario
+    .suggestion = use `!` para realizar la negación bit a bit
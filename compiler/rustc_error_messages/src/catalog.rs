@@ -0,0 +1,222 @@
+//! The messages of the default "en-US" Fluent resources, for `--print diagnostic-catalog`,
+//! and how much of them a translation covers, for `--print translation-coverage`.
+
+use fluent_bundle::FluentResource;
+use fluent_syntax::ast::{
+    CallArguments, Entry, Expression, InlineExpression, Pattern, PatternElement, VariantKey,
+};
+use fluent_syntax::parser;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fluent_generated::{DEFAULT_LOCALE_RESOURCES, DEFAULT_LOCALE_RESOURCE_NAMES};
+use crate::{locale_resources, LanguageIdentifier, TranslationBundleError};
+
+/// A message of the default Fluent resources.
+pub struct CatalogMessage {
+    /// The Fluent identifier of the message, e.g. `borrowck_move_unsized`.
+    pub id: &'static str,
+    /// The crate whose resource defines the message, e.g. `borrowck`.
+    pub resource: &'static str,
+    /// The pattern of the message, in Fluent syntax, e.g. `cannot move a value of type {$ty}`.
+    pub value: Option<String>,
+    /// The attributes of the message, e.g. its `label` or `note`, and their patterns.
+    pub attributes: Vec<(&'static str, String)>,
+}
+
+/// Lists the messages of the default Fluent resources, in the order they are defined in.
+pub fn default_locale_catalog() -> Vec<CatalogMessage> {
+    let mut messages = vec![];
+    for (&source, &resource) in DEFAULT_LOCALE_RESOURCES.iter().zip(DEFAULT_LOCALE_RESOURCE_NAMES) {
+        // The resources were already checked by `fluent_messages!` when the compiler was built.
+        let ast = parser::parse(source).expect("failed to parse fallback fluent resource");
+        for entry in ast.body {
+            let Entry::Message(message) = entry else { continue };
+            messages.push(CatalogMessage {
+                id: message.id.name,
+                resource,
+                value: message.value.as_ref().map(pattern_to_string),
+                attributes: message
+                    .attributes
+                    .iter()
+                    .map(|attr| (attr.id.name, pattern_to_string(&attr.value)))
+                    .collect(),
+            });
+        }
+    }
+    messages
+}
+
+/// How much of the messages of the default Fluent resources a locale translates.
+pub struct TranslationCoverage {
+    /// For each default resource, its name, its number of messages, and how many of them are
+    /// fully translated, i.e. with their value and all of their attributes.
+    pub resources: Vec<(&'static str, usize, usize)>,
+    /// The untranslated messages and attributes, e.g. `parse_expect_path` or
+    /// `parse_incorrect_semicolon.help`, in the order they are defined in.
+    pub missing: Vec<String>,
+    /// The translated messages and attributes that are not in the default resources, most likely
+    /// because they were renamed or removed since they were translated. They are never used.
+    pub unknown: Vec<String>,
+}
+
+/// Compares the messages of `$sysroot/share/locale/$locale/*.ftl`, and of the
+/// `-Z translate-additional-ftl` resource if any, with those of the default resources.
+pub fn translation_coverage(
+    user_provided_sysroot: Option<PathBuf>,
+    sysroot_candidates: Vec<PathBuf>,
+    locale: &LanguageIdentifier,
+    additional_ftl_path: Option<&Path>,
+) -> Result<TranslationCoverage, TranslationBundleError> {
+    let mut resources = locale_resources(user_provided_sysroot, sysroot_candidates, locale)?;
+    if let Some(additional_ftl_path) = additional_ftl_path {
+        let resource_str =
+            fs::read_to_string(additional_ftl_path).map_err(TranslationBundleError::ReadFtl)?;
+        resources.push(FluentResource::try_new(resource_str)?);
+    }
+
+    // The translated messages, with whether they have a value and the names of their attributes.
+    let mut translated: FxHashMap<&str, (bool, FxHashSet<&str>)> = FxHashMap::default();
+    for resource in &resources {
+        for entry in resource.entries() {
+            let Entry::Message(message) = entry else { continue };
+            let (has_value, attributes) = translated.entry(message.id.name).or_default();
+            *has_value |= message.value.is_some();
+            attributes.extend(message.attributes.iter().map(|attr| attr.id.name));
+        }
+    }
+
+    let mut coverage = TranslationCoverage { resources: vec![], missing: vec![], unknown: vec![] };
+    let mut known: FxHashSet<&str> = FxHashSet::default();
+    for message in default_locale_catalog() {
+        if coverage.resources.last().map(|&(resource, ..)| resource) != Some(message.resource) {
+            coverage.resources.push((message.resource, 0, 0));
+        }
+        let (_, total, complete) = coverage.resources.last_mut().unwrap();
+        *total += 1;
+        known.insert(message.id);
+
+        let Some((has_value, attributes)) = translated.get_mut(message.id) else {
+            coverage.missing.push(message.id.to_string());
+            continue;
+        };
+        let missing_before = coverage.missing.len();
+        if message.value.is_some() && !*has_value {
+            coverage.missing.push(message.id.to_string());
+        }
+        for (attr, _) in &message.attributes {
+            if !attributes.remove(attr) {
+                coverage.missing.push(format!("{}.{attr}", message.id));
+            }
+        }
+        if coverage.missing.len() == missing_before {
+            *complete += 1;
+        }
+    }
+
+    for (id, (_, attributes)) in translated {
+        if !known.contains(id) {
+            coverage.unknown.push(id.to_string());
+        } else {
+            // Only the attributes that are not in the default resources are left.
+            coverage.unknown.extend(attributes.into_iter().map(|attr| format!("{id}.{attr}")));
+        }
+    }
+    coverage.unknown.sort_unstable();
+
+    Ok(coverage)
+}
+
+fn pattern_to_string(pattern: &Pattern<&str>) -> String {
+    let mut s = String::new();
+    write_pattern(&mut s, pattern);
+    s.trim_end().to_string()
+}
+
+fn write_pattern(s: &mut String, pattern: &Pattern<&str>) {
+    for element in &pattern.elements {
+        match element {
+            PatternElement::TextElement { value } => s.push_str(value),
+            PatternElement::Placeable { expression } => {
+                s.push('{');
+                write_expression(s, expression);
+                s.push('}');
+            }
+        }
+    }
+}
+
+fn write_expression(s: &mut String, expression: &Expression<&str>) {
+    match expression {
+        Expression::Inline(expression) => write_inline_expression(s, expression),
+        Expression::Select { selector, variants } => {
+            write_inline_expression(s, selector);
+            s.push_str(" ->");
+            for variant in variants {
+                let default = if variant.default { "*" } else { "" };
+                let key = match variant.key {
+                    VariantKey::Identifier { name } => name,
+                    VariantKey::NumberLiteral { value } => value,
+                };
+                write!(s, " {default}[{key}] ").unwrap();
+                write_pattern(s, &variant.value);
+            }
+            s.push(' ');
+        }
+    }
+}
+
+fn write_inline_expression(s: &mut String, expression: &InlineExpression<&str>) {
+    match expression {
+        InlineExpression::StringLiteral { value } => write!(s, "\"{value}\"").unwrap(),
+        InlineExpression::NumberLiteral { value } => s.push_str(value),
+        InlineExpression::FunctionReference { id, arguments } => {
+            s.push_str(id.name);
+            write_arguments(s, arguments);
+        }
+        InlineExpression::MessageReference { id, attribute } => {
+            s.push_str(id.name);
+            if let Some(attribute) = attribute {
+                write!(s, ".{}", attribute.name).unwrap();
+            }
+        }
+        InlineExpression::TermReference { id, attribute, arguments } => {
+            write!(s, "-{}", id.name).unwrap();
+            if let Some(attribute) = attribute {
+                write!(s, ".{}", attribute.name).unwrap();
+            }
+            if let Some(arguments) = arguments {
+                write_arguments(s, arguments);
+            }
+        }
+        InlineExpression::VariableReference { id } => write!(s, "${}", id.name).unwrap(),
+        InlineExpression::Placeable { expression } => {
+            s.push('{');
+            write_expression(s, expression);
+            s.push('}');
+        }
+    }
+}
+
+fn write_arguments(s: &mut String, arguments: &CallArguments<&str>) {
+    s.push('(');
+    let mut first = true;
+    for positional in &arguments.positional {
+        if !first {
+            s.push_str(", ");
+        }
+        first = false;
+        write_inline_expression(s, positional);
+    }
+    for named in &arguments.named {
+        if !first {
+            s.push_str(", ");
+        }
+        first = false;
+        write!(s, "{}: ", named.name.name).unwrap();
+        write_inline_expression(s, &named.value);
+    }
+    s.push(')');
+}
@@ -76,6 +76,11 @@
 
 pub use fluent_generated::{self as fluent, DEFAULT_LOCALE_RESOURCES};
 
+mod catalog;
+pub use catalog::{
+    default_locale_catalog, translation_coverage, CatalogMessage, TranslationCoverage,
+};
+
 pub type FluentBundle = fluent_bundle::bundle::FluentBundle<FluentResource, IntlLangMemoizer>;
 
 #[cfg(parallel_compiler)]
@@ -163,8 +168,8 @@ fn from(mut errs: Vec<FluentError>) -> Self {
 /// (overriding any conflicting messages).
 #[instrument(level = "trace")]
 pub fn fluent_bundle(
-    mut user_provided_sysroot: Option<PathBuf>,
-    mut sysroot_candidates: Vec<PathBuf>,
+    user_provided_sysroot: Option<PathBuf>,
+    sysroot_candidates: Vec<PathBuf>,
     requested_locale: Option<LanguageIdentifier>,
     additional_ftl_path: Option<&Path>,
     with_directionality_markers: bool,
@@ -191,43 +196,10 @@ pub fn fluent_bundle(
 
     // If the user requests the default locale then don't try to load anything.
     if !requested_fallback_locale && let Some(requested_locale) = requested_locale {
-        let mut found_resources = false;
-        for sysroot in user_provided_sysroot.iter_mut().chain(sysroot_candidates.iter_mut()) {
-            sysroot.push("share");
-            sysroot.push("locale");
-            sysroot.push(requested_locale.to_string());
-            trace!(?sysroot);
-
-            if !sysroot.exists() {
-                trace!("skipping");
-                continue;
-            }
-
-            if !sysroot.is_dir() {
-                return Err(TranslationBundleError::LocaleIsNotDir);
-            }
-
-            for entry in sysroot.read_dir().map_err(TranslationBundleError::ReadLocalesDir)? {
-                let entry = entry.map_err(TranslationBundleError::ReadLocalesDirEntry)?;
-                let path = entry.path();
-                trace!(?path);
-                if path.extension().and_then(|s| s.to_str()) != Some("ftl") {
-                    trace!("skipping");
-                    continue;
-                }
-
-                let resource_str =
-                    fs::read_to_string(path).map_err(TranslationBundleError::ReadFtl)?;
-                let resource =
-                    FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
-                trace!(?resource);
-                bundle.add_resource(resource).map_err(TranslationBundleError::from)?;
-                found_resources = true;
-            }
-        }
-
-        if !found_resources {
-            return Err(TranslationBundleError::MissingLocale);
+        let resources =
+            locale_resources(user_provided_sysroot, sysroot_candidates, &requested_locale)?;
+        for resource in resources {
+            bundle.add_resource(resource).map_err(TranslationBundleError::from)?;
         }
     }
 
@@ -244,6 +216,53 @@ pub fn fluent_bundle(
     Ok(Some(bundle))
 }
 
+/// Returns the resources of `$sysroot/share/locale/$locale/*.ftl`, from all the sysroots
+/// that have that locale.
+#[instrument(level = "trace")]
+pub fn locale_resources(
+    mut user_provided_sysroot: Option<PathBuf>,
+    mut sysroot_candidates: Vec<PathBuf>,
+    locale: &LanguageIdentifier,
+) -> Result<Vec<FluentResource>, TranslationBundleError> {
+    let mut resources = vec![];
+    for sysroot in user_provided_sysroot.iter_mut().chain(sysroot_candidates.iter_mut()) {
+        sysroot.push("share");
+        sysroot.push("locale");
+        sysroot.push(locale.to_string());
+        trace!(?sysroot);
+
+        if !sysroot.exists() {
+            trace!("skipping");
+            continue;
+        }
+
+        if !sysroot.is_dir() {
+            return Err(TranslationBundleError::LocaleIsNotDir);
+        }
+
+        for entry in sysroot.read_dir().map_err(TranslationBundleError::ReadLocalesDir)? {
+            let entry = entry.map_err(TranslationBundleError::ReadLocalesDirEntry)?;
+            let path = entry.path();
+            trace!(?path);
+            if path.extension().and_then(|s| s.to_str()) != Some("ftl") {
+                trace!("skipping");
+                continue;
+            }
+
+            let resource_str = fs::read_to_string(path).map_err(TranslationBundleError::ReadFtl)?;
+            let resource =
+                FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
+            trace!(?resource);
+            resources.push(resource);
+        }
+    }
+
+    if resources.is_empty() {
+        return Err(TranslationBundleError::MissingLocale);
+    }
+    Ok(resources)
+}
+
 /// Type alias for the result of `fallback_fluent_bundle` - a reference-counted pointer to a lazily
 /// evaluated fluent bundle.
 pub type LazyFallbackBundle = Lrc<Lazy<FluentBundle, impl FnOnce() -> FluentBundle>>;
@@ -0,0 +1,310 @@
+//! Applying machine-applicable suggestions to the source files, for `-Z apply-suggestions`.
+//!
+//! Suggestions are applied the way rustfix, which is used by `cargo fix`, applies them:
+//!
+//! * only suggestions that are [`Applicability::MachineApplicable`] and have a single
+//!   substitution are applied,
+//! * a suggestion is applied with all of its parts, or not at all,
+//! * suggestions are applied in the order their diagnostics were emitted, and a suggestion
+//!   that overlaps an edit of a suggestion applied before it is skipped, unless it makes
+//!   exactly the same edit.
+//!
+//! Suggestions are made against the source files as they were read by rustc, so a file that
+//! changed since then is not edited at all.
+
+use crate::{Applicability, CodeSuggestion};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, SourceFile};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// The number of unchanged lines shown around the changes of a unified diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// What `-Z apply-suggestions` does with the machine-applicable suggestions.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ApplySuggestions {
+    /// Edit the source files in place.
+    InPlace,
+    /// Print the edits as a unified diff, without touching the source files.
+    Diff,
+}
+
+/// Whether `suggestion` can be applied without asking the user anything.
+pub fn is_machine_applicable(suggestion: &CodeSuggestion) -> bool {
+    suggestion.applicability == Applicability::MachineApplicable
+        && suggestion.substitutions.len() == 1
+}
+
+/// The replacement of the bytes of `range` of a source file, as it is on disk, with `snippet`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Edit {
+    range: Range<usize>,
+    snippet: String,
+}
+
+impl Edit {
+    fn conflicts_with(&self, other: &Edit) -> bool {
+        // Two insertions at the same position conflict, as it is unclear which text goes first.
+        (self.range.start < other.range.end && other.range.start < self.range.end)
+            || self.range.start == other.range.start
+    }
+}
+
+/// The edits made to a source file by the applied suggestions.
+pub struct FileFixes {
+    pub path: PathBuf,
+    source_file: Lrc<SourceFile>,
+    /// Sorted by position, and without conflicts.
+    edits: Vec<Edit>,
+}
+
+/// The machine-applicable suggestions of a compilation session, applied to the source files.
+#[derive(Default)]
+pub struct SuggestionFixes {
+    pub files: Vec<FileFixes>,
+    /// The number of suggestions that were applied.
+    pub applied: usize,
+    /// The number of suggestions that were skipped, because they conflict with suggestions
+    /// that were applied before them.
+    pub conflicting: usize,
+}
+
+impl SuggestionFixes {
+    /// Applies `suggestions`, in order, skipping those that are not machine-applicable or that
+    /// can't be applied to a local source file.
+    pub fn new(sm: &SourceMap, suggestions: &[CodeSuggestion]) -> SuggestionFixes {
+        let mut fixes = SuggestionFixes::default();
+        let mut files: FxIndexMap<PathBuf, FileFixes> = FxIndexMap::default();
+
+        'suggestions: for suggestion in suggestions {
+            if !is_machine_applicable(suggestion) {
+                continue;
+            }
+
+            let mut new_edits: Vec<(PathBuf, Edit)> = vec![];
+            for part in &suggestion.substitutions[0].parts {
+                let Some((source_file, path, edit)) = resolve_edit(sm, part.span, &part.snippet)
+                else {
+                    continue 'suggestions;
+                };
+                let file = files.entry(path.clone()).or_insert_with(|| FileFixes {
+                    path: path.clone(),
+                    source_file,
+                    edits: vec![],
+                });
+                if file.edits.contains(&edit)
+                    || new_edits.iter().any(|(p, e)| *p == path && *e == edit)
+                {
+                    // The same edit was already made by another suggestion.
+                    continue;
+                }
+                if file.edits.iter().any(|e| e.conflicts_with(&edit))
+                    || new_edits.iter().any(|(p, e)| *p == path && e.conflicts_with(&edit))
+                {
+                    fixes.conflicting += 1;
+                    continue 'suggestions;
+                }
+                new_edits.push((path, edit));
+            }
+
+            if new_edits.is_empty() {
+                continue;
+            }
+            for (path, edit) in new_edits {
+                files[&path].edits.push(edit);
+            }
+            fixes.applied += 1;
+        }
+
+        fixes.files = files
+            .into_values()
+            .filter(|file| !file.edits.is_empty())
+            .map(|mut file| {
+                file.edits.sort_by_key(|edit| edit.range.start);
+                file
+            })
+            .collect();
+        fixes
+    }
+}
+
+/// Finds the local file that `span` points into, and the range of `span` in the file as it is
+/// on disk, i.e. before rustc removed its byte order mark and normalized its newlines.
+fn resolve_edit(
+    sm: &SourceMap,
+    span: rustc_span::Span,
+    snippet: &str,
+) -> Option<(Lrc<SourceFile>, PathBuf, Edit)> {
+    if span.is_dummy() {
+        return None;
+    }
+    let lo = sm.lookup_byte_offset(span.lo());
+    let hi = sm.lookup_byte_offset(span.hi());
+    if !Lrc::ptr_eq(&lo.sf, &hi.sf) || lo.sf.src.is_none() {
+        return None;
+    }
+    let FileName::Real(ref name) = lo.sf.name else { return None };
+    let path = name.local_path()?.to_path_buf();
+
+    let start = lo.sf.original_relative_byte_pos(span.lo()).to_usize();
+    let end = lo.sf.original_relative_byte_pos(span.hi()).to_usize();
+    Some((lo.sf, path, Edit { range: start..end, snippet: snippet.to_string() }))
+}
+
+impl FileFixes {
+    /// Reads the file, making sure it has not changed since rustc read it.
+    pub fn read_original(&self) -> io::Result<String> {
+        let original = fs::read_to_string(&self.path)?;
+        if !self.source_file.src_hash.matches(&original) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "the file was modified during the compilation",
+            ));
+        }
+        Ok(original)
+    }
+
+    /// Returns `original` with the edits applied.
+    pub fn fixed_source(&self, original: &str) -> String {
+        apply_edits(original, 0, &self.edits)
+    }
+
+    /// Returns the edits as a unified diff of `original`, which `patch -p1` and `git apply`
+    /// can apply.
+    pub fn unified_diff(&self, original: &str) -> String {
+        unified_diff(&self.path, original, &self.edits)
+    }
+}
+
+/// Applies `edits` to `text`, which starts at byte `offset` of the file the edits are for.
+fn apply_edits(text: &str, offset: usize, edits: &[Edit]) -> String {
+    let mut fixed = String::with_capacity(text.len());
+    let mut pos = 0;
+    for edit in edits {
+        fixed.push_str(&text[pos..edit.range.start - offset]);
+        fixed.push_str(&edit.snippet);
+        pos = edit.range.end - offset;
+    }
+    fixed.push_str(&text[pos..]);
+    fixed
+}
+
+/// A group of edits that touch the same lines, and the lines they touch.
+struct ChangedLines<'a> {
+    lines: Range<usize>,
+    edits: &'a [Edit],
+}
+
+fn unified_diff(path: &Path, original: &str, edits: &[Edit]) -> String {
+    let mut line_starts = vec![0];
+    line_starts
+        .extend(original.match_indices('\n').map(|(i, _)| i + 1).filter(|&i| i < original.len()));
+    let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos) - 1;
+    let line_range = |lines: &Range<usize>| {
+        line_starts[lines.start]..line_starts.get(lines.end).copied().unwrap_or(original.len())
+    };
+
+    // An edit touches the lines from the one it starts on to the one it ends on, so an edit that
+    // removes a newline touches the line after it too, as that line is joined with its own.
+    let mut changes: Vec<ChangedLines<'_>> = vec![];
+    let mut first_edit = 0;
+    for (i, edit) in edits.iter().enumerate() {
+        let lines = line_of(edit.range.start)..line_of(edit.range.end) + 1;
+        match changes.last_mut() {
+            Some(last) if lines.start < last.lines.end => {
+                last.lines.end = last.lines.end.max(lines.end);
+                last.edits = &edits[first_edit..=i];
+            }
+            _ => {
+                first_edit = i;
+                changes.push(ChangedLines { lines, edits: &edits[i..=i] });
+            }
+        }
+    }
+
+    let display_path = path.display();
+    let (old_prefix, new_prefix) = if path.is_relative() { ("a/", "b/") } else { ("", "") };
+    let mut diff = format!("--- {old_prefix}{display_path}\n+++ {new_prefix}{display_path}\n");
+
+    // The number of lines added by the previous hunks, minus the number of lines they removed.
+    let mut line_delta: isize = 0;
+    let mut i = 0;
+    while i < changes.len() {
+        // A hunk has all the changes that are close enough for their context lines to overlap.
+        let mut j = i + 1;
+        while j < changes.len()
+            && changes[j].lines.start - changes[j - 1].lines.end <= 2 * DIFF_CONTEXT_LINES
+        {
+            j += 1;
+        }
+        let hunk_changes = &changes[i..j];
+        i = j;
+
+        let start = hunk_changes[0].lines.start.saturating_sub(DIFF_CONTEXT_LINES);
+        let end =
+            (hunk_changes.last().unwrap().lines.end + DIFF_CONTEXT_LINES).min(line_starts.len());
+
+        let mut body = String::new();
+        let mut old_len = 0;
+        let mut new_len = 0;
+        let mut pos = start;
+        for change in hunk_changes {
+            for line in original[line_range(&(pos..change.lines.start))].split_inclusive('\n') {
+                push_diff_line(&mut body, ' ', line);
+            }
+            old_len += change.lines.start - pos;
+            new_len += change.lines.start - pos;
+            let range = line_range(&change.lines);
+            let old = &original[range.clone()];
+            let new = apply_edits(old, range.start, change.edits);
+            for line in old.split_inclusive('\n') {
+                push_diff_line(&mut body, '-', line);
+            }
+            for line in new.split_inclusive('\n') {
+                push_diff_line(&mut body, '+', line);
+            }
+            old_len += change.lines.len();
+            new_len += new.split_inclusive('\n').count();
+            pos = change.lines.end;
+        }
+        for line in original[line_range(&(pos..end))].split_inclusive('\n') {
+            push_diff_line(&mut body, ' ', line);
+        }
+        old_len += end - pos;
+        new_len += end - pos;
+
+        let new_start = (start as isize + line_delta) as usize;
+        line_delta += new_len as isize - old_len as isize;
+        writeln!(diff, "@@ -{} +{} @@", hunk_range(start, old_len), hunk_range(new_start, new_len))
+            .unwrap();
+        diff.push_str(&body);
+    }
+    diff
+}
+
+/// The range of lines of a hunk, as `<first line>,<number of lines>`, where lines are counted
+/// from 1, and where an empty range is given by the line before it.
+fn hunk_range(start: usize, len: usize) -> String {
+    if len == 0 {
+        format!("{start},0")
+    } else {
+        format!("{},{len}", start + 1)
+    }
+}
+
+fn push_diff_line(diff: &mut String, prefix: char, line: &str) {
+    diff.push(prefix);
+    diff.push_str(line);
+    if !line.ends_with('\n') {
+        diff.push_str("\n\\ No newline at end of file\n");
+    }
+}
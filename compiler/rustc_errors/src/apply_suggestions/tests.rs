@@ -0,0 +1,117 @@
+use super::*;
+
+use crate::{DiagnosticMessage, Substitution, SubstitutionPart, SuggestionStyle};
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, Span};
+
+const SOURCE: &str = "fn main() {\n    let x = 1;\n    let y = 2;\n}\n";
+
+fn suggestion(parts: &[(u32, u32, &str)], applicability: Applicability) -> CodeSuggestion {
+    CodeSuggestion {
+        substitutions: vec![Substitution {
+            parts: parts
+                .iter()
+                .map(|&(lo, hi, snippet)| SubstitutionPart {
+                    span: Span::with_root_ctxt(BytePos(lo), BytePos(hi)),
+                    snippet: snippet.to_string(),
+                })
+                .collect(),
+        }],
+        msg: DiagnosticMessage::Str("test".to_string()),
+        style: SuggestionStyle::ShowCode,
+        applicability,
+    }
+}
+
+fn fix(suggestions: &[CodeSuggestion]) -> (SuggestionFixes, Option<String>) {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = SourceMap::new(FilePathMapping::empty());
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), SOURCE.to_owned());
+        let fixes = SuggestionFixes::new(&sm, suggestions);
+        let fixed = fixes.files.first().map(|file| file.fixed_source(SOURCE));
+        (fixes, fixed)
+    })
+}
+
+#[test]
+fn applies_machine_applicable_suggestions() {
+    let (fixes, fixed) = fix(&[
+        suggestion(&[(20, 21, "_x")], Applicability::MachineApplicable),
+        suggestion(&[(35, 36, "_y")], Applicability::MaybeIncorrect),
+    ]);
+    assert_eq!(fixes.applied, 1);
+    assert_eq!(fixes.conflicting, 0);
+    assert_eq!(fixed.unwrap(), "fn main() {\n    let _x = 1;\n    let y = 2;\n}\n");
+}
+
+#[test]
+fn skips_conflicting_suggestions() {
+    let (fixes, fixed) = fix(&[
+        suggestion(&[(20, 21, "_x")], Applicability::MachineApplicable),
+        // The same edit is not a conflict.
+        suggestion(&[(20, 21, "_x"), (35, 36, "_y")], Applicability::MachineApplicable),
+        // An overlapping edit is, and none of the parts of its suggestion are applied.
+        suggestion(&[(42, 42, "// end\n"), (20, 24, "z")], Applicability::MachineApplicable),
+        // As is an insertion at the position of another edit.
+        suggestion(&[(35, 35, "mut ")], Applicability::MachineApplicable),
+    ]);
+    assert_eq!(fixes.applied, 2);
+    assert_eq!(fixes.conflicting, 2);
+    assert_eq!(fixed.unwrap(), "fn main() {\n    let _x = 1;\n    let _y = 2;\n}\n");
+}
+
+#[test]
+fn unified_diff_hunks() {
+    let original = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+    let line = |i: usize| original.match_indices("line ").nth(i - 1).unwrap().0;
+    let edits = [
+        Edit { range: line(2)..line(2) + 6, snippet: "LINE 2".to_string() },
+        // Close enough to the first change to be in the same hunk.
+        Edit { range: line(8)..line(9), snippet: String::new() },
+        Edit { range: line(18)..line(18), snippet: "new line\n".to_string() },
+    ];
+    assert_eq!(
+        unified_diff(Path::new("src/lib.rs"), &original, &edits),
+        "--- a/src/lib.rs\n\
+         +++ b/src/lib.rs\n\
+         @@ -1,12 +1,11 @@\n \
+         line 1\n\
+         -line 2\n\
+         +LINE 2\n \
+         line 3\n \
+         line 4\n \
+         line 5\n \
+         line 6\n \
+         line 7\n\
+         -line 8\n\
+         -line 9\n\
+         +line 9\n \
+         line 10\n \
+         line 11\n \
+         line 12\n\
+         @@ -15,6 +14,7 @@\n \
+         line 15\n \
+         line 16\n \
+         line 17\n\
+         -line 18\n\
+         +new line\n\
+         +line 18\n \
+         line 19\n \
+         line 20\n"
+    );
+}
+
+#[test]
+fn unified_diff_without_trailing_newline() {
+    let edits = [Edit { range: 4..5, snippet: "y".to_string() }];
+    assert_eq!(
+        unified_diff(Path::new("/tmp/a.rs"), "let x", &edits),
+        "--- /tmp/a.rs\n\
+         +++ /tmp/a.rs\n\
+         @@ -1,1 +1,1 @@\n\
+         -let x\n\
+         \\ No newline at end of file\n\
+         +let y\n\
+         \\ No newline at end of file\n"
+    );
+}
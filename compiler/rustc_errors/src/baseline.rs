@@ -0,0 +1,156 @@
+//! Diagnostic baselines, for `-Z diagnostic-baseline` and `-Z write-diagnostic-baseline`.
+//!
+//! A baseline lists the lint diagnostics that a crate is known to have, so that they can be
+//! suppressed without adding `#[allow]` attributes all over its source. Each entry of a baseline
+//! is a line of the form
+//!
+//! ```text
+//! <lint name> <fingerprint> <file>
+//! ```
+//!
+//! where the fingerprint is a hash of the source lines that the primary span of the diagnostic
+//! covers, with whitespace normalized, and of the text of the span. It does not depend on line
+//! numbers, so an entry still matches after code was added or removed above it, but it no
+//! longer matches once the offending code itself changes. A diagnostic that is reported several
+//! times for the same code has an entry for every occurrence.
+//!
+//! Diagnostics are matched when they are emitted rather than when their lint level is computed,
+//! so that the diagnostics replayed from the incremental cache are matched too.
+
+use crate::{Diagnostic, DiagnosticId, Level};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_span::source_map::SourceMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+struct BaselineEntry {
+    file: String,
+    lint: String,
+    fingerprint: String,
+}
+
+/// The known diagnostics of `-Z diagnostic-baseline`, and the diagnostics emitted so far, for
+/// `-Z write-diagnostic-baseline`.
+#[derive(Default, Debug)]
+pub struct DiagnosticBaseline {
+    /// The number of occurrences of every known diagnostic that are still to be suppressed.
+    known: FxHashMap<BaselineEntry, usize>,
+    /// The lint diagnostics emitted so far, including the suppressed ones.
+    seen: Vec<BaselineEntry>,
+}
+
+impl DiagnosticBaseline {
+    /// Reads the baseline at `path`.
+    pub fn load(path: &Path) -> io::Result<DiagnosticBaseline> {
+        DiagnosticBaseline::parse(&fs::read_to_string(path)?)
+    }
+
+    fn parse(text: &str) -> io::Result<DiagnosticBaseline> {
+        let mut baseline = DiagnosticBaseline::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(3, ' ');
+            let (Some(lint), Some(fingerprint), Some(file)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: expected `<lint name> <fingerprint> <file>`", i + 1),
+                ));
+            };
+            let entry = BaselineEntry {
+                file: file.to_string(),
+                lint: lint.to_string(),
+                fingerprint: fingerprint.to_string(),
+            };
+            *baseline.known.entry(entry).or_insert(0) += 1;
+        }
+        Ok(baseline)
+    }
+
+    /// Records `diagnostic`, and returns whether it is a known diagnostic that should be
+    /// suppressed. Only lint diagnostics whose level comes from the lint level machinery are
+    /// considered, so `--force-warn` lints are always reported.
+    pub(crate) fn suppresses(&mut self, diagnostic: &Diagnostic, sm: Option<&SourceMap>) -> bool {
+        let Some(DiagnosticId::Lint { name, is_force_warn: false, .. }) = &diagnostic.code else {
+            return false;
+        };
+        if !matches!(diagnostic.level, Level::Warning(_) | Level::Error { lint: true }) {
+            return false;
+        }
+
+        let (file, fingerprint) = fingerprint(diagnostic, sm);
+        let entry = BaselineEntry { file, lint: name.clone(), fingerprint };
+        let suppressed = match self.known.get_mut(&entry) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        };
+        self.seen.push(entry);
+        suppressed
+    }
+
+    /// Writes the diagnostics emitted so far to `path`, as a baseline.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    fn to_text(&self) -> String {
+        let mut entries = self.seen.clone();
+        entries.sort();
+        let mut text = String::from("# <lint name> <fingerprint> <file>\n");
+        for BaselineEntry { file, lint, fingerprint } in entries {
+            writeln!(text, "{lint} {fingerprint} {file}").unwrap();
+        }
+        text
+    }
+}
+
+/// Returns the file of the primary span of `diagnostic`, and the fingerprint of the code it
+/// points to. Diagnostics without a span all have the fingerprint of empty code.
+fn fingerprint(diagnostic: &Diagnostic, sm: Option<&SourceMap>) -> (String, String) {
+    let mut file = String::new();
+    let mut lines = String::new();
+    let mut snippet = String::new();
+    if let Some(sm) = sm
+        && let Some(span) = diagnostic.span.primary_span()
+        && !span.is_dummy()
+    {
+        let span = span.source_callsite();
+        if let Ok(span_lines) = sm.span_to_lines(span) {
+            file = span_lines.file.name.prefer_remapped().to_string();
+            for line in &span_lines.lines {
+                if let Some(text) = span_lines.file.get_line(line.line_index) {
+                    lines.push_str(&normalize_whitespace(&text));
+                    lines.push('\n');
+                }
+            }
+        }
+        if let Ok(text) = sm.span_to_snippet(span) {
+            snippet = normalize_whitespace(&text);
+        }
+    }
+
+    let mut hasher = StableHasher::new();
+    lines.hash(&mut hasher);
+    snippet.hash(&mut hasher);
+    let hash: u64 = hasher.finish();
+    (file, format!("{hash:016x}"))
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, Span};
+
+const SOURCE: &str = "fn main() {\n    let x = 1;\n    let y = 2; let z = 3;\n}\n";
+
+fn lint(name: &str, lo: u32, hi: u32) -> Diagnostic {
+    let code = DiagnosticId::Lint {
+        name: name.to_string(),
+        has_future_breakage: false,
+        is_force_warn: false,
+    };
+    let mut diag = Diagnostic::new_with_code(Level::Warning(None), Some(code), "test");
+    diag.set_span(Span::with_root_ctxt(BytePos(lo), BytePos(hi)));
+    diag
+}
+
+/// Runs `f` with a source map that has `source` as its only file.
+fn with_source<R>(source: &str, f: impl FnOnce(&SourceMap) -> R) -> R {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = SourceMap::new(FilePathMapping::empty());
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), source.to_owned());
+        f(&sm)
+    })
+}
+
+#[test]
+fn fingerprints_ignore_line_numbers_and_whitespace() {
+    let first = with_source(SOURCE, |sm| fingerprint(&lint("unused_variables", 20, 21), Some(sm)));
+    let moved = with_source(&format!("\n\n{}", SOURCE.replace("let x", "let  x")), |sm| {
+        fingerprint(&lint("unused_variables", 23, 24), Some(sm))
+    });
+    assert_eq!(first.0, "test.rs");
+    assert_eq!(first, moved);
+
+    // Diagnostics on the same line are told apart by the code they point to.
+    let (y, z) = with_source(SOURCE, |sm| {
+        (
+            fingerprint(&lint("unused_variables", 35, 36), Some(sm)),
+            fingerprint(&lint("unused_variables", 46, 47), Some(sm)),
+        )
+    });
+    assert_ne!(y, z);
+}
+
+#[test]
+fn suppresses_known_diagnostics_once_per_entry() {
+    with_source(SOURCE, |sm| {
+        let mut writer = DiagnosticBaseline::default();
+        assert!(!writer.suppresses(&lint("unused_variables", 20, 21), Some(sm)));
+        assert!(!writer.suppresses(&lint("unused_variables", 20, 21), Some(sm)));
+        let text = writer.to_text();
+
+        let mut baseline = DiagnosticBaseline::parse(&text).unwrap();
+        assert!(baseline.suppresses(&lint("unused_variables", 20, 21), Some(sm)));
+        assert!(baseline.suppresses(&lint("unused_variables", 20, 21), Some(sm)));
+        // A third occurrence is new, as is another lint for the same code.
+        assert!(!baseline.suppresses(&lint("unused_variables", 20, 21), Some(sm)));
+        assert!(!baseline.suppresses(&lint("dead_code", 20, 21), Some(sm)));
+        // Everything that was emitted is written, suppressed or not.
+        assert_eq!(baseline.seen.len(), 4);
+    });
+}
+
+#[test]
+fn ignores_non_lint_diagnostics() {
+    with_source(SOURCE, |sm| {
+        let mut writer = DiagnosticBaseline::default();
+        writer.suppresses(&lint("unused_variables", 20, 21), Some(sm));
+        let mut baseline = DiagnosticBaseline::parse(&writer.to_text()).unwrap();
+
+        // Same span as the known entry, but not a lint whose level may be lowered.
+        let span = Span::with_root_ctxt(BytePos(20), BytePos(21));
+        let mut warning = Diagnostic::new(Level::Warning(None), "test");
+        warning.set_span(span);
+        let code = DiagnosticId::Error("E0308".to_string());
+        let mut error = Diagnostic::new_with_code(Level::Error { lint: false }, Some(code), "test");
+        error.set_span(span);
+        let mut force_warn = lint("unused_variables", 20, 21);
+        if let Some(DiagnosticId::Lint { is_force_warn, .. }) = &mut force_warn.code {
+            *is_force_warn = true;
+        }
+
+        for diag in [&warning, &error, &force_warn] {
+            assert!(!baseline.suppresses(diag, Some(sm)));
+        }
+        assert!(baseline.seen.is_empty());
+        // The known entry is still there for the lint itself.
+        assert!(baseline.suppresses(&lint("unused_variables", 20, 21), Some(sm)));
+    });
+}
+
+#[test]
+fn rejects_malformed_entries() {
+    let baseline = DiagnosticBaseline::parse("# comment\n\nunused_variables 0123 src/a b.rs\n");
+    assert_eq!(baseline.unwrap().known.values().sum::<usize>(), 1);
+
+    let err = DiagnosticBaseline::parse("\nunused_variables src/a.rs\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 2: expected `<lint name> <fingerprint> <file>`");
+}
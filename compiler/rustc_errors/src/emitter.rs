@@ -12,6 +12,7 @@
 use rustc_span::source_map::SourceMap;
 use rustc_span::{FileLines, SourceFile, Span};
 
+use crate::html::{HtmlBlockWriter, HtmlBlocks};
 use crate::snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, Style, StyledString};
 use crate::styled_buffer::StyledBuffer;
 use crate::translation::{to_fluent_args, Translate};
@@ -713,6 +714,33 @@ pub fn new(
         }
     }
 
+    /// Creates an emitter that adds the rendered messages to `blocks`, as HTML, for
+    /// `--error-format=html`. Lines are only trimmed to fit `diagnostic_width` if it is given,
+    /// as the width of the terminal has nothing to do with the page.
+    pub(crate) fn html(
+        blocks: HtmlBlocks,
+        source_map: Option<Lrc<SourceMap>>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+        teach: bool,
+        diagnostic_width: Option<usize>,
+        macro_backtrace: bool,
+        track_diagnostics: bool,
+    ) -> EmitterWriter {
+        EmitterWriter {
+            dst: Html(blocks),
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            short_message: false,
+            teach,
+            ui_testing: false,
+            diagnostic_width: Some(diagnostic_width.unwrap_or(DEFAULT_COLUMN_WIDTH)),
+            macro_backtrace,
+            track_diagnostics,
+        }
+    }
+
     pub fn ui_testing(mut self, ui_testing: bool) -> Self {
         self.ui_testing = ui_testing;
         self
@@ -2536,6 +2564,8 @@ pub enum Destination {
     Buffered(BufferWriter),
     // The bool denotes whether we should be emitting ansi color codes or not
     Raw(Box<(dyn Write + Send)>, bool),
+    /// Each rendered message is added to the blocks of an `HtmlEmitter`, as HTML.
+    Html(HtmlBlocks),
 }
 
 pub enum WritableDst<'a> {
@@ -2543,6 +2573,7 @@ pub enum WritableDst<'a> {
     Buffered(&'a mut BufferWriter, Buffer),
     Raw(&'a mut (dyn Write + Send)),
     ColoredRaw(Ansi<&'a mut (dyn Write + Send)>),
+    Html(HtmlBlockWriter<'a>),
 }
 
 impl Destination {
@@ -2570,6 +2601,7 @@ fn writable(&mut self) -> WritableDst<'_> {
             }
             Destination::Raw(ref mut t, false) => WritableDst::Raw(t),
             Destination::Raw(ref mut t, true) => WritableDst::ColoredRaw(Ansi::new(t)),
+            Destination::Html(ref blocks) => WritableDst::Html(HtmlBlockWriter::new(blocks)),
         }
     }
 
@@ -2578,6 +2610,7 @@ fn supports_color(&self) -> bool {
             Self::Terminal(ref stream) => stream.supports_color(),
             Self::Buffered(ref buffer) => buffer.buffer().supports_color(),
             Self::Raw(_, supports_color) => supports_color,
+            Self::Html(_) => true,
         }
     }
 }
@@ -2638,6 +2671,7 @@ fn set_color(&mut self, color: &ColorSpec) -> io::Result<()> {
             WritableDst::Terminal(ref mut t) => t.set_color(color),
             WritableDst::Buffered(_, ref mut t) => t.set_color(color),
             WritableDst::ColoredRaw(ref mut t) => t.set_color(color),
+            WritableDst::Html(ref mut t) => t.set_color(color),
             WritableDst::Raw(_) => Ok(()),
         }
     }
@@ -2647,6 +2681,7 @@ fn reset(&mut self) -> io::Result<()> {
             WritableDst::Terminal(ref mut t) => t.reset(),
             WritableDst::Buffered(_, ref mut t) => t.reset(),
             WritableDst::ColoredRaw(ref mut t) => t.reset(),
+            WritableDst::Html(ref mut t) => t.reset(),
             WritableDst::Raw(_) => Ok(()),
         }
     }
@@ -2659,6 +2694,7 @@ fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
             WritableDst::Buffered(_, ref mut buf) => buf.write(bytes),
             WritableDst::Raw(ref mut w) => w.write(bytes),
             WritableDst::ColoredRaw(ref mut t) => t.write(bytes),
+            WritableDst::Html(ref mut t) => t.write(bytes),
         }
     }
 
@@ -2668,6 +2704,7 @@ fn flush(&mut self) -> io::Result<()> {
             WritableDst::Buffered(_, ref mut buf) => buf.flush(),
             WritableDst::Raw(ref mut w) => w.flush(),
             WritableDst::ColoredRaw(ref mut w) => w.flush(),
+            WritableDst::Html(ref mut w) => w.flush(),
         }
     }
 }
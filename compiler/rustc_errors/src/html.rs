@@ -0,0 +1,297 @@
+//! An emitter for `--error-format=html`, which writes the diagnostics of the compilation session
+//! as a self-contained HTML page, e.g. for the review pages of a CI system.
+//!
+//! Diagnostics are rendered by an [`EmitterWriter`], so they have the same snippets and colors
+//! as on a terminal, except that the styled text is written as `<span>` elements with CSS
+//! classes instead of ANSI escape sequences. Each diagnostic is a `<section>` with an anchor
+//! that links to it, and its notes, help messages and suggestions are collapsed in a
+//! `<details>` element below its main message.
+//!
+//! As the page is a single document, it is written when the emitter is dropped.
+
+use crate::emitter::{Emitter, EmitterWriter};
+use crate::translation::Translate;
+use crate::{Diagnostic, FluentBundle, LazyFallbackBundle, Level};
+use rustc_lint_defs::pluralize;
+
+use rustc_data_structures::sync::Lrc;
+use rustc_span::source_map::SourceMap;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::mem;
+use std::sync::{Arc, Mutex};
+use termcolor::{Color, ColorSpec, WriteColor};
+
+#[cfg(test)]
+mod tests;
+
+/// The messages rendered by the [`EmitterWriter`] of an [`HtmlEmitter`], as HTML, in the order
+/// they were rendered. For every diagnostic, these are its main message, then a separator line
+/// if it has sub-messages, then each of its sub-messages, then a blank line.
+#[derive(Clone, Default)]
+pub struct HtmlBlocks(Arc<Mutex<Vec<String>>>);
+
+impl HtmlBlocks {
+    fn take(&self) -> Vec<String> {
+        mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// Writes a rendered message as HTML, and adds it to the blocks when dropped.
+pub struct HtmlBlockWriter<'a> {
+    blocks: &'a HtmlBlocks,
+    html: String,
+    /// Whether a `<span>` was opened for the current style.
+    in_span: bool,
+}
+
+impl<'a> HtmlBlockWriter<'a> {
+    pub(crate) fn new(blocks: &'a HtmlBlocks) -> HtmlBlockWriter<'a> {
+        HtmlBlockWriter { blocks, html: String::new(), in_span: false }
+    }
+}
+
+impl Write for HtmlBlockWriter<'_> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        escape_html(&mut self.html, &String::from_utf8_lossy(bytes));
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl WriteColor for HtmlBlockWriter<'_> {
+    fn supports_color(&self) -> bool {
+        true
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        self.reset()?;
+        let classes = css_classes(spec);
+        if !classes.is_empty() {
+            write!(self.html, "<span class=\"{}\">", classes.join(" ")).unwrap();
+            self.in_span = true;
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        if mem::take(&mut self.in_span) {
+            self.html.push_str("</span>");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for HtmlBlockWriter<'_> {
+    fn drop(&mut self) {
+        let _ = self.reset();
+        self.blocks.0.lock().unwrap().push(mem::take(&mut self.html));
+    }
+}
+
+/// The CSS classes of the text of a terminal styled by `spec`, which the style sheet of the
+/// page gives the colors that terminals usually use.
+fn css_classes(spec: &ColorSpec) -> Vec<&'static str> {
+    let mut classes = vec![];
+    let fg = match spec.fg() {
+        Some(Color::Black) => Some("fg-black"),
+        Some(Color::Blue) => Some("fg-blue"),
+        Some(Color::Green) => Some("fg-green"),
+        Some(Color::Red) => Some("fg-red"),
+        Some(Color::Cyan) => Some("fg-cyan"),
+        Some(Color::Magenta) => Some("fg-magenta"),
+        Some(Color::Yellow) => Some("fg-yellow"),
+        Some(Color::White) => Some("fg-white"),
+        // Only the eight basic colors are used by the emitter.
+        _ => None,
+    };
+    classes.extend(fg);
+    if fg.is_some() && spec.intense() {
+        classes.push("intense");
+    }
+    if spec.bold() {
+        classes.push("bold");
+    }
+    if spec.underline() {
+        classes.push("underline");
+    }
+    classes
+}
+
+fn escape_html(html: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            _ => html.push(c),
+        }
+    }
+}
+
+const STYLE_SHEET: &str = "\
+body { background: #1e1e1e; color: #d4d4d4; font-family: monospace; margin: 2em; }
+pre { margin: 0; }
+.diagnostic { border-left: 3px solid #808080; margin: 1em 0; padding: 0.25em 1em; }
+.diagnostic.error { border-color: #f14c4c; }
+.diagnostic.warning { border-color: #e5e510; }
+.diagnostic.note { border-color: #23d18b; }
+.anchor { color: #808080; float: right; text-decoration: none; }
+.anchor:hover { color: #d4d4d4; }
+summary { color: #808080; cursor: pointer; }
+.bold { font-weight: bold; }
+.underline { text-decoration: underline; }
+.fg-black { color: #000000; } .fg-black.intense { color: #666666; }
+.fg-red { color: #cd3131; } .fg-red.intense { color: #f14c4c; }
+.fg-green { color: #0dbc79; } .fg-green.intense { color: #23d18b; }
+.fg-yellow { color: #e5e510; } .fg-yellow.intense { color: #f5f543; }
+.fg-blue { color: #2472c8; } .fg-blue.intense { color: #3b8eea; }
+.fg-magenta { color: #bc3fbc; } .fg-magenta.intense { color: #d670d6; }
+.fg-cyan { color: #11a8cd; } .fg-cyan.intense { color: #29b8db; }
+.fg-white { color: #e5e5e5; } .fg-white.intense { color: #ffffff; }
+";
+
+pub struct HtmlEmitter {
+    dst: Box<dyn Write + Send>,
+    blocks: HtmlBlocks,
+    writer: EmitterWriter,
+    /// The diagnostics emitted so far, as `<section>` elements.
+    sections: Vec<String>,
+}
+
+impl HtmlEmitter {
+    pub fn stderr(
+        source_map: Option<Lrc<SourceMap>>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+        teach: bool,
+        diagnostic_width: Option<usize>,
+        macro_backtrace: bool,
+        track_diagnostics: bool,
+    ) -> HtmlEmitter {
+        HtmlEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+            teach,
+            diagnostic_width,
+            macro_backtrace,
+            track_diagnostics,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        source_map: Option<Lrc<SourceMap>>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+        teach: bool,
+        diagnostic_width: Option<usize>,
+        macro_backtrace: bool,
+        track_diagnostics: bool,
+    ) -> HtmlEmitter {
+        let blocks = HtmlBlocks::default();
+        let writer = EmitterWriter::html(
+            blocks.clone(),
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+            teach,
+            diagnostic_width,
+            macro_backtrace,
+            track_diagnostics,
+        );
+        HtmlEmitter { dst, blocks, writer, sections: vec![] }
+    }
+
+    pub fn ui_testing(mut self, ui_testing: bool) -> Self {
+        self.writer = self.writer.ui_testing(ui_testing);
+        self
+    }
+
+    fn to_html(&self) -> String {
+        let mut page = String::from(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>rustc diagnostics</title>\n<style>\n",
+        );
+        page.push_str(STYLE_SHEET);
+        page.push_str("</style>\n</head>\n<body>\n");
+        for section in &self.sections {
+            page.push_str(section);
+        }
+        page.push_str("</body>\n</html>\n");
+        page
+    }
+}
+
+impl Translate for HtmlEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.writer.fluent_bundle()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        self.writer.fallback_fluent_bundle()
+    }
+}
+
+impl Emitter for HtmlEmitter {
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        self.writer.source_map()
+    }
+
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        self.writer.emit_diagnostic(diag);
+        let mut blocks = self.blocks.take();
+        // Every diagnostic ends with a blank line, which only separates it from the next one on
+        // a terminal and is not one of its messages.
+        if blocks.last().map_or(false, |block| block == "\n") {
+            blocks.pop();
+        }
+        let Some((main, sub_messages)) = blocks.split_first() else { return };
+
+        let class = match diag.level {
+            Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => "error",
+            Level::Warning(_) => "warning",
+            Level::Note | Level::OnceNote | Level::Help => "note",
+            Level::FailureNote | Level::Allow | Level::Expect(_) => "failure-note",
+        };
+        let id = format!("diagnostic-{}", self.sections.len() + 1);
+        let mut section = format!(
+            "<section class=\"diagnostic {class}\" id=\"{id}\">\n\
+             <a class=\"anchor\" href=\"#{id}\" title=\"Link to this diagnostic\">#</a>\n\
+             <pre>{main}</pre>\n"
+        );
+        // The first block after the main message is the separator line that connects it to
+        // the sub-messages.
+        if sub_messages.len() > 1 {
+            let count = sub_messages.len() - 1;
+            write!(
+                section,
+                "<details>\n<summary>{count} more message{}</summary>\n<pre>{}</pre>\n</details>\n",
+                pluralize!(count),
+                sub_messages.concat(),
+            )
+            .unwrap();
+        }
+        section.push_str("</section>\n");
+        self.sections.push(section);
+    }
+
+    fn supports_color(&self) -> bool {
+        true
+    }
+}
+
+impl Drop for HtmlEmitter {
+    fn drop(&mut self) {
+        // There is nowhere left to report a failure to: the page is written to the same
+        // destination the diagnostics would have been written to.
+        let page = self.to_html();
+        let _ = self.dst.write_all(page.as_bytes()).and_then(|_| self.dst.flush());
+    }
+}
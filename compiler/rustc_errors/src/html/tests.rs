@@ -0,0 +1,68 @@
+use super::*;
+
+use crate::tests::emitter_output;
+use crate::{DiagnosticId, Handler};
+use rustc_span::{BytePos, Span};
+
+/// Emits the diagnostics built by `emit` for `code` in `test.rs`, and returns the page.
+fn html_page(code: &str, emit: impl FnOnce(&Handler)) -> String {
+    emitter_output(
+        code,
+        |dst, sm, fallback_bundle| {
+            Box::new(
+                HtmlEmitter::new(dst, Some(sm), None, fallback_bundle, false, None, false, false)
+                    .ui_testing(true),
+            )
+        },
+        emit,
+    )
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn diagnostics_are_sections() {
+    let page = html_page("let x = 1;\nlet y = x;\n", |handler| {
+        let mut diag = handler.struct_span_err_with_code(
+            span(15, 16),
+            "expected `Vec<u8>`",
+            DiagnosticId::Error("E0001".into()),
+        );
+        diag.note("first note");
+        diag.help("then help");
+        diag.emit();
+        handler.struct_span_warn(span(4, 5), "unused & <unnamed>").emit();
+    });
+
+    assert!(page.starts_with("<!DOCTYPE html>"));
+    assert!(page.ends_with("</html>\n"));
+    assert!(page.contains("<style>"));
+
+    assert!(page.contains("<section class=\"diagnostic error\" id=\"diagnostic-1\">"));
+    assert!(page.contains("<a class=\"anchor\" href=\"#diagnostic-1\""));
+    assert!(page.contains("<section class=\"diagnostic warning\" id=\"diagnostic-2\">"));
+
+    // Colors are CSS classes rather than ANSI escapes, and the text is escaped.
+    assert!(!page.contains('\x1b'));
+    assert!(page.contains("<span class=\"fg-red intense bold\">error[E0001]</span>"));
+    assert!(page.contains("expected `Vec&lt;u8&gt;`"));
+    assert!(page.contains("unused &amp; &lt;unnamed&gt;"));
+    assert!(page.contains("test.rs:2:5"));
+    assert!(page.contains("LL"));
+
+    // Only the error has sub-messages to collapse.
+    assert_eq!(page.matches("<details>").count(), 1);
+    assert!(page.contains("<summary>2 more messages</summary>"));
+    let details = &page[page.find("<details>").unwrap()..page.find("</details>").unwrap()];
+    assert!(details.contains("first note"));
+    assert!(details.contains("then help"));
+}
+
+#[test]
+fn empty_page() {
+    let page = html_page("", |_| {});
+    assert!(!page.contains("<section"));
+    assert!(page.contains("<body>\n</body>"));
+}
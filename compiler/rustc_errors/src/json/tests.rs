@@ -1,14 +1,11 @@
 use super::*;
 
 use crate::json::JsonEmitter;
-use rustc_span::source_map::{FilePathMapping, SourceMap};
+use crate::tests::emitter_output;
 
 use crate::emitter::{ColorConfig, HumanReadableErrorType};
-use crate::Handler;
 use rustc_span::{BytePos, Span};
 
-use std::str;
-
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -26,54 +23,34 @@ struct SpanTestData {
     pub column_end: u32,
 }
 
-struct Shared<T> {
-    data: Arc<Mutex<T>>,
-}
-
-impl<T: Write> Write for Shared<T> {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.data.lock().unwrap().write(buf)
-    }
-
-    fn flush(&mut self) -> io::Result<()> {
-        self.data.lock().unwrap().flush()
-    }
-}
-
 /// Test the span yields correct positions in JSON.
 fn test_positions(code: &str, span: (u32, u32), expected_output: SpanTestData) {
-    rustc_span::create_default_session_globals_then(|| {
-        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
-        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
-        let fallback_bundle =
-            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
-
-        let output = Arc::new(Mutex::new(Vec::new()));
-        let je = JsonEmitter::new(
-            Box::new(Shared { data: output.clone() }),
-            None,
-            sm,
-            None,
-            fallback_bundle,
-            true,
-            HumanReadableErrorType::Short(ColorConfig::Never),
-            None,
-            false,
-            false,
-        );
-
-        let span = Span::with_root_ctxt(BytePos(span.0), BytePos(span.1));
-        let handler = Handler::with_emitter(true, None, Box::new(je));
-        handler.span_err(span, "foo");
-
-        let bytes = output.lock().unwrap();
-        let actual_output = str::from_utf8(&bytes).unwrap();
-        let actual_output: TestData = serde_json::from_str(actual_output).unwrap();
-        let spans = actual_output.spans;
-        assert_eq!(spans.len(), 1);
-
-        assert_eq!(expected_output, spans[0])
-    })
+    let output = emitter_output(
+        code,
+        |dst, sm, fallback_bundle| {
+            Box::new(JsonEmitter::new(
+                dst,
+                None,
+                sm,
+                None,
+                fallback_bundle,
+                true,
+                HumanReadableErrorType::Short(ColorConfig::Never),
+                None,
+                false,
+                false,
+            ))
+        },
+        |handler| {
+            handler.span_err(Span::with_root_ctxt(BytePos(span.0), BytePos(span.1)), "foo");
+        },
+    );
+
+    let actual_output: TestData = serde_json::from_str(&output).unwrap();
+    let spans = actual_output.spans;
+    assert_eq!(spans.len(), 1);
+
+    assert_eq!(expected_output, spans[0])
 }
 
 #[test]
@@ -31,8 +31,9 @@
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
 pub use rustc_error_messages::{
-    fallback_fluent_bundle, fluent, fluent_bundle, DelayDm, DiagnosticMessage, FluentBundle,
-    LanguageIdentifier, LazyFallbackBundle, MultiSpan, SpanLabel, SubdiagnosticMessage,
+    default_locale_catalog, fallback_fluent_bundle, fluent, fluent_bundle, translation_coverage,
+    CatalogMessage, DelayDm, DiagnosticMessage, FluentBundle, LanguageIdentifier,
+    LazyFallbackBundle, MultiSpan, SpanLabel, SubdiagnosticMessage, TranslationCoverage,
     DEFAULT_LOCALE_RESOURCES,
 };
 pub use rustc_lint_defs::{pluralize, Applicability};
@@ -42,6 +43,7 @@
 
 use std::borrow::Cow;
 use std::hash::Hash;
+use std::io;
 use std::num::NonZeroUsize;
 use std::panic;
 use std::path::Path;
@@ -50,17 +52,24 @@
 use termcolor::{Color, ColorSpec};
 
 pub mod annotate_snippet_emitter_writer;
+pub mod apply_suggestions;
+pub mod baseline;
 mod diagnostic;
 mod diagnostic_builder;
 mod diagnostic_impls;
 pub mod emitter;
+pub mod html;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
+#[cfg(test)]
+mod tests;
 pub mod translation;
 
+pub use baseline::DiagnosticBaseline;
 pub use diagnostic_builder::IntoDiagnostic;
 pub use snippet::Style;
 
@@ -433,6 +442,16 @@ struct HandlerInner {
 
     future_breakage_diagnostics: Vec<Diagnostic>,
 
+    /// Diagnostics held back by [`HandlerFlags::sort_diagnostics`].
+    sorted_diagnostics: Vec<Diagnostic>,
+
+    /// The suggestions kept for [`HandlerFlags::keep_machine_applicable_suggestions`], in the
+    /// order their diagnostics were emitted.
+    machine_applicable_suggestions: Vec<CodeSuggestion>,
+
+    /// The baseline of `-Z diagnostic-baseline` and `-Z write-diagnostic-baseline`, if any.
+    diagnostic_baseline: Option<DiagnosticBaseline>,
+
     /// The [`Self::unstable_expect_diagnostics`] should be empty when this struct is
     /// dropped. However, it can have values if the compilation is stopped early
     /// or is only partially executed. To avoid ICEs, like in rust#94953 we only
@@ -499,11 +518,21 @@ pub struct HandlerFlags {
     pub deduplicate_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
+    /// If true, diagnostics are held back and emitted sorted by their primary span the next
+    /// time the compiler may stop, so that their order does not depend on which thread
+    /// reported them first.
+    /// (rustc: enabled by `-Z threads` with more than one thread)
+    pub sort_diagnostics: bool,
+    /// If true, the machine-applicable suggestions of the emitted diagnostics are kept, to be
+    /// applied to the source files once the compilation is over.
+    /// (rustc: see `-Z apply-suggestions`)
+    pub keep_machine_applicable_suggestions: bool,
 }
 
 impl Drop for HandlerInner {
     fn drop(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_sorted_diagnostics();
 
         if !self.has_errors() {
             let bugs = std::mem::replace(&mut self.delayed_span_bugs, Vec::new());
@@ -604,6 +633,9 @@ pub fn with_emitter_and_flags(
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
+                sorted_diagnostics: Vec::new(),
+                machine_applicable_suggestions: Vec::new(),
+                diagnostic_baseline: None,
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
                 fulfilled_expectations: Default::default(),
@@ -1069,6 +1101,38 @@ pub fn print_error_count(&self, registry: &Registry) {
         self.inner.borrow_mut().print_error_count(registry)
     }
 
+    /// Takes the machine-applicable suggestions of the diagnostics emitted so far, if they are
+    /// kept, see [`HandlerFlags::keep_machine_applicable_suggestions`].
+    pub fn take_machine_applicable_suggestions(&self) -> Vec<CodeSuggestion> {
+        let mut inner = self.inner.borrow_mut();
+        // Suggestions are applied in the order their diagnostics are emitted in.
+        inner.emit_sorted_diagnostics();
+        std::mem::take(&mut inner.machine_applicable_suggestions)
+    }
+
+    /// Suppresses the lint diagnostics listed in `baseline` from now on, and records the lint
+    /// diagnostics that are emitted for [`Handler::write_diagnostic_baseline`].
+    ///
+    /// This is done here rather than in `rustc_lint::levels`, as lint levels are not computed
+    /// again for the diagnostics that are replayed from the incremental cache, and because a
+    /// fingerprint needs the final primary span of a diagnostic. Only diagnostics with a lint
+    /// code are affected: errors, non-lint warnings and `--force-warn` lints are never
+    /// suppressed nor written to a baseline.
+    pub fn set_diagnostic_baseline(&self, baseline: DiagnosticBaseline) {
+        self.inner.borrow_mut().diagnostic_baseline = Some(baseline);
+    }
+
+    /// Writes the lint diagnostics emitted so far to `path` as a baseline, if a baseline was set
+    /// with [`Handler::set_diagnostic_baseline`].
+    pub fn write_diagnostic_baseline(&self, path: &Path) -> io::Result<()> {
+        let mut inner = self.inner.borrow_mut();
+        inner.emit_sorted_diagnostics();
+        match &inner.diagnostic_baseline {
+            Some(baseline) => baseline.write(path),
+            None => Ok(()),
+        }
+    }
+
     pub fn take_future_breakage_diagnostics(&self) -> Vec<Diagnostic> {
         std::mem::take(&mut self.inner.borrow_mut().future_breakage_diagnostics)
     }
@@ -1141,7 +1205,9 @@ pub fn emit_artifact_notification(&self, path: &Path, artifact_type: &str) {
     }
 
     pub fn emit_future_breakage_report(&self, diags: Vec<Diagnostic>) {
-        self.inner.borrow_mut().emitter.emit_future_breakage_report(diags)
+        let mut inner = self.inner.borrow_mut();
+        inner.emit_sorted_diagnostics();
+        inner.emitter.emit_future_breakage_report(diags)
     }
 
     pub fn emit_unused_externs(
@@ -1299,6 +1365,12 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             return None;
         }
 
+        if let Some(baseline) = &mut self.diagnostic_baseline
+            && baseline.suppresses(diagnostic, self.emitter.source_map().map(|sm| &**sm))
+        {
+            return None;
+        }
+
         if let Some(ref code) = diagnostic.code {
             self.emitted_diagnostic_codes.insert(code.clone());
         }
@@ -1329,7 +1401,15 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
 
             diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
 
-            self.emitter.emit_diagnostic(diagnostic);
+            if self.flags.sort_diagnostics && !matches!(diagnostic.level, Bug | DelayedBug | Fatal)
+            {
+                self.sorted_diagnostics.push(diagnostic.clone());
+            } else {
+                // Bugs and fatal errors stop the compiler, so everything reported before them
+                // has to be emitted first.
+                self.emit_sorted_diagnostics();
+                self.emit_to_emitter(diagnostic);
+            }
             if diagnostic.is_error() {
                 self.deduplicated_err_count += 1;
             } else if let Warning(_) = diagnostic.level {
@@ -1352,13 +1432,48 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
     }
 
     fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
+        self.emit_sorted_diagnostics();
         self.emitter.emit_artifact_notification(path, artifact_type);
     }
 
     fn emit_unused_externs(&mut self, lint_level: rustc_lint_defs::Level, unused_externs: &[&str]) {
+        self.emit_sorted_diagnostics();
         self.emitter.emit_unused_externs(lint_level, unused_externs);
     }
 
+    /// Emits the diagnostics held back by [`HandlerFlags::sort_diagnostics`], ordered by their
+    /// primary span. Diagnostics without a span come last, and ties are broken by the level and
+    /// the error code or lint name so that the order is the same no matter how many threads
+    /// reported them.
+    fn emit_sorted_diagnostics(&mut self) {
+        let mut diagnostics = std::mem::take(&mut self.sorted_diagnostics);
+        diagnostics.sort_by(|a, b| {
+            let key = |diag: &Diagnostic| {
+                let span = diag.span.primary_span();
+                let code = diag.code.as_ref().map(|code| match code {
+                    DiagnosticId::Error(code) => code.as_str(),
+                    DiagnosticId::Lint { name, .. } => name.as_str(),
+                });
+                (span.is_none(), span, diag.level.to_str(), code)
+            };
+            key(a).cmp(&key(b))
+        });
+        for diag in &diagnostics {
+            self.emit_to_emitter(diag);
+        }
+    }
+
+    fn emit_to_emitter(&mut self, diagnostic: &Diagnostic) {
+        if self.flags.keep_machine_applicable_suggestions
+            && let Ok(suggestions) = &diagnostic.suggestions
+        {
+            self.machine_applicable_suggestions.extend(
+                suggestions.iter().filter(|s| apply_suggestions::is_machine_applicable(s)).cloned(),
+            );
+        }
+        self.emitter.emit_diagnostic(diagnostic);
+    }
+
     fn treat_err_as_bug(&self) -> bool {
         self.flags.treat_err_as_bug.map_or(false, |c| {
             self.err_count() + self.lint_err_count + self.delayed_bug_count() >= c.get()
@@ -1371,6 +1486,7 @@ fn delayed_bug_count(&self) -> usize {
 
     fn print_error_count(&mut self, registry: &Registry) {
         self.emit_stashed_diagnostics();
+        self.emit_sorted_diagnostics();
 
         let warnings = match self.deduplicated_warn_count {
             0 => String::new(),
@@ -1502,6 +1618,7 @@ fn is_compilation_going_to_fail(&self) -> bool {
 
     fn abort_if_errors(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_sorted_diagnostics();
 
         if self.has_errors() {
             FatalError.raise();
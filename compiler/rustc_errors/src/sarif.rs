@@ -0,0 +1,474 @@
+//! An emitter for the Static Analysis Results Interchange Format (SARIF) 2.1.0.
+//!
+//! Unlike the JSON emitter, which writes one object per diagnostic, a SARIF log is a single
+//! document describing the whole compilation session. Diagnostics are therefore collected as
+//! they are emitted and the log is written when the emitter is dropped.
+//!
+//! Diagnostics are mapped to SARIF results as follows:
+//!
+//! * error codes and lint names become the `rules` of the run, and the `ruleId` of results,
+//! * primary spans become `locations`, secondary spans and child diagnostics become
+//!   `relatedLocations`,
+//! * each alternative of a suggestion becomes one of the `fixes` of the result.
+//!
+//! Columns are 1-based and counted in characters, i.e. the `columnKind` of the run is
+//! `unicodeCodePoints`, consistent with the columns of the JSON emitter.
+
+use rustc_span::source_map::SourceMap;
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{to_fluent_args, Translate};
+use crate::{
+    CodeSuggestion, DiagnosticId, FluentBundle, LazyFallbackBundle, Level, MultiSpan, SubDiagnostic,
+};
+use rustc_lint_defs::Applicability;
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_error_messages::FluentArgs;
+use rustc_span::{SourceFile, Span};
+use std::io::{self, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+    /// The error codes and lints of the results, indexed by their `ruleIndex`.
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            registry,
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            rules: FxIndexMap::default(),
+            results: vec![],
+        }
+    }
+
+    /// Returns the index of the rule for `code`, adding the rule if this is its first result.
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let id = match code {
+            DiagnosticId::Error(code) => code,
+            DiagnosticId::Lint { name, .. } => name,
+        };
+        let entry = self.rules.entry(id.clone());
+        let index = entry.index();
+        entry.or_insert_with(|| {
+            let (help_uri, explanation) = match code {
+                DiagnosticId::Error(code) => (
+                    Some(format!("https://doc.rust-lang.org/error-index.html#{code}")),
+                    self.registry
+                        .as_ref()
+                        .and_then(|registry| registry.try_find_description(code).ok().flatten()),
+                ),
+                DiagnosticId::Lint { .. } => (None, None),
+            };
+            ReportingDescriptor {
+                id: id.clone(),
+                help_uri,
+                help: explanation.map(|explanation| MultiformatMessage {
+                    text: explanation.to_owned(),
+                    markdown: explanation.to_owned(),
+                }),
+            }
+        });
+        index
+    }
+
+    fn physical_location(&self, span: Span) -> Option<PhysicalLocation> {
+        if span.is_dummy() {
+            return None;
+        }
+        Some(PhysicalLocation {
+            artifact_location: self.artifact_location(&self.sm.lookup_source_file(span.lo())),
+            region: self.region(span),
+        })
+    }
+
+    fn artifact_location(&self, file: &SourceFile) -> ArtifactLocation {
+        let name = self.sm.filename_for_diagnostics(&file.name).to_string();
+        if Path::new(&name).is_absolute() {
+            let path = path_to_uri(&name);
+            let separator = if path.starts_with('/') { "" } else { "/" };
+            ArtifactLocation { uri: format!("file://{separator}{path}"), uri_base_id: None }
+        } else {
+            ArtifactLocation { uri: path_to_uri(&name), uri_base_id: Some("%SRCROOT%") }
+        }
+    }
+
+    fn region(&self, span: Span) -> Region {
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset: byte_start,
+            byte_length: byte_end.saturating_sub(byte_start),
+        }
+    }
+
+    /// The locations of the primary spans of `span`, and of its secondary spans.
+    fn locations(&self, span: &MultiSpan, args: &FluentArgs<'_>) -> (Vec<Location>, Vec<Location>) {
+        let mut primary = vec![];
+        let mut secondary = vec![];
+        for label in span.span_labels() {
+            let Some(physical_location) = self.physical_location(label.span) else {
+                continue;
+            };
+            let location = Location {
+                physical_location: Some(physical_location),
+                message: label
+                    .label
+                    .as_ref()
+                    .map(|label| Message::new(self.translate_message(label, args).to_string())),
+            };
+            if label.is_primary {
+                primary.push(location)
+            } else {
+                secondary.push(location)
+            }
+        }
+        (primary, secondary)
+    }
+
+    /// The related locations of a child diagnostic: the locations of its primary spans, or just
+    /// its message if it has no span, like the `= note: ...` lines of the human output.
+    fn child_locations(&self, child: &SubDiagnostic, args: &FluentArgs<'_>) -> Vec<Location> {
+        let message =
+            format!("{}: {}", child.level.to_str(), self.translate_messages(&child.message, args));
+        let span = child.render_span.as_ref().unwrap_or(&child.span);
+        let physical_locations: Vec<_> =
+            span.primary_spans().iter().filter_map(|&span| self.physical_location(span)).collect();
+        if physical_locations.is_empty() {
+            return vec![Location {
+                physical_location: None,
+                message: Some(Message::new(message)),
+            }];
+        }
+        physical_locations
+            .into_iter()
+            .map(|physical_location| Location {
+                physical_location: Some(physical_location),
+                message: Some(Message::new(message.clone())),
+            })
+            .collect()
+    }
+
+    /// One fix per alternative of `suggestion`, with the replacements grouped by file.
+    fn fixes(&self, suggestion: &CodeSuggestion, args: &FluentArgs<'_>) -> Vec<Fix> {
+        let description = self.translate_message(&suggestion.msg, args).to_string();
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                let mut changes: FxIndexMap<String, ArtifactChange> = FxIndexMap::default();
+                for part in &substitution.parts {
+                    let artifact_location =
+                        self.artifact_location(&self.sm.lookup_source_file(part.span.lo()));
+                    changes
+                        .entry(artifact_location.uri.clone())
+                        .or_insert_with(|| ArtifactChange {
+                            artifact_location,
+                            replacements: vec![],
+                        })
+                        .replacements
+                        .push(Replacement {
+                            deleted_region: self.region(part.span),
+                            inserted_content: ArtifactContent { text: part.snippet.clone() },
+                        });
+                }
+                Fix {
+                    description: Message::new(description.clone()),
+                    artifact_changes: changes.into_values().collect(),
+                    properties: FixProperties { applicability: suggestion.applicability },
+                }
+            })
+            .collect()
+    }
+}
+
+impl Translate for SarifEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &self.fallback_bundle
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        let level = match diag.level {
+            Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => "error",
+            Level::Warning(_) => "warning",
+            Level::Note | Level::OnceNote | Level::Help => "note",
+            // "aborting due to previous error" and the like describe the session, which is
+            // already described by the log itself.
+            Level::FailureNote | Level::Allow | Level::Expect(_) => return,
+        };
+
+        let args = to_fluent_args(diag.args());
+        let rule_index = diag.code.as_ref().map(|code| self.rule_index(code));
+        let (locations, mut related_locations) = self.locations(&diag.span, &args);
+        related_locations
+            .extend(diag.children.iter().flat_map(|child| self.child_locations(child, &args)));
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|suggestion| self.fixes(suggestion, &args))
+            .collect();
+
+        self.results.push(SarifResult {
+            rule_id: rule_index.map(|index| self.rules[index].id.clone()),
+            rule_index,
+            level,
+            message: Message::new(self.translate_messages(&diag.message, &args).to_string()),
+            locations,
+            related_locations,
+            fixes,
+        });
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: [Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: self.rules.values().collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        // There is nowhere left to report a failure to: the log is written to the same
+        // destination the diagnostics would have been written to.
+        let _ = serde_json::to_writer(&mut self.dst, &log)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(&mut self.dst))
+            .and_then(|_| self.dst.flush());
+    }
+}
+
+/// Turns a file name into a URI reference, as required for artifact locations.
+fn path_to_uri(path: &str) -> String {
+    let mut uri = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            '\\' => uri.push('/'),
+            '%' => uri.push_str("%25"),
+            ' ' => uri.push_str("%20"),
+            '#' => uri.push_str("%23"),
+            '?' => uri.push_str("%3F"),
+            c => uri.push(c),
+        }
+    }
+    uri
+}
+
+// The following data types are provided just for serialisation, see the SARIF 2.1.0
+// specification for the meaning of their fields.
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<&'a ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code, e.g. `E0308`, or the lint name, e.g. `unused_variables`.
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    /// The explanation of an error code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<MultiformatMessage>,
+}
+
+#[derive(Serialize)]
+struct MultiformatMessage {
+    text: String,
+    markdown: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning" or "note".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+impl Message {
+    fn new(text: String) -> Message {
+        Message { text }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    physical_location: Option<PhysicalLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    /// Set for relative paths, which are relative to the directory rustc was invoked in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+use crate::tests::emitter_output;
+use crate::Handler;
+use rustc_span::BytePos;
+
+use serde_json::Value;
+
+/// Emits the diagnostics built by `emit` for `code` in `test.rs`, and returns the SARIF log.
+fn sarif_log(code: &str, emit: impl FnOnce(&Handler)) -> Value {
+    let log = emitter_output(
+        code,
+        |dst, sm, fallback_bundle| {
+            Box::new(SarifEmitter::new(dst, None, sm, None, fallback_bundle))
+        },
+        emit,
+    );
+    serde_json::from_str(&log).unwrap()
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn error_with_note_and_suggestion() {
+    let log = sarif_log("let x = 1;\nlet y = x;\n", |handler| {
+        let mut diag = handler.struct_span_err_with_code(
+            span(15, 16),
+            "first",
+            DiagnosticId::Error("E0001".into()),
+        );
+        diag.span_label(span(4, 5), "defined here");
+        diag.span_note(span(19, 20), "used here");
+        diag.note("without a span");
+        diag.span_suggestion(span(15, 16), "rename it", "z", Applicability::MachineApplicable);
+        diag.emit();
+    });
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "E0001");
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "E0001");
+    assert_eq!(result["ruleIndex"], 0);
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "first");
+
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "test.rs");
+    assert_eq!(location["region"]["startLine"], 2);
+    assert_eq!(location["region"]["startColumn"], 5);
+    assert_eq!(location["region"]["byteOffset"], 15);
+    assert_eq!(location["region"]["byteLength"], 1);
+
+    let related = result["relatedLocations"].as_array().unwrap();
+    assert_eq!(related.len(), 3);
+    assert_eq!(related[0]["message"]["text"], "defined here");
+    assert_eq!(related[1]["message"]["text"], "note: used here");
+    assert_eq!(related[1]["physicalLocation"]["region"]["startColumn"], 9);
+    assert_eq!(related[2]["message"]["text"], "note: without a span");
+    assert!(related[2].get("physicalLocation").is_none());
+
+    let fix = &result["fixes"][0];
+    assert_eq!(fix["description"]["text"], "rename it");
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let replacement = &fix["artifactChanges"][0]["replacements"][0];
+    assert_eq!(replacement["insertedContent"]["text"], "z");
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], 15);
+}
+
+#[test]
+fn lints_share_rules() {
+    let log = sarif_log("fn a() {}\nfn b() {}\n", |handler| {
+        for lo in [3, 13] {
+            let code = DiagnosticId::Lint {
+                name: "dead_code".into(),
+                has_future_breakage: false,
+                is_force_warn: false,
+            };
+            handler.struct_span_warn_with_code(span(lo, lo + 1), "never used", code).emit();
+        }
+        handler.struct_err("without a code").emit();
+    });
+
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results[..2].iter().all(|r| r["ruleIndex"] == 0 && r["level"] == "warning"));
+    assert_eq!(results[1]["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+    assert!(results[2].get("ruleId").is_none());
+}
@@ -0,0 +1,56 @@
+//! Helpers shared by the tests of the emitters.
+
+use crate::emitter::Emitter;
+use crate::{Handler, LazyFallbackBundle};
+use rustc_data_structures::sync::Lrc;
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+/// A writer whose output can still be read after it was handed to an emitter.
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Emits the diagnostics built by `emit` for `code` in `test.rs`, with the emitter built by
+/// `emitter` from a writer, the source map and the fallback bundle, and returns its output.
+/// The handler and the emitter are dropped before the output is returned, as some emitters
+/// only write it then.
+pub(crate) fn emitter_output(
+    code: &str,
+    emitter: impl FnOnce(
+        Box<dyn Write + Send>,
+        Lrc<SourceMap>,
+        LazyFallbackBundle,
+    ) -> Box<dyn Emitter + Send>,
+    emit: impl FnOnce(&Handler),
+) -> String {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let emitter = emitter(Box::new(Shared { data: output.clone() }), sm, fallback_bundle);
+        let handler = Handler::with_emitter(true, None, emitter);
+        emit(&handler);
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        str::from_utf8(&bytes).unwrap().to_owned()
+    })
+}
@@ -39,6 +39,21 @@
 //!    at the beginning of the session has become obsolete because we have just
 //!    published a more current version. Thus the compiler will delete it.
 //!
+//! ## Relocation
+//!
+//! The directory of a crate within the incremental compilation directory is
+//! named after the crate name and its `StableCrateId`, and nothing stored in a
+//! session directory refers to the location of the sources, provided that the
+//! host-dependent parts of their paths are mapped away with
+//! `--remap-path-prefix`. A whole incremental compilation directory can thus be
+//! moved, or reused by a checkout of the same sources at a different path.
+//!
+//! With `-Zincremental-import=<dir>`, a compilation session that does not find
+//! any finalized session directory of its own starts out as a copy of the
+//! newest one found for the crate in `<dir>` instead, e.g. a cache produced on a
+//! CI machine. From then on, the imported copy is a regular local session
+//! directory, and `<dir>` is never modified.
+//!
 //! ## Garbage Collection
 //!
 //! Naively following the above protocol might lead to old session directories
@@ -256,13 +271,19 @@ pub fn prepare_session_directory(
         let source_directory = find_source_directory(&crate_dir, &source_directories_already_tried);
 
         let Some(source_directory) = source_directory else {
-            // There's nowhere to copy from, we're done
-            debug!(
-                "no source directory found. Continuing with empty session \
-                    directory."
-            );
+            // There's nowhere to copy from locally. If this crate has never
+            // been compiled here, try to start from an imported cache instead.
+            let imported = source_directories_already_tried.is_empty()
+                && import_session_directory(sess, crate_name, stable_crate_id, &session_dir);
+
+            if !imported {
+                debug!(
+                    "no source directory found. Continuing with empty session \
+                        directory."
+                );
+            }
 
-            sess.init_incr_comp_session(session_dir, directory_lock, false);
+            sess.init_incr_comp_session(session_dir, directory_lock, imported);
             return Ok(());
         };
 
@@ -463,6 +484,94 @@ fn copy_files(sess: &Session, target_dir: &Path, source_dir: &Path) -> Result<bo
     Ok(files_linked > 0 || files_copied == 0)
 }
 
+/// Seeds `session_dir` with the newest finalized session directory of the crate found in the
+/// cache directory given with `-Zincremental-import`. Returns `true` if anything was imported.
+///
+/// The imported cache is looked up by crate name and `StableCrateId`, exactly like in the local
+/// cache directory, so it does not matter in which directory it was produced. Whether it can
+/// actually be reused is decided when loading the dep-graph, like for a local cache: it is
+/// discarded if it was produced by a different compiler or with different commandline arguments.
+fn import_session_directory(
+    sess: &Session,
+    crate_name: Symbol,
+    stable_crate_id: StableCrateId,
+    session_dir: &Path,
+) -> bool {
+    let Some(import_dir) = &sess.opts.unstable_opts.incremental_import else {
+        return false;
+    };
+
+    let import_crate_dir = import_dir.join(crate_dir_name(crate_name, stable_crate_id));
+    let Ok(import_crate_dir_iterator) = import_crate_dir.read_dir() else {
+        debug!("no cache to import in `{}`", import_crate_dir.display());
+        return false;
+    };
+    let iter = import_crate_dir_iterator.filter_map(|e| e.ok().map(|e| e.path()));
+    let Some(source_directory) = find_source_directory_in_iter(iter, &FxHashSet::default()) else {
+        debug!("no finalized session directory to import in `{}`", import_crate_dir.display());
+        return false;
+    };
+
+    debug!("attempting to import data from: {}", source_directory.display());
+
+    match import_files(session_dir, &source_directory) {
+        Ok(files) => {
+            if sess.opts.unstable_opts.incremental_info {
+                eprintln!(
+                    "[incremental] imported {} files from `{}`",
+                    files,
+                    source_directory.display()
+                );
+            }
+            true
+        }
+        Err(err) => {
+            sess.warn(&format!(
+                "Could not import incremental compilation cache from `{}`: {}",
+                source_directory.display(),
+                err
+            ));
+
+            // Don't leave a partial copy behind, the session starts out empty instead.
+            if let Ok(session_dir_iterator) = session_dir.read_dir() {
+                for entry in session_dir_iterator.flatten() {
+                    let _ = safe_remove_file(&entry.path());
+                }
+            }
+            false
+        }
+    }
+}
+
+fn import_files(target_dir: &Path, source_dir: &Path) -> io::Result<usize> {
+    // An imported cache may have been unpacked from an archive that did not keep
+    // the lock files. If there is one, hold a shared lock while copying, like
+    // `copy_files` does, so that a compiler working on that cache directory
+    // does not garbage collect the session directory out from under us.
+    let lock_file_path = lock_file_path(source_dir);
+    let _lock = if lock_file_path.exists() {
+        Some(flock::Lock::new(
+            &lock_file_path,
+            false, // don't wait
+            false, // don't create
+            false,
+        )?)
+    } else {
+        None
+    };
+
+    let mut files = 0;
+    for entry in source_dir.read_dir()? {
+        let entry = entry?;
+        let target_file_path = target_dir.join(entry.file_name());
+        debug!("importing into session dir: {}", entry.path().display());
+        link_or_copy(entry.path(), target_file_path)?;
+        files += 1;
+    }
+
+    Ok(files)
+}
+
 /// Generates unique directory path of the form:
 /// {crate_dir}/s-{timestamp}-{random-number}-working
 fn generate_session_dir_path(crate_dir: &Path) -> PathBuf {
@@ -659,11 +768,16 @@ fn string_to_timestamp(s: &str) -> Result<SystemTime, ()> {
 
 fn crate_path(sess: &Session, crate_name: Symbol, stable_crate_id: StableCrateId) -> PathBuf {
     let incr_dir = sess.opts.incremental.as_ref().unwrap().clone();
+    incr_dir.join(crate_dir_name(crate_name, stable_crate_id))
+}
 
+/// The name of a crate's directory within an incremental compilation directory.
+///
+/// This only depends on the crate name and the `StableCrateId`, and not on
+/// where the crate is located, so that caches can be moved or imported.
+fn crate_dir_name(crate_name: Symbol, stable_crate_id: StableCrateId) -> String {
     let stable_crate_id = base_n::encode(stable_crate_id.to_u64() as u128, INT_ENCODE_BASE);
-
-    let crate_name = format!("{}-{}", crate_name, stable_crate_id);
-    incr_dir.join(crate_name)
+    format!("{}-{}", crate_name, stable_crate_id)
 }
 
 fn assert_no_characters_lost(s: &str) {
@@ -6,7 +6,7 @@
 use rustc_middle::ty::OnDiskCache;
 use rustc_serialize::opaque::MemDecoder;
 use rustc_serialize::Decodable;
-use rustc_session::config::IncrementalStateAssertion;
+use rustc_session::config::{IncrementalStateAssertion, IncrementalWhyFormat};
 use rustc_session::Session;
 use std::path::Path;
 
@@ -142,6 +142,7 @@ pub fn load_dep_graph(sess: &Session) -> DepGraphFuture {
     // Fortunately, we just checked that this isn't the case.
     let path = dep_graph_path(&sess);
     let report_incremental_info = sess.opts.unstable_opts.incremental_info;
+    let report_why = sess.opts.unstable_opts.incremental_why == Some(IncrementalWhyFormat::Human);
     let expected_hash = sess.opts.dep_tracking_hash(false);
 
     let mut prev_work_products = FxHashMap::default();
@@ -191,7 +192,7 @@ pub fn load_dep_graph(sess: &Session) -> DepGraphFuture {
                 let prev_commandline_args_hash = u64::decode(&mut decoder);
 
                 if prev_commandline_args_hash != expected_hash {
-                    if report_incremental_info {
+                    if report_incremental_info || report_why {
                         eprintln!(
                             "[incremental] completely ignoring cache because of \
                                     differing commandline arguments"
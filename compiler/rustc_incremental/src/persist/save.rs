@@ -11,6 +11,7 @@
 use super::dirty_clean;
 use super::file_format;
 use super::fs::*;
+use super::why;
 use super::work_product;
 
 /// Saves and writes the [`DepGraph`] to the file system.
@@ -42,6 +43,9 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
         if sess.opts.unstable_opts.incremental_info {
             tcx.dep_graph.print_incremental_info()
         }
+        if let Some(format) = sess.opts.unstable_opts.incremental_why {
+            why::report_rebuild_causes(tcx, format);
+        }
 
         join(
             move || {
@@ -184,5 +188,6 @@ pub fn build_dep_graph(
         encoder,
         sess.opts.unstable_opts.query_dep_graph,
         sess.opts.unstable_opts.incremental_info,
+        sess.opts.unstable_opts.incremental_why.is_some(),
     ))
 }
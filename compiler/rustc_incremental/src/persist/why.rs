@@ -0,0 +1,107 @@
+//! Reports why queries of the previous session had to be re-executed, for `-Zincremental-why`.
+//!
+//! While trying to mark nodes green, the dep-graph records the first dependency that prevented
+//! each node from being marked green. These records are traced back to the input changes they
+//! originate from, see [`DepGraph::rebuild_causes`], and summarized here.
+//!
+//! [`DepGraph::rebuild_causes`]: rustc_query_system::dep_graph::DepGraph::rebuild_causes
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::dep_graph::RebuildCauses;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::IncrementalWhyFormat;
+use serde_json::{json, Value};
+
+const INCREMENTAL_WHY_JSON_VERSION: u32 = 1;
+
+pub(super) fn report_rebuild_causes(tcx: TyCtxt<'_>, format: IncrementalWhyFormat) {
+    let Some(causes) = tcx.dep_graph.rebuild_causes(tcx) else {
+        return;
+    };
+
+    match format {
+        IncrementalWhyFormat::Human => print_human(&causes),
+        IncrementalWhyFormat::Json => eprintln!("{}", to_json(&causes)),
+    }
+}
+
+/// A root cause, i.e. the changed node and the input it changed because of, and the number of
+/// queries that were re-executed because of it, most frequent first.
+fn root_causes(causes: &RebuildCauses) -> Vec<((String, Option<String>), usize)> {
+    let mut counts: FxHashMap<(String, Option<String>), usize> = FxHashMap::default();
+    for reexecuted in &causes.reexecuted {
+        let root =
+            (format!("{:?}", reexecuted.changed), reexecuted.input.map(|i| format!("{:?}", i)));
+        *counts.entry(root).or_default() += 1;
+    }
+
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a_root, a_count), (b_root, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_root.cmp(b_root))
+    });
+    counts
+}
+
+fn print_human(causes: &RebuildCauses) {
+    if causes.previous_node_count == 0 {
+        eprintln!(
+            "[incremental] nothing was reused: there is no dep-graph from a previous session"
+        );
+        return;
+    }
+
+    let root_causes = root_causes(causes);
+    eprintln!(
+        "[incremental] {} queries were re-executed because of {} root causes",
+        causes.reexecuted.len(),
+        root_causes.len(),
+    );
+
+    let width = root_causes.first().map_or(0, |(_, count)| count.to_string().len());
+    for ((changed, input), count) in root_causes {
+        match input {
+            Some(input) => eprintln!(
+                "[incremental]   {:>width$} because `{}` changed (from input `{}`)",
+                count, changed, input
+            ),
+            None => eprintln!("[incremental]   {:>width$} because `{}` changed", count, changed),
+        }
+    }
+}
+
+fn to_json(causes: &RebuildCauses) -> Value {
+    let root_causes: Vec<_> = root_causes(causes)
+        .into_iter()
+        .map(|((changed, input), count)| {
+            json!({
+                "changed": changed,
+                "input": input,
+                "reexecuted": count,
+            })
+        })
+        .collect();
+
+    let mut reexecuted: Vec<_> = causes
+        .reexecuted
+        .iter()
+        .map(|reexecuted| {
+            (
+                format!("{:?}", reexecuted.node),
+                json!({
+                    "query": format!("{:?}", reexecuted.node),
+                    "first_red": format!("{:?}", reexecuted.first_red),
+                    "changed": format!("{:?}", reexecuted.changed),
+                    "input": reexecuted.input.map(|i| format!("{:?}", i)),
+                }),
+            )
+        })
+        .collect();
+    reexecuted.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    json!({
+        "version": INCREMENTAL_WHY_JSON_VERSION,
+        "previous_session": causes.previous_node_count != 0,
+        "root_causes": root_causes,
+        "reexecuted": reexecuted.into_iter().map(|(_, r)| r).collect::<Vec<_>>(),
+    })
+}
@@ -2,8 +2,11 @@
 use crate::interface::parse_cfgspecs;
 
 use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::apply_suggestions::ApplySuggestions;
 use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
 use rustc_session::config::InstrumentCoverage;
+use rustc_session::config::MirOptStatsFormat;
+use rustc_session::config::ProofTreeFormat;
 use rustc_session::config::Strip;
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc_session::config::{
@@ -11,9 +14,10 @@
 };
 use rustc_session::config::{
     BranchProtection, Externs, OomStrategy, OutputType, OutputTypes, PAuthKey, PacRet,
-    ProcMacroExecutionStrategy, SymbolManglingVersion, WasiExecModel,
+    ProcMacroExecutionStrategy, RemapPathPrefix, SymbolManglingVersion, WasiExecModel,
 };
 use rustc_session::config::{CFGuard, ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
+use rustc_session::config::{DataflowDumpFormat, DumpMirDataflow, IncrementalWhyFormat};
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
@@ -516,6 +520,27 @@ fn test_native_libs_tracking_hash_different_order() {
     assert_different_hash(&v2, &v3);
 }
 
+#[test]
+fn test_remap_path_prefix_tracking_hash_different_from_paths() {
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    let mut v3 = Options::default();
+    let mut v4 = Options::default();
+
+    v1.remap_path_prefix = RemapPathPrefix::new(vec![("/home/a/rust".into(), "/src".into())]);
+    v2.remap_path_prefix = RemapPathPrefix::new(vec![("/home/b/rust".into(), "/src".into())]);
+    v3.remap_path_prefix = RemapPathPrefix::new(vec![("/home/a/rust".into(), "/other".into())]);
+    v4.remap_path_prefix = RemapPathPrefix::new(vec![
+        ("/home/a/rust".into(), "/src".into()),
+        ("/home/a/.cargo".into(), "/cargo".into()),
+    ]);
+
+    // The host-dependent `FROM` side of a mapping is left out of the crate hash only.
+    assert_non_crate_hash_different(&v1, &v2);
+    assert_different_hash(&v1, &v3);
+    assert_different_hash(&v1, &v4);
+}
+
 #[test]
 fn test_codegen_options_tracking_hash() {
     let reference = Options::default();
@@ -616,7 +641,6 @@ macro_rules! tracked {
         real_rust_source_base_dir,
         Some("/home/bors/rust/.rustup/toolchains/nightly/lib/rustlib/src/rust".into())
     );
-    tracked!(remap_path_prefix, vec![("/home/bors/rust".into(), "src".into())]);
     // tidy-alphabetical-end
 }
 
@@ -635,35 +659,54 @@ macro_rules! untracked {
 
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
+    untracked!(apply_suggestions, Some(ApplySuggestions::Diff));
     untracked!(assert_incr_state, Some(String::from("loaded")));
+    untracked!(codegen_backend_by_crate, vec![("abc".to_string(), "def".to_string())]);
+    untracked!(compact_cycles, true);
     untracked!(deduplicate_diagnostics, false);
     untracked!(dep_tasks, true);
+    untracked!(diagnostic_baseline, Some(PathBuf::from("baseline.txt")));
     untracked!(dlltool, Some(PathBuf::from("custom_dlltool.exe")));
     untracked!(dont_buffer_diagnostics, true);
+    untracked!(dump_cycle_graph, Some(PathBuf::from("cycle.dot")));
     untracked!(dump_dep_graph, true);
     untracked!(dump_drop_tracking_cfg, Some("cfg.dot".to_string()));
     untracked!(dump_mir, Some(String::from("abc")));
-    untracked!(dump_mir_dataflow, true);
+    untracked!(
+        dump_mir_dataflow,
+        Some(DumpMirDataflow {
+            analyses: vec![String::from("liveness")],
+            format: DataflowDumpFormat::Json,
+        })
+    );
     untracked!(dump_mir_dir, String::from("abc"));
     untracked!(dump_mir_exclude_pass_number, true);
     untracked!(dump_mir_graphviz, true);
+    untracked!(dump_proof_tree, Some(String::from("Send")));
+    untracked!(dump_proof_tree_format, ProofTreeFormat::Json);
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
+    untracked!(explain_auto_traits, true);
     untracked!(future_incompat_test, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
     untracked!(incremental_ignore_spans, true);
+    untracked!(incremental_import, Some(PathBuf::from("/tmp/incr")));
     untracked!(incremental_info, true);
     untracked!(incremental_verify_ich, true);
+    untracked!(incremental_why, Some(IncrementalWhyFormat::Json));
     untracked!(input_stats, true);
     untracked!(keep_hygiene_data, true);
     untracked!(link_native_libraries, false);
+    untracked!(lint_config, Some(PathBuf::from("lints.cfg")));
     untracked!(llvm_time_trace, true);
     untracked!(ls, true);
     untracked!(macro_backtrace, true);
     untracked!(meta_stats, true);
+    untracked!(mir_opt_stats, Some(MirOptStatsFormat::Json));
     untracked!(mir_pretty_relative_line_numbers, true);
     untracked!(nll_facts, true);
+    untracked!(nll_facts_json, true);
     untracked!(no_analysis, true);
     untracked!(no_leak_check, true);
     untracked!(no_parallel_llvm, true);
@@ -680,7 +723,9 @@ macro_rules! untracked {
     untracked!(query_dep_graph, true);
     untracked!(save_analysis, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
+    untracked!(self_profile_chrome_trace, true);
     untracked!(self_profile_events, Some(vec![String::new()]));
+    untracked!(self_profile_summary, true);
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
     untracked!(temps_dir, Some(String::from("abc")));
@@ -695,6 +740,7 @@ macro_rules! untracked {
     untracked!(unstable_options, true);
     untracked!(validate_mir, true);
     untracked!(verbose, true);
+    untracked!(write_diagnostic_baseline, Some(PathBuf::from("baseline.txt")));
     // tidy-alphabetical-end
 
     macro_rules! tracked {
@@ -713,6 +759,7 @@ macro_rules! tracked {
     tracked!(asm_comments, true);
     tracked!(assume_incomplete_release, true);
     tracked!(binary_dep_depinfo, true);
+    tracked!(borrowck_explain, true);
     tracked!(box_noalias, Some(false));
     tracked!(
         branch_protection,
@@ -812,6 +859,27 @@ macro_rules! tracked_no_crate_hash {
     tracked_no_crate_hash!(no_codegen, true);
 }
 
+#[test]
+fn test_codegen_backend_by_crate() {
+    let build = |args: &[&str]| {
+        let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+        build_session_options_and_crate_config(optgroups().parse(&args).unwrap()).0
+    };
+
+    // Only the entry of the crate being compiled is used, and the last one wins.
+    let opts = build(&["--crate-name=foo", "-Zcodegen-backend-by-crate=foo=a,bar=b,foo=c"]);
+    assert_eq!(opts.unstable_opts.codegen_backend.as_deref(), Some("c"));
+    let by_crate = build(&["--crate-name=foo", "-Zcodegen-backend-by-crate=foo=c"]);
+    let global = build(&["--crate-name=foo", "-Zcodegen-backend=c"]);
+    assert_same_hash(&by_crate, &global);
+
+    // The entries of other crates don't affect the hashes.
+    let other = build(&["--crate-name=bar", "-Zcodegen-backend-by-crate=foo=c"]);
+    let none = build(&["--crate-name=bar"]);
+    assert_eq!(other.unstable_opts.codegen_backend, None);
+    assert_same_hash(&other, &none);
+}
+
 #[test]
 fn test_edition_parsing() {
     // test default edition
@@ -17,8 +17,8 @@
 use self::TargetLint::*;
 
 use crate::errors::{
-    CheckNameDeprecated, CheckNameUnknown, CheckNameUnknownTool, CheckNameWarning, RequestedLevel,
-    UnsupportedGroup,
+    CheckNameDeprecated, CheckNameUnknown, CheckNameUnknownTool, CheckNameWarning,
+    LintConfigUnknownLint, RequestedLevel, UnsupportedGroup,
 };
 use crate::levels::LintLevelsBuilder;
 use crate::passes::{EarlyLintPassObject, LateLintPassObject};
@@ -38,6 +38,7 @@
 use rustc_middle::ty::{self, print::Printer, subst::GenericArg, RegisteredTools, Ty, TyCtxt};
 use rustc_session::lint::{BuiltinLintDiagnostics, LintExpectationId};
 use rustc_session::lint::{FutureIncompatibleInfo, Level, Lint, LintBuffer, LintId};
+use rustc_session::lint_config::LintConfigEntry;
 use rustc_session::Session;
 use rustc_span::lev_distance::find_best_match_for_name;
 use rustc_span::symbol::{sym, Ident, Symbol};
@@ -46,6 +47,7 @@
 
 use std::cell::Cell;
 use std::iter;
+use std::path::Path;
 use std::slice;
 
 type EarlyLintPassFactory = dyn Fn() -> EarlyLintPassObject + sync::Send + sync::Sync;
@@ -374,6 +376,27 @@ pub fn check_lint_name_cmdline(
         };
     }
 
+    /// Checks the validity of lint names from the `-Z lint-config` file.
+    pub fn check_lint_name_config(
+        &self,
+        sess: &Session,
+        entry: &LintConfigEntry,
+        registered_tools: &RegisteredTools,
+    ) {
+        let (tool_name, lint_name_only) = parse_lint_and_tool_name(&entry.lint_name);
+        match self.check_lint_name(lint_name_only, tool_name, registered_tools) {
+            CheckLintNameResult::NoLint(_) | CheckLintNameResult::NoTool => {
+                let path = sess.opts.lint_config.path.as_deref().unwrap_or(Path::new(""));
+                sess.emit_warning(LintConfigUnknownLint {
+                    lint_name: entry.lint_name.clone(),
+                    path: path.display().to_string(),
+                    line: entry.line,
+                });
+            }
+            _ => {}
+        }
+    }
+
     /// True if this symbol represents a lint group name.
     pub fn is_lint_group(&self, lint_name: Symbol) -> bool {
         debug!(
@@ -15,6 +15,7 @@
 //! for all lint attributes.
 
 use crate::context::{EarlyContext, LintContext, LintStore};
+use crate::levels::LintConfigModule;
 use crate::passes::{EarlyLintPass, EarlyLintPassObject};
 use rustc_ast::ptr::P;
 use rustc_ast::visit::{self as ast_visit, Visitor};
@@ -22,7 +23,7 @@
 use rustc_middle::ty::RegisteredTools;
 use rustc_session::lint::{BufferedEarlyLint, LintBuffer, LintPass};
 use rustc_session::Session;
-use rustc_span::symbol::Ident;
+use rustc_span::symbol::{Ident, Symbol};
 use rustc_span::Span;
 
 macro_rules! lint_callback { ($cx:expr, $f:ident, $($args:expr),*) => ({
@@ -34,6 +35,8 @@ macro_rules! lint_callback { ($cx:expr, $f:ident, $($args:expr),*) => ({
 pub struct EarlyContextAndPass<'a, T: EarlyLintPass> {
     context: EarlyContext<'a>,
     pass: T,
+    /// The path of the module being checked, relative to the crate root.
+    module_path: Vec<Symbol>,
 }
 
 impl<'a, T: EarlyLintPass> EarlyContextAndPass<'a, T> {
@@ -63,10 +66,33 @@ fn check_id(&mut self, id: ast::NodeId) {
     fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f: F)
     where
         F: FnOnce(&mut Self),
+    {
+        self.with_module_lint_attrs(id, attrs, None, f)
+    }
+
+    /// Like `with_lint_attrs`, for a node that may be a module. In that case, `module` has the
+    /// span of the contents of the module, and the span of the module item if the module is not
+    /// the crate root, and the lints specified by the `-Z lint-config` file for the module are
+    /// merged too, before those of the attributes.
+    fn with_module_lint_attrs<F>(
+        &mut self,
+        id: ast::NodeId,
+        attrs: &'a [ast::Attribute],
+        module: Option<(Span, Option<Span>)>,
+        f: F,
+    ) where
+        F: FnOnce(&mut Self),
     {
         let is_crate_node = id == ast::CRATE_NODE_ID;
         debug!(?id);
-        let push = self.context.builder.push(attrs, is_crate_node, None);
+        let sess = self.context.sess();
+        let module = match module {
+            Some((inner_span, item_span)) if !sess.opts.lint_config.entries.is_empty() => {
+                Some(LintConfigModule::new(sess, &self.module_path, inner_span, item_span))
+            }
+            _ => None,
+        };
+        let push = self.context.builder.push(attrs, is_crate_node, None, module);
 
         self.inlined_check_id(id);
         debug!("early context: enter_attrs({:?})", attrs);
@@ -87,11 +113,21 @@ fn visit_param(&mut self, param: &'a ast::Param) {
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
-        self.with_lint_attrs(it.id, &it.attrs, |cx| {
+        let module = match &it.kind {
+            ast::ItemKind::Mod(_, ast::ModKind::Loaded(_, _, spans)) => {
+                self.module_path.push(it.ident.name);
+                Some((spans.inner_span, Some(it.span)))
+            }
+            _ => None,
+        };
+        self.with_module_lint_attrs(it.id, &it.attrs, module, |cx| {
             lint_callback!(cx, check_item, it);
             ast_visit::walk_item(cx, it);
             lint_callback!(cx, check_item_post, it);
-        })
+        });
+        if module.is_some() {
+            self.module_path.pop();
+        }
     }
 
     fn visit_foreign_item(&mut self, it: &'a ast::ForeignItem) {
@@ -332,6 +368,8 @@ pub trait EarlyCheckNode<'a>: Copy {
     fn attrs<'b>(self) -> &'b [ast::Attribute]
     where
         'a: 'b;
+    /// The span of the contents of the node, if it is the crate root.
+    fn crate_inner_span(self) -> Option<Span>;
     fn check<'b, T: EarlyLintPass>(self, cx: &mut EarlyContextAndPass<'b, T>)
     where
         'a: 'b;
@@ -347,6 +385,9 @@ fn attrs<'b>(self) -> &'b [ast::Attribute]
     {
         &self.attrs
     }
+    fn crate_inner_span(self) -> Option<Span> {
+        Some(self.spans.inner_span)
+    }
     fn check<'b, T: EarlyLintPass>(self, cx: &mut EarlyContextAndPass<'b, T>)
     where
         'a: 'b,
@@ -367,6 +408,9 @@ fn attrs<'b>(self) -> &'b [ast::Attribute]
     {
         self.1
     }
+    fn crate_inner_span(self) -> Option<Span> {
+        None
+    }
     fn check<'b, T: EarlyLintPass>(self, cx: &mut EarlyContextAndPass<'b, T>)
     where
         'a: 'b,
@@ -414,9 +458,12 @@ pub fn check_ast_node_inner<'a, T: EarlyLintPass>(
     context: EarlyContext<'_>,
     pass: T,
 ) {
-    let mut cx = EarlyContextAndPass { context, pass };
+    let mut cx = EarlyContextAndPass { context, pass, module_path: vec![] };
 
-    cx.with_lint_attrs(check_node.id(), check_node.attrs(), |cx| check_node.check(cx));
+    let module = check_node.crate_inner_span().map(|inner_span| (inner_span, None));
+    cx.with_module_lint_attrs(check_node.id(), check_node.attrs(), module, |cx| {
+        check_node.check(cx)
+    });
 
     // All of the buffered lints should have been emitted at this point.
     // If not, that means that we somehow buffered a lint for a node id
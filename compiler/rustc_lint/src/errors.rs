@@ -23,6 +23,7 @@ pub enum OverruledAttributeSub {
     DefaultSource { id: String },
     NodeSource { span: Span, reason: Option<Symbol> },
     CommandLineSource,
+    ConfigFileSource { line: usize },
 }
 
 impl AddToDiagnostic for OverruledAttributeSub {
@@ -44,6 +45,10 @@ fn add_to_diagnostic_with<F>(self, diag: &mut Diagnostic, _: F)
             OverruledAttributeSub::CommandLineSource => {
                 diag.note(fluent::lint_command_line_source);
             }
+            OverruledAttributeSub::ConfigFileSource { line } => {
+                diag.note(fluent::lint_config_file_source);
+                diag.set_arg("line", line);
+            }
         }
     }
 }
@@ -95,6 +100,14 @@ pub struct RequestedLevel {
     pub lint_name: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(lint_config_unknown_lint)]
+pub struct LintConfigUnknownLint {
+    pub lint_name: String,
+    pub path: String,
+    pub line: usize,
+}
+
 #[derive(Diagnostic)]
 #[diag(lint_unsupported_group, code = "E0602")]
 pub struct UnsupportedGroup {
@@ -5,6 +5,7 @@
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, DiagnosticMessage, MultiSpan};
 use rustc_hir as hir;
+use rustc_hir::def_id::CRATE_DEF_ID;
 use rustc_hir::intravisit::{self, Visitor};
 use rustc_hir::HirId;
 use rustc_index::vec::IndexVec;
@@ -155,6 +156,31 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
         levels.add_command_line();
     }
 
+    if !tcx.sess.opts.lint_config.entries.is_empty() {
+        let module = match tcx.hir().owner(owner) {
+            hir::OwnerNode::Crate(module) => Some((module, None)),
+            hir::OwnerNode::Item(&hir::Item {
+                kind: hir::ItemKind::Mod(ref module), span, ..
+            }) => Some((module, Some(span))),
+            _ => None,
+        };
+        if let Some((module, item_span)) = module {
+            let mut path = vec![];
+            let mut def_id = owner.def_id;
+            while def_id != CRATE_DEF_ID {
+                path.push(tcx.item_name(def_id.to_def_id()));
+                def_id = tcx.parent_module_from_def_id(def_id);
+            }
+            path.reverse();
+            levels.add_config_entries(LintConfigModule::new(
+                tcx.sess,
+                &path,
+                module.spans.inner_span,
+                item_span,
+            ));
+        }
+    }
+
     match attrs.map.range(..) {
         // There is only something to do if there are attributes at all.
         [] => {}
@@ -429,6 +455,35 @@ pub(crate) struct BuilderPush {
     prev: LintStackIndex,
 }
 
+/// A module, for the entries of the `-Z lint-config` file that apply to it.
+pub(crate) struct LintConfigModule<'a> {
+    /// The path of the module, relative to the crate root.
+    path: &'a [Symbol],
+    /// The name of the source file of the module, if the module is the crate root or an
+    /// out-of-line module.
+    file: Option<String>,
+}
+
+impl<'a> LintConfigModule<'a> {
+    /// Creates the module at `path`, whose contents are at `inner_span`. `item_span` is the span
+    /// of the module item, or `None` for the crate root.
+    pub(crate) fn new(
+        sess: &Session,
+        path: &'a [Symbol],
+        inner_span: Span,
+        item_span: Option<Span>,
+    ) -> Self {
+        let sm = sess.source_map();
+        let file = sm.lookup_source_file(inner_span.lo());
+        let is_out_of_line =
+            item_span.map_or(true, |span| sm.lookup_source_file(span.lo()).name != file.name);
+        LintConfigModule {
+            path,
+            file: is_out_of_line.then(|| file.name.prefer_remapped().to_string()),
+        }
+    }
+}
+
 impl<'s> LintLevelsBuilder<'s, TopDown> {
     pub(crate) fn new(
         sess: &'s Session,
@@ -470,17 +525,24 @@ fn process_command_line(&mut self) {
     /// * Lint attributes are validated, e.g., a `#[forbid]` can't be switched to
     ///   `#[allow]`
     ///
+    /// If the attributes are those of a module, `module` is the module, and the levels of the
+    /// entries of the `-Z lint-config` file for it are added before those of the attributes.
+    ///
     /// Don't forget to call `pop`!
     pub(crate) fn push(
         &mut self,
         attrs: &[ast::Attribute],
         is_crate_node: bool,
         source_hir_id: Option<HirId>,
+        module: Option<LintConfigModule<'_>>,
     ) -> BuilderPush {
         let prev = self.provider.cur;
         self.provider.cur =
             self.provider.sets.list.push(LintSet { specs: FxHashMap::default(), parent: prev });
 
+        if let Some(module) = module {
+            self.add_config_entries(module);
+        }
         self.add(attrs, is_crate_node, source_hir_id);
 
         if self.provider.current_specs().is_empty() {
@@ -546,6 +608,32 @@ fn add_command_line(&mut self) {
                 }
             }
         }
+
+        for entry in &self.sess.opts.lint_config.entries {
+            self.store.check_lint_name_config(self.sess, entry, self.registered_tools);
+        }
+    }
+
+    /// Adds the levels of the entries of the `-Z lint-config` file that apply to `module`, as if
+    /// they were attributes of the module.
+    fn add_config_entries(&mut self, module: LintConfigModule<'_>) {
+        let sess = self.sess;
+        for entry in sess.opts.lint_config.module_entries(module.path, module.file.as_deref()) {
+            let Ok(ids) = self.store.find_lints(&entry.lint_name) else {
+                // errors handled in check_lint_name_config from add_command_line
+                continue
+            };
+            let src = LintLevelSource::ConfigFile {
+                name: Symbol::intern(&entry.lint_name),
+                level: entry.level,
+                line: entry.line,
+            };
+            for id in ids {
+                if self.check_gated_lint(id, DUMMY_SP) {
+                    self.insert_spec(id, (entry.level, src));
+                }
+            }
+        }
     }
 
     /// Attempts to insert the `id` to `level_src` map entry. If unsuccessful
@@ -575,6 +663,7 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                     LintLevelSource::Default => false,
                     LintLevelSource::Node { name, .. } => self.store.is_lint_group(name),
                     LintLevelSource::CommandLine(symbol, _) => self.store.is_lint_group(symbol),
+                    LintLevelSource::ConfigFile { name, .. } => self.store.is_lint_group(name),
                 };
                 debug!(
                     "fcw_warning={:?}, specs.get(&id) = {:?}, old_src={:?}, id_name={:?}",
@@ -602,6 +691,12 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                         LintLevelSource::CommandLine(_, _) => {
                             diag.note("`forbid` lint level was set on command line");
                         }
+                        LintLevelSource::ConfigFile { line, .. } => {
+                            diag.note(&format!(
+                                "`forbid` lint level was set in the lint configuration file, \
+                                 at line {line}"
+                            ));
+                        }
                     }
                 };
                 if !fcw_warning {
@@ -620,6 +715,9 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                             LintLevelSource::CommandLine(_, _) => {
                                 OverruledAttributeSub::CommandLineSource
                             }
+                            LintLevelSource::ConfigFile { line, .. } => {
+                                OverruledAttributeSub::ConfigFileSource { line }
+                            }
                         },
                     });
                 } else {
@@ -1088,6 +1186,26 @@ pub(crate) fn struct_lint(
     }
 }
 
+/// Returns the level of every lint at the crate root, and its source, for `--print lint-levels`.
+///
+/// The levels are those set by the default levels, the command line, the `-Z lint-config` file
+/// and the attributes of the crate root. `root_file` is the name of the source file of the crate
+/// root.
+pub fn crate_root_lint_levels(
+    sess: &Session,
+    store: &LintStore,
+    crate_attrs: &[ast::Attribute],
+    root_file: Option<String>,
+) -> Vec<(&'static Lint, LevelAndSource)> {
+    let registered_tools = RegisteredTools::default();
+    let mut builder = LintLevelsBuilder::new(sess, false, store, &registered_tools);
+    let module = LintConfigModule { path: &[], file: root_file };
+    let push = builder.push(crate_attrs, true, None, Some(module));
+    let levels = store.get_lints().iter().map(|&lint| (lint, builder.lint_level(lint))).collect();
+    builder.pop(push);
+    levels
+}
+
 pub(crate) fn provide(providers: &mut Providers) {
     *providers = Providers { shallow_lint_levels_on, lint_expectations, ..*providers };
 }
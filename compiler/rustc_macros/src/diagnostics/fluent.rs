@@ -99,6 +99,7 @@ pub(crate) fn fluent_messages(input: proc_macro::TokenStream) -> proc_macro::Tok
     let mut previous_attrs = HashSet::new();
 
     let mut includes = TokenStream::new();
+    let mut resource_names = TokenStream::new();
     let mut generated = TokenStream::new();
 
     for res in resources.0 {
@@ -276,6 +277,8 @@ pub(crate) fn fluent_messages(input: proc_macro::TokenStream) -> proc_macro::Tok
         }
 
         includes.extend(quote! { include_str!(#relative_ftl_path), });
+        let resource_name = res.krate.to_string();
+        resource_names.extend(quote! { #resource_name, });
 
         generated.extend(constants);
     }
@@ -288,6 +291,11 @@ pub mod fluent_generated {
                 #includes
             ];
 
+            /// The crate each of `DEFAULT_LOCALE_RESOURCES` belongs to.
+            pub static DEFAULT_LOCALE_RESOURCE_NAMES: &'static [&'static str] = &[
+                #resource_names
+            ];
+
             #generated
 
             pub mod _subdiag {
@@ -34,6 +34,11 @@ pub enum LintLevelSource {
     /// The provided `Level` is the level specified on the command line.
     /// (The actual level may be lower due to `--cap-lints`.)
     CommandLine(Symbol, Level),
+
+    /// Lint level was set by an entry of the `-Z lint-config` file.
+    /// The provided `Level` is the level of the entry, and `line` is its line in the file.
+    /// (The actual level may be lower due to `--cap-lints`.)
+    ConfigFile { name: Symbol, level: Level, line: usize },
 }
 
 impl LintLevelSource {
@@ -42,6 +47,7 @@ pub fn name(&self) -> Symbol {
             LintLevelSource::Default => symbol::kw::Default,
             LintLevelSource::Node { name, .. } => name,
             LintLevelSource::CommandLine(name, _) => name,
+            LintLevelSource::ConfigFile { name, .. } => name,
         }
     }
 
@@ -50,6 +56,7 @@ pub fn span(&self) -> Span {
             LintLevelSource::Default => DUMMY_SP,
             LintLevelSource::Node { span, .. } => span,
             LintLevelSource::CommandLine(_, _) => DUMMY_SP,
+            LintLevelSource::ConfigFile { .. } => DUMMY_SP,
         }
     }
 }
@@ -258,6 +265,25 @@ pub fn explain_lint_level_source(
                 ));
             }
         }
+        LintLevelSource::ConfigFile { name: lint_config_name, level: orig_level, line } => {
+            if lint_config_name.as_str() == name {
+                err.note_once(&format!(
+                    "requested in the lint configuration file with `{} {}`, at line {}",
+                    orig_level.as_str(),
+                    name,
+                    line
+                ));
+            } else {
+                err.note_once(&format!(
+                    "`{} {}` implied by `{} {}` in the lint configuration file, at line {}",
+                    orig_level.as_str(),
+                    name,
+                    orig_level.as_str(),
+                    lint_config_name,
+                    line
+                ));
+            }
+        }
         LintLevelSource::Node { name: lint_attr_name, span, reason, .. } => {
             if let Some(rationale) = reason {
                 err.note(rationale.as_str());
@@ -0,0 +1,384 @@
+//! Serializes the THIR of a body to JSON for `-Zunpretty=thir-json`.
+//!
+//! Every body is printed as one object with the tables `params`, `exprs`, `blocks`, `stmts`
+//! and `arms`. Nodes refer to each other through the ids used by the `Debug` output of
+//! `-Zunpretty=thir-tree` (`e3`, `b0`, `s1`, `a2`, `p0`), which are the indices into the
+//! corresponding table. Every node carries its span and, where it has one, its type.
+//! Patterns are inlined as nested objects since they are not interned.
+
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::thir::*;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::Span;
+use serde_json::{json, Map, Value};
+
+const THIR_JSON_VERSION: u32 = 1;
+
+pub(crate) fn thir_json<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    owner_def: ty::WithOptConstParam<LocalDefId>,
+) -> String {
+    let def_path = tcx.def_path_str(owner_def.did.to_def_id());
+    let value = match super::cx::thir_body(tcx, owner_def) {
+        Ok((thir, expr)) => {
+            let thir = thir.steal();
+            let printer = ThirJson { tcx };
+            let params: Vec<_> =
+                thir.params.iter_enumerated().map(|(id, param)| printer.param(id, param)).collect();
+            let exprs: Vec<_> =
+                thir.exprs.iter_enumerated().map(|(id, expr)| printer.expr(id, expr)).collect();
+            let blocks: Vec<_> =
+                thir.blocks.iter_enumerated().map(|(id, block)| printer.block(id, block)).collect();
+            let stmts: Vec<_> =
+                thir.stmts.iter_enumerated().map(|(id, stmt)| printer.stmt(id, stmt)).collect();
+            let arms: Vec<_> =
+                thir.arms.iter_enumerated().map(|(id, arm)| printer.arm(id, arm)).collect();
+            json!({
+                "version": THIR_JSON_VERSION,
+                "def_path": def_path,
+                "body": id(expr),
+                "params": params,
+                "exprs": exprs,
+                "blocks": blocks,
+                "stmts": stmts,
+                "arms": arms,
+            })
+        }
+        Err(_) => json!({ "version": THIR_JSON_VERSION, "def_path": def_path, "error": true }),
+    };
+    value.to_string()
+}
+
+/// Renders a THIR id the same way its `Debug` implementation does, e.g. `e12`.
+fn id(id: impl std::fmt::Debug) -> Value {
+    json!(format!("{id:?}"))
+}
+
+fn ids<T: std::fmt::Debug>(ids: &[T]) -> Value {
+    ids.iter().map(id).collect()
+}
+
+fn opt_id(opt: Option<impl std::fmt::Debug>) -> Value {
+    opt.map_or(Value::Null, id)
+}
+
+/// Renders anything else that does not have a structured representation.
+fn debug(value: impl std::fmt::Debug) -> Value {
+    json!(format!("{value:?}"))
+}
+
+struct ThirJson<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> ThirJson<'tcx> {
+    fn span(&self, span: Span) -> Value {
+        let source_map = self.tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+        json!({
+            "file": lo.file.name.prefer_remapped().to_string(),
+            "lo": [lo.line, lo.col.0 + 1],
+            "hi": [hi.line, hi.col.0 + 1],
+        })
+    }
+
+    fn ty(&self, ty: Ty<'tcx>) -> Value {
+        json!(ty.to_string())
+    }
+
+    fn def(&self, def_id: DefId) -> Value {
+        json!(self.tcx.def_path_str(def_id))
+    }
+
+    fn var(&self, var: LocalVarId) -> Value {
+        json!({
+            "id": var.0.local_id.as_u32(),
+            "name": self.tcx.hir().name(var.0).to_string(),
+        })
+    }
+
+    fn param(&self, param_id: ParamId, param: &Param<'tcx>) -> Value {
+        json!({
+            "id": id(param_id),
+            "ty": self.ty(param.ty),
+            "ty_span": param.ty_span.map_or(Value::Null, |span| self.span(span)),
+            "pat": param.pat.as_ref().map_or(Value::Null, |pat| self.pat(pat)),
+        })
+    }
+
+    fn block(&self, block_id: BlockId, block: &Block) -> Value {
+        json!({
+            "id": id(block_id),
+            "span": self.span(block.span),
+            "stmts": ids(&block.stmts),
+            "expr": opt_id(block.expr),
+            "targeted_by_break": block.targeted_by_break,
+            "safety": match block.safety_mode {
+                BlockSafety::Safe => "safe",
+                BlockSafety::BuiltinUnsafe => "builtin_unsafe",
+                BlockSafety::ExplicitUnsafe(_) => "unsafe",
+            },
+        })
+    }
+
+    fn stmt(&self, stmt_id: StmtId, stmt: &Stmt<'tcx>) -> Value {
+        match &stmt.kind {
+            StmtKind::Expr { expr, .. } => {
+                json!({ "id": id(stmt_id), "kind": "Expr", "expr": id(expr) })
+            }
+            StmtKind::Let { pattern, initializer, else_block, .. } => json!({
+                "id": id(stmt_id),
+                "kind": "Let",
+                "pat": self.pat(pattern),
+                "initializer": opt_id(*initializer),
+                "else_block": opt_id(*else_block),
+            }),
+        }
+    }
+
+    fn arm(&self, arm_id: ArmId, arm: &Arm<'tcx>) -> Value {
+        let guard = match &arm.guard {
+            None => Value::Null,
+            Some(Guard::If(expr)) => json!({ "kind": "If", "expr": id(expr) }),
+            Some(Guard::IfLet(pat, expr)) => {
+                json!({ "kind": "IfLet", "pat": self.pat(pat), "expr": id(expr) })
+            }
+        };
+        json!({
+            "id": id(arm_id),
+            "span": self.span(arm.span),
+            "pat": self.pat(&arm.pattern),
+            "guard": guard,
+            "body": id(arm.body),
+        })
+    }
+
+    fn expr(&self, expr_id: ExprId, expr: &Expr<'tcx>) -> Value {
+        let mut fields = Map::new();
+        fields.insert("id".into(), id(expr_id));
+        fields.insert("ty".into(), self.ty(expr.ty));
+        fields.insert("span".into(), self.span(expr.span));
+        let (kind, details) = self.expr_kind(&expr.kind);
+        fields.insert("kind".into(), json!(kind));
+        if let Value::Object(details) = details {
+            fields.extend(details);
+        }
+        Value::Object(fields)
+    }
+
+    fn expr_kind(&self, kind: &ExprKind<'tcx>) -> (&'static str, Value) {
+        match kind {
+            ExprKind::Scope { value, .. } => ("Scope", json!({ "value": id(value) })),
+            ExprKind::Box { value } => ("Box", json!({ "value": id(value) })),
+            ExprKind::If { cond, then, else_opt, .. } => {
+                ("If", json!({ "cond": id(cond), "then": id(then), "else": opt_id(*else_opt) }))
+            }
+            ExprKind::Call { ty, fun, args, from_hir_call, fn_span } => (
+                "Call",
+                json!({
+                    "fn_ty": self.ty(*ty),
+                    "fun": id(fun),
+                    "args": ids(args),
+                    "from_hir_call": from_hir_call,
+                    "fn_span": self.span(*fn_span),
+                }),
+            ),
+            ExprKind::Deref { arg } => ("Deref", json!({ "arg": id(arg) })),
+            ExprKind::Binary { op, lhs, rhs } => {
+                ("Binary", json!({ "op": debug(op), "lhs": id(lhs), "rhs": id(rhs) }))
+            }
+            ExprKind::LogicalOp { op, lhs, rhs } => {
+                ("LogicalOp", json!({ "op": debug(op), "lhs": id(lhs), "rhs": id(rhs) }))
+            }
+            ExprKind::Unary { op, arg } => ("Unary", json!({ "op": debug(op), "arg": id(arg) })),
+            ExprKind::Cast { source } => ("Cast", json!({ "source": id(source) })),
+            ExprKind::Use { source } => ("Use", json!({ "source": id(source) })),
+            ExprKind::NeverToAny { source } => ("NeverToAny", json!({ "source": id(source) })),
+            ExprKind::Pointer { cast, source } => {
+                ("Pointer", json!({ "cast": debug(cast), "source": id(source) }))
+            }
+            ExprKind::Loop { body } => ("Loop", json!({ "body": id(body) })),
+            ExprKind::Let { expr, pat } => {
+                ("Let", json!({ "expr": id(expr), "pat": self.pat(pat) }))
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                ("Match", json!({ "scrutinee": id(scrutinee), "arms": ids(arms) }))
+            }
+            ExprKind::Block { block } => ("Block", json!({ "block": id(block) })),
+            ExprKind::Assign { lhs, rhs } => ("Assign", json!({ "lhs": id(lhs), "rhs": id(rhs) })),
+            ExprKind::AssignOp { op, lhs, rhs } => {
+                ("AssignOp", json!({ "op": debug(op), "lhs": id(lhs), "rhs": id(rhs) }))
+            }
+            ExprKind::Field { lhs, variant_index, name } => (
+                "Field",
+                json!({
+                    "lhs": id(lhs),
+                    "variant_index": variant_index.as_u32(),
+                    "field": name.as_u32(),
+                }),
+            ),
+            ExprKind::Index { lhs, index } => {
+                ("Index", json!({ "lhs": id(lhs), "index": id(index) }))
+            }
+            ExprKind::VarRef { id } => ("VarRef", json!({ "var": self.var(*id) })),
+            ExprKind::UpvarRef { closure_def_id, var_hir_id } => (
+                "UpvarRef",
+                json!({ "closure": self.def(*closure_def_id), "var": self.var(*var_hir_id) }),
+            ),
+            ExprKind::Borrow { borrow_kind, arg } => {
+                ("Borrow", json!({ "borrow_kind": debug(borrow_kind), "arg": id(arg) }))
+            }
+            ExprKind::AddressOf { mutability, arg } => {
+                ("AddressOf", json!({ "mutability": debug(mutability), "arg": id(arg) }))
+            }
+            ExprKind::Break { value, .. } => ("Break", json!({ "value": opt_id(*value) })),
+            ExprKind::Continue { .. } => ("Continue", json!({})),
+            ExprKind::Return { value } => ("Return", json!({ "value": opt_id(*value) })),
+            ExprKind::ConstBlock { did, substs } => {
+                ("ConstBlock", json!({ "def": self.def(*did), "substs": debug(substs) }))
+            }
+            ExprKind::Repeat { value, count } => {
+                ("Repeat", json!({ "value": id(value), "count": count.to_string() }))
+            }
+            ExprKind::Array { fields } => ("Array", json!({ "fields": ids(fields) })),
+            ExprKind::Tuple { fields } => ("Tuple", json!({ "fields": ids(fields) })),
+            ExprKind::Adt(adt) => {
+                let variant = adt.adt_def.variant(adt.variant_index);
+                let fields: Vec<_> = adt
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        json!({
+                            "name": variant.fields[field.name.index()].name.to_string(),
+                            "expr": id(field.expr),
+                        })
+                    })
+                    .collect();
+                (
+                    "Adt",
+                    json!({
+                        "adt": self.def(adt.adt_def.did()),
+                        "variant": variant.name.to_string(),
+                        "fields": fields,
+                        "base": adt.base.as_ref().map_or(Value::Null, |base| id(base.base)),
+                    }),
+                )
+            }
+            ExprKind::PlaceTypeAscription { source, .. } => {
+                ("PlaceTypeAscription", json!({ "source": id(source) }))
+            }
+            ExprKind::ValueTypeAscription { source, .. } => {
+                ("ValueTypeAscription", json!({ "source": id(source) }))
+            }
+            ExprKind::Closure(closure) => (
+                "Closure",
+                json!({
+                    "closure": self.def(closure.closure_id.to_def_id()),
+                    "upvars": ids(&closure.upvars),
+                    "movability": closure.movability.map_or(Value::Null, debug),
+                }),
+            ),
+            ExprKind::Literal { lit, neg } => {
+                ("Literal", json!({ "lit": debug(&lit.node), "neg": neg }))
+            }
+            ExprKind::NonHirLiteral { lit, .. } => ("NonHirLiteral", json!({ "lit": debug(lit) })),
+            ExprKind::ZstLiteral { .. } => ("ZstLiteral", json!({})),
+            ExprKind::NamedConst { def_id, substs, .. } => {
+                ("NamedConst", json!({ "def": self.def(*def_id), "substs": debug(substs) }))
+            }
+            ExprKind::ConstParam { param, def_id } => {
+                ("ConstParam", json!({ "param": param.name.to_string(), "def": self.def(*def_id) }))
+            }
+            ExprKind::StaticRef { def_id, .. } => {
+                ("StaticRef", json!({ "def": self.def(*def_id) }))
+            }
+            ExprKind::InlineAsm(asm) => (
+                "InlineAsm",
+                json!({
+                    "template": debug(asm.template),
+                    "operands": asm.operands.iter().map(debug).collect::<Vec<_>>(),
+                }),
+            ),
+            ExprKind::ThreadLocalRef(def_id) => {
+                ("ThreadLocalRef", json!({ "def": self.def(*def_id) }))
+            }
+            ExprKind::Yield { value } => ("Yield", json!({ "value": id(value) })),
+        }
+    }
+
+    fn pat(&self, pat: &Pat<'tcx>) -> Value {
+        let mut fields = Map::new();
+        fields.insert("ty".into(), self.ty(pat.ty));
+        fields.insert("span".into(), self.span(pat.span));
+        let (kind, details) = self.pat_kind(&pat.kind);
+        fields.insert("kind".into(), json!(kind));
+        if let Value::Object(details) = details {
+            fields.extend(details);
+        }
+        Value::Object(fields)
+    }
+
+    fn pats(&self, pats: &[Box<Pat<'tcx>>]) -> Value {
+        pats.iter().map(|pat| self.pat(pat)).collect()
+    }
+
+    fn field_pats(&self, pats: &[FieldPat<'tcx>]) -> Value {
+        pats.iter()
+            .map(|field| json!({ "field": field.field.as_u32(), "pat": self.pat(&field.pattern) }))
+            .collect()
+    }
+
+    fn pat_kind(&self, kind: &PatKind<'tcx>) -> (&'static str, Value) {
+        match kind {
+            PatKind::Wild => ("Wild", json!({})),
+            PatKind::AscribeUserType { subpattern, .. } => {
+                ("AscribeUserType", json!({ "subpattern": self.pat(subpattern) }))
+            }
+            PatKind::Binding { mutability, name, mode, var, subpattern, .. } => (
+                "Binding",
+                json!({
+                    "name": name.to_string(),
+                    "mutability": debug(mutability),
+                    "mode": debug(mode),
+                    "var": self.var(*var),
+                    "subpattern": subpattern.as_ref().map_or(Value::Null, |pat| self.pat(pat)),
+                }),
+            ),
+            PatKind::Variant { adt_def, variant_index, subpatterns, .. } => (
+                "Variant",
+                json!({
+                    "adt": self.def(adt_def.did()),
+                    "variant": adt_def.variant(*variant_index).name.to_string(),
+                    "subpatterns": self.field_pats(subpatterns),
+                }),
+            ),
+            PatKind::Leaf { subpatterns } => {
+                ("Leaf", json!({ "subpatterns": self.field_pats(subpatterns) }))
+            }
+            PatKind::Deref { subpattern } => {
+                ("Deref", json!({ "subpattern": self.pat(subpattern) }))
+            }
+            PatKind::Constant { value } => ("Constant", json!({ "value": value.to_string() })),
+            PatKind::Range(range) => (
+                "Range",
+                json!({
+                    "lo": range.lo.to_string(),
+                    "hi": range.hi.to_string(),
+                    "end": range.end.to_string(),
+                }),
+            ),
+            PatKind::Slice { prefix, slice, suffix } | PatKind::Array { prefix, slice, suffix } => {
+                (
+                    if matches!(kind, PatKind::Slice { .. }) { "Slice" } else { "Array" },
+                    json!({
+                        "prefix": self.pats(prefix),
+                        "slice": slice.as_ref().map_or(Value::Null, |pat| self.pat(pat)),
+                        "suffix": self.pats(suffix),
+                    }),
+                )
+            }
+            PatKind::Or { pats } => ("Or", json!({ "pats": self.pats(pats) })),
+        }
+    }
+}
@@ -8,6 +8,8 @@
 
 pub(crate) mod cx;
 
+pub(crate) mod json;
+
 pub(crate) mod pattern;
 
 mod util;
@@ -17,10 +17,12 @@
 use rustc_middle::mir::{self, traversal, BasicBlock};
 use rustc_middle::mir::{create_dump_file, dump_enabled};
 use rustc_middle::ty::TyCtxt;
+use rustc_session::config::DataflowDumpFormat;
 use rustc_span::symbol::{sym, Symbol};
 
 use super::fmt::DebugWithContext;
 use super::graphviz;
+use super::json;
 use super::{
     visit_results, Analysis, Direction, GenKill, GenKillAnalysis, GenKillSet, JoinSemiLattice,
     ResultsCursor, ResultsVisitor,
@@ -255,6 +257,11 @@ pub fn iterate_to_fixpoint(self) -> Results<'tcx, A>
             error!("Failed to write graphviz dataflow results: {}", e);
         }
 
+        let res = write_json_results(tcx, &body, &results, pass_name);
+        if let Err(e) = res {
+            error!("Failed to write JSON dataflow results: {}", e);
+        }
+
         results
     }
 }
@@ -291,9 +298,7 @@ fn write_graphviz_results<'tcx, A>(
             io::BufWriter::new(fs::File::create(&path)?)
         }
 
-        None if tcx.sess.opts.unstable_opts.dump_mir_dataflow
-            && dump_enabled(tcx, A::NAME, def_id) =>
-        {
+        None if dump_format::<A>(tcx, def_id) == Some(DataflowDumpFormat::Graphviz) => {
             create_dump_file(tcx, ".dot", false, A::NAME, &pass_name.unwrap_or("-----"), body)?
         }
 
@@ -320,6 +325,36 @@ fn write_graphviz_results<'tcx, A>(
     Ok(())
 }
 
+/// Writes a JSON file containing the state before and after every location if the user
+/// requested it via `-Z dump-mir-dataflow=<analysis>,json`.
+fn write_json_results<'tcx, A>(
+    tcx: TyCtxt<'tcx>,
+    body: &mir::Body<'tcx>,
+    results: &Results<'tcx, A>,
+    pass_name: Option<&'static str>,
+) -> std::io::Result<()>
+where
+    A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
+{
+    if dump_format::<A>(tcx, body.source.def_id()) != Some(DataflowDumpFormat::Json) {
+        return Ok(());
+    }
+
+    let mut file =
+        create_dump_file(tcx, "json", false, A::NAME, &pass_name.unwrap_or("-----"), body)?;
+    json::write_results(tcx, &mut file, body, results, pass_name)
+}
+
+/// The format `-Z dump-mir-dataflow` requests for the results of `A` on `def_id`, if any.
+fn dump_format<'tcx, A>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<DataflowDumpFormat>
+where
+    A: Analysis<'tcx>,
+{
+    let opts = tcx.sess.opts.unstable_opts.dump_mir_dataflow.as_ref()?;
+    (opts.enabled_for(A::NAME) && dump_enabled(tcx, A::NAME, def_id)).then_some(opts.format)
+}
+
 #[derive(Default)]
 struct RustcMirAttrs {
     basename_and_suffix: Option<PathBuf>,
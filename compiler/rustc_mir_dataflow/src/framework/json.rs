@@ -0,0 +1,181 @@
+//! Machine-readable dataflow results, written by `-Z dump-mir-dataflow=<analysis>,json`.
+//!
+//! The output is a single JSON object per analysis and body:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "analysis": "maybe_init",
+//!   "pass": "borrowck",
+//!   "def_path": "crate::foo",
+//!   "direction": "forward",
+//!   "blocks": [
+//!     {
+//!       "block": "bb0",
+//!       "entry": "{_1}",
+//!       "locations": [
+//!         { "location": "bb0[0]", "mir": "_2 = _1", "entry": "{_1}", "exit": "{_1, _2}" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! States are rendered with the same `DebugWithContext` implementation used by the graphviz
+//! output, e.g. sets of move paths or locals are printed as `{a, b}`. `entry` and `exit` follow
+//! the direction of the analysis: for a backward analysis like `liveness`, the `entry` state of a
+//! location is the state *after* it in program order. Locations are always listed in program
+//! order, and only reachable blocks are included.
+
+use std::io;
+
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::{self, traversal, BasicBlock, Location};
+use rustc_middle::ty::TyCtxt;
+use serde_json::{json, Value};
+
+use super::fmt::{DebugWithAdapter, DebugWithContext};
+use super::{Analysis, Direction, Results, ResultsVisitor};
+
+const DATAFLOW_JSON_VERSION: u32 = 1;
+
+pub(super) fn write_results<'tcx, A>(
+    tcx: TyCtxt<'tcx>,
+    w: &mut impl io::Write,
+    body: &mir::Body<'tcx>,
+    results: &Results<'tcx, A>,
+    pass_name: Option<&'static str>,
+) -> io::Result<()>
+where
+    A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
+{
+    let reachable: BitSet<BasicBlock> = traversal::reachable_as_bitset(body);
+    let blocks = body.basic_blocks.indices().filter(|bb| reachable.contains(*bb));
+
+    let mut collector = StateCollector {
+        analysis: &results.analysis,
+        prev_state: results.analysis.bottom_value(body),
+        block_entry: String::new(),
+        locations: vec![],
+        blocks: vec![],
+    };
+    results.visit_with(body, blocks, &mut collector);
+
+    let dump = json!({
+        "version": DATAFLOW_JSON_VERSION,
+        "analysis": A::NAME,
+        "pass": pass_name,
+        "def_path": tcx.def_path_str(body.source.def_id()),
+        "direction": if A::Direction::IS_FORWARD { "forward" } else { "backward" },
+        "blocks": collector.blocks,
+    });
+
+    serde_json::to_writer_pretty(&mut *w, &dump)?;
+    writeln!(w)
+}
+
+struct StateCollector<'a, 'tcx, A>
+where
+    A: Analysis<'tcx>,
+{
+    analysis: &'a A,
+    prev_state: A::Domain,
+    /// The entry state of the block currently being visited.
+    block_entry: String,
+    /// The locations of the block currently being visited, in visiting order.
+    locations: Vec<Value>,
+    blocks: Vec<Value>,
+}
+
+impl<'a, 'tcx, A> StateCollector<'a, 'tcx, A>
+where
+    A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
+{
+    fn pretty(&self, state: &A::Domain) -> String {
+        format!("{:?}", DebugWithAdapter { this: state, ctxt: self.analysis })
+    }
+
+    fn enter_block(&mut self, state: &A::Domain) {
+        self.block_entry = self.pretty(state);
+        self.prev_state.clone_from(state);
+    }
+
+    fn exit_block(&mut self, block: BasicBlock) {
+        let mut locations = std::mem::take(&mut self.locations);
+        if A::Direction::IS_BACKWARD {
+            locations.reverse();
+        }
+
+        self.blocks.push(json!({
+            "block": format!("{:?}", block),
+            "entry": std::mem::take(&mut self.block_entry),
+            "locations": locations,
+        }));
+    }
+
+    fn record(&mut self, state: &A::Domain, location: Location, mir: String) {
+        self.locations.push(json!({
+            "location": format!("{:?}", location),
+            "mir": mir,
+            "entry": self.pretty(&self.prev_state),
+            "exit": self.pretty(state),
+        }));
+        self.prev_state.clone_from(state);
+    }
+}
+
+impl<'a, 'tcx, A> ResultsVisitor<'a, 'tcx> for StateCollector<'a, 'tcx, A>
+where
+    A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
+{
+    type FlowState = A::Domain;
+
+    fn visit_block_start(
+        &mut self,
+        state: &Self::FlowState,
+        _block_data: &mir::BasicBlockData<'tcx>,
+        block: BasicBlock,
+    ) {
+        if A::Direction::IS_FORWARD {
+            self.enter_block(state);
+        } else {
+            self.exit_block(block);
+        }
+    }
+
+    fn visit_block_end(
+        &mut self,
+        state: &Self::FlowState,
+        _block_data: &mir::BasicBlockData<'tcx>,
+        block: BasicBlock,
+    ) {
+        if A::Direction::IS_FORWARD {
+            self.exit_block(block);
+        } else {
+            self.enter_block(state);
+        }
+    }
+
+    fn visit_statement_after_primary_effect(
+        &mut self,
+        state: &Self::FlowState,
+        statement: &mir::Statement<'tcx>,
+        location: Location,
+    ) {
+        self.record(state, location, format!("{:?}", statement));
+    }
+
+    fn visit_terminator_after_primary_effect(
+        &mut self,
+        state: &Self::FlowState,
+        terminator: &mir::Terminator<'tcx>,
+        location: Location,
+    ) {
+        let mut mir = String::new();
+        terminator.kind.fmt_head(&mut mir).unwrap();
+        self.record(state, location, mir);
+    }
+}
@@ -42,6 +42,7 @@
 mod engine;
 pub mod fmt;
 pub mod graphviz;
+mod json;
 pub mod lattice;
 mod visitor;
 
@@ -55,11 +55,21 @@ pub trait ValueAnalysis<'tcx> {
 
     fn map(&self) -> &Map;
 
-    fn handle_statement(&self, statement: &Statement<'tcx>, state: &mut State<Self::Value>) {
-        self.super_statement(statement, state)
+    fn handle_statement(
+        &self,
+        statement: &Statement<'tcx>,
+        location: Location,
+        state: &mut State<Self::Value>,
+    ) {
+        self.super_statement(statement, location, state)
     }
 
-    fn super_statement(&self, statement: &Statement<'tcx>, state: &mut State<Self::Value>) {
+    fn super_statement(
+        &self,
+        statement: &Statement<'tcx>,
+        _location: Location,
+        state: &mut State<Self::Value>,
+    ) {
         match &statement.kind {
             StatementKind::Assign(box (place, rvalue)) => {
                 self.handle_assign(*place, rvalue, state);
@@ -82,7 +92,7 @@ fn super_statement(&self, statement: &Statement<'tcx>, state: &mut State<Self::V
                 state.flood_with(place.as_ref(), self.map(), Self::Value::bottom());
             }
             StatementKind::Retag(..) => {
-                // We don't track references.
+                // We don't track what references point to.
             }
             StatementKind::Nop
             | StatementKind::FakeRead(..)
@@ -218,11 +228,21 @@ fn super_constant(
 
     /// The effect of a successful function call return should not be
     /// applied here, see [`Analysis::apply_terminator_effect`].
-    fn handle_terminator(&self, terminator: &Terminator<'tcx>, state: &mut State<Self::Value>) {
-        self.super_terminator(terminator, state)
+    fn handle_terminator(
+        &self,
+        terminator: &Terminator<'tcx>,
+        location: Location,
+        state: &mut State<Self::Value>,
+    ) {
+        self.super_terminator(terminator, location, state)
     }
 
-    fn super_terminator(&self, terminator: &Terminator<'tcx>, _state: &mut State<Self::Value>) {
+    fn super_terminator(
+        &self,
+        terminator: &Terminator<'tcx>,
+        _location: Location,
+        _state: &mut State<Self::Value>,
+    ) {
         match &terminator.kind {
             TerminatorKind::Call { .. } | TerminatorKind::InlineAsm { .. } => {
                 // Effect is applied by `handle_call_return`.
@@ -322,10 +342,10 @@ fn apply_statement_effect(
         &self,
         state: &mut Self::Domain,
         statement: &Statement<'tcx>,
-        _location: Location,
+        location: Location,
     ) {
         if state.is_reachable() {
-            self.0.handle_statement(statement, state);
+            self.0.handle_statement(statement, location, state);
         }
     }
 
@@ -333,10 +353,10 @@ fn apply_terminator_effect(
         &self,
         state: &mut Self::Domain,
         terminator: &Terminator<'tcx>,
-        _location: Location,
+        location: Location,
     ) {
         if state.is_reachable() {
-            self.0.handle_terminator(terminator, state);
+            self.0.handle_terminator(terminator, location, state);
         }
     }
 
@@ -621,8 +641,9 @@ fn register_with_filter_rec<'tcx>(
         ty: Ty<'tcx>,
         filter: &mut impl FnMut(Ty<'tcx>) -> bool,
     ) {
-        // Note: The framework supports only scalars for now.
-        if filter(ty) && ty.is_scalar() {
+        // Note: The framework supports only scalars and references for now. A reference is tracked
+        // as a value of its own, the place it points to is not tracked.
+        if filter(ty) && (ty.is_scalar() || ty.is_ref()) {
             // We know that the projection only contains trackable elements.
             let place = self.make_place(local, projection).unwrap();
 
@@ -830,13 +851,15 @@ struct Collector {
 
     impl<'tcx> Visitor<'tcx> for Collector {
         fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, _location: Location) {
-            if context.is_borrow()
+            if (context.is_borrow()
                 || context.is_address_of()
                 || context.is_drop()
-                || context == PlaceContext::MutatingUse(MutatingUseContext::AsmOutput)
+                || context == PlaceContext::MutatingUse(MutatingUseContext::AsmOutput))
+                && !place.is_indirect()
             {
                 // A pointer to a place could be used to access other places with the same local,
-                // hence we have to exclude the local completely.
+                // hence we have to exclude the local completely. A place behind a dereference is
+                // not part of the local, so reborrowing it does not give access to the local.
                 self.result[place.local] = true;
             }
         }
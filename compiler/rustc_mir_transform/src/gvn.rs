@@ -0,0 +1,463 @@
+//! Global value numbering.
+//!
+//! A dataflow analysis on top of `value_analysis` assigns a value number to the value held by
+//! every tracked place. Two rvalues that compute the same value from the same operands get the
+//! same number, so an assignment of an rvalue whose value is already held by a local is replaced
+//! with a copy of that local. Only pure rvalues are numbered: operations on scalars, lengths,
+//! casts, and loads through shared references to `Freeze` types, which cannot change while the
+//! reference is live.
+//!
+//! A value number has to stand for a single value during one execution of the body. A value
+//! that is not computed from other values is therefore named after the location at which it is
+//! first seen. In a reducible control-flow graph, a location can only be executed again after
+//! going through the header of a loop that contains it, and the join at the header forgets
+//! every value that was not held on entry to the loop (see `ValueNumber::join`).
+
+use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
+use rustc_data_structures::graph::loops::is_reducible;
+use rustc_index::vec::Idx;
+use rustc_middle::mir::visit::Visitor;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_mir_dataflow::lattice::{HasBottom, HasTop};
+use rustc_mir_dataflow::value_analysis::{
+    Map, PlaceIndex, State, TrackElem, ValueAnalysis, ValueOrPlace,
+};
+use rustc_mir_dataflow::{Analysis, JoinSemiLattice, ResultsVisitor};
+use std::cell::RefCell;
+
+use crate::MirPass;
+
+// These limits are the same as the ones of `DataflowConstProp`, whose analysis has the same
+// complexity. If `tcx.sess.mir_opt_level() >= 4`, we ignore them.
+const BLOCK_LIMIT: usize = 100;
+const PLACE_LIMIT: usize = 100;
+
+pub struct GVN;
+
+impl<'tcx> MirPass<'tcx> for GVN {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 3
+    }
+
+    #[instrument(skip_all level = "debug")]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.mir_opt_level() < 4 && body.basic_blocks.len() > BLOCK_LIMIT {
+            debug!("aborted GVN due to too many basic blocks");
+            return;
+        }
+
+        if !is_reducible(&body.basic_blocks, &body.basic_blocks.dominators()) {
+            debug!("aborted GVN due to irreducible control flow");
+            return;
+        }
+
+        let map = Map::from_filter(tcx, body, is_tracked);
+        if tcx.sess.mir_opt_level() < 4 && map.tracked_places() > PLACE_LIMIT {
+            debug!("aborted GVN due to too many tracked places");
+            return;
+        }
+
+        let replacements = {
+            let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+            let analysis = ValueNumbering {
+                tcx,
+                param_env,
+                local_decls: &body.local_decls,
+                map,
+                values: RefCell::default(),
+            };
+            let results = debug_span!("analyze")
+                .in_scope(|| analysis.wrap().into_engine(tcx, body).iterate_to_fixpoint());
+
+            let mut collector = Collector::new(&results.analysis.0);
+            debug_span!("collect").in_scope(|| results.visit_reachable_with(body, &mut collector));
+            collector.replacements
+        };
+
+        let basic_blocks = body.basic_blocks.as_mut();
+        for (location, local) in replacements {
+            let statement = &mut basic_blocks[location.block].statements[location.statement_index];
+            match &mut statement.kind {
+                StatementKind::Assign(box (_, rvalue)) => {
+                    *rvalue = Rvalue::Use(Operand::Copy(local.into()));
+                }
+                _ => bug!("found a replacement for a non-assign statement"),
+            }
+        }
+    }
+}
+
+/// References are tracked to number the loads through them, not what they point to.
+fn is_tracked(ty: Ty<'_>) -> bool {
+    ty.is_scalar() || ty.is_ref()
+}
+
+rustc_index::newtype_index!(
+    /// Identifies a value in `ValueNumbering::values`.
+    struct VnIndex {}
+);
+
+/// A value computed by the body, in terms of other values.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum Value<'tcx> {
+    /// The value of a tracked place that was unknown when the place was read at this location.
+    Unknown(Location, PlaceIndex),
+    /// The value of an rvalue at this location that cannot be numbered.
+    Opaque(Location),
+    Constant(ConstantKind<'tcx>),
+    /// The place that a shared reference to a `Freeze` type points to. It is only used to load
+    /// from it, as it cannot be modified while the reference is live.
+    Deref(VnIndex),
+    Field(VnIndex, Field),
+    /// The length of the slice a pointer points to.
+    Len(VnIndex),
+    Discriminant(VnIndex),
+    NullaryOp(NullOp, Ty<'tcx>),
+    UnaryOp(UnOp, VnIndex),
+    BinaryOp(BinOp, VnIndex, VnIndex),
+    CheckedBinaryOp(BinOp, VnIndex, VnIndex),
+    Cast(CastKind, VnIndex, Ty<'tcx>),
+}
+
+/// The value number of the value held by a tracked place.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum ValueNumber {
+    Uninit,
+    Known(VnIndex),
+    Unknown,
+}
+
+impl JoinSemiLattice for ValueNumber {
+    /// Unlike with `FlatSet`, joining an uninitialized place with a known value does not keep the
+    /// value: at a loop header, a value named after a location in the loop must be forgotten
+    /// even if the place is uninitialized on entry to the loop, as it would otherwise be confused
+    /// with the value of the next iteration.
+    fn join(&mut self, other: &Self) -> bool {
+        if self == other || *self == ValueNumber::Unknown {
+            return false;
+        }
+        *self = ValueNumber::Unknown;
+        true
+    }
+}
+
+impl HasBottom for ValueNumber {
+    /// This is not below `Known`, see `join`.
+    fn bottom() -> Self {
+        ValueNumber::Uninit
+    }
+}
+
+impl HasTop for ValueNumber {
+    fn top() -> Self {
+        ValueNumber::Unknown
+    }
+}
+
+struct ValueNumbering<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    local_decls: &'a LocalDecls<'tcx>,
+    map: Map,
+    values: RefCell<FxIndexSet<Value<'tcx>>>,
+}
+
+impl<'tcx> ValueAnalysis<'tcx> for ValueNumbering<'_, 'tcx> {
+    type Value = ValueNumber;
+
+    const NAME: &'static str = "ValueNumbering";
+
+    fn map(&self) -> &Map {
+        &self.map
+    }
+
+    fn handle_statement(
+        &self,
+        statement: &Statement<'tcx>,
+        location: Location,
+        state: &mut State<Self::Value>,
+    ) {
+        match &statement.kind {
+            StatementKind::Assign(box (target, rvalue)) => {
+                self.assign(*target, rvalue, location, state);
+            }
+            _ => self.super_statement(statement, location, state),
+        }
+    }
+
+    fn handle_terminator(
+        &self,
+        terminator: &Terminator<'tcx>,
+        location: Location,
+        state: &mut State<Self::Value>,
+    ) {
+        ForgetMoved { map: &self.map, state: &mut *state, except: None }
+            .visit_terminator(terminator, location);
+        self.super_terminator(terminator, location, state)
+    }
+}
+
+impl<'tcx> ValueNumbering<'_, 'tcx> {
+    fn insert(&self, value: Value<'tcx>) -> VnIndex {
+        VnIndex::new(self.values.borrow_mut().insert_full(value).0)
+    }
+
+    fn assign(
+        &self,
+        target: Place<'tcx>,
+        rvalue: &Rvalue<'tcx>,
+        location: Location,
+        state: &mut State<ValueNumber>,
+    ) {
+        let value = self.rvalue_value(rvalue, location, state);
+        if let Some(target_index) = self.map.find(target.as_ref()) {
+            match (value, rvalue) {
+                (None, Rvalue::Use(Operand::Copy(source) | Operand::Move(source)))
+                    if let Some(source) = self.map.find(source.as_ref()) =>
+                {
+                    // Only the tracked fields of `source` are copied.
+                    state.assign_place_idx(target_index, source, &self.map);
+                }
+                _ => {
+                    let value = value.unwrap_or_else(|| self.insert(Value::Opaque(location)));
+                    let ty = target.ty(self.local_decls, self.tcx).ty;
+                    self.assign_value(target_index, ty, value, state);
+                }
+            }
+        }
+        // The places that are moved out of may not be read anymore, so they cannot hold the value
+        // of an rvalue that is replaced.
+        ForgetMoved { map: &self.map, state, except: Some(target) }.visit_rvalue(rvalue, location);
+    }
+
+    /// Assigns `value` to the tracked place `target`, and its fields to the tracked fields of
+    /// `target`.
+    fn assign_value(
+        &self,
+        target: PlaceIndex,
+        ty: Ty<'tcx>,
+        value: VnIndex,
+        state: &mut State<ValueNumber>,
+    ) {
+        state.assign_idx(target, ValueOrPlace::Value(ValueNumber::Known(value)), &self.map);
+        if let ty::Tuple(fields) = ty.kind() {
+            for (field, ty) in fields.iter().enumerate() {
+                if let Some(child) = self.map.apply(target, TrackElem::Field(field.into())) {
+                    let value = self.insert(Value::Field(value, field.into()));
+                    self.assign_value(child, ty, value, state);
+                }
+            }
+        }
+    }
+
+    /// Returns the value number of the value held by the tracked place `place`, naming the value
+    /// after `location` if it is unknown.
+    fn tracked_value(
+        &self,
+        place: PlaceIndex,
+        location: Location,
+        state: &mut State<ValueNumber>,
+    ) -> Option<VnIndex> {
+        match state.get_idx(place, &self.map) {
+            ValueNumber::Known(value) => Some(value),
+            ValueNumber::Unknown => {
+                let value = self.insert(Value::Unknown(location, place));
+                state.assign_idx(place, ValueOrPlace::Value(ValueNumber::Known(value)), &self.map);
+                Some(value)
+            }
+            // Reading the place is UB, or the state is unreachable.
+            ValueNumber::Uninit => None,
+        }
+    }
+
+    fn place_value(
+        &self,
+        place: PlaceRef<'tcx>,
+        location: Location,
+        state: &mut State<ValueNumber>,
+    ) -> Option<VnIndex> {
+        if let Some(index) = self.map.find(place) {
+            let ty = place.ty(self.local_decls, self.tcx).ty;
+            return if is_tracked(ty) { self.tracked_value(index, location, state) } else { None };
+        }
+
+        // Otherwise, the place can only be numbered as a load through a tracked reference.
+        let deref = place.projection.iter().position(|elem| *elem == ProjectionElem::Deref)?;
+        let pointer = PlaceRef { local: place.local, projection: &place.projection[..deref] };
+        let mut value = self.place_value(pointer, location, state)?;
+        for (index, elem) in place.projection.iter().enumerate().skip(deref) {
+            let base = PlaceRef { local: place.local, projection: &place.projection[..index] };
+            let value_kind = match *elem {
+                ProjectionElem::Deref => match base.ty(self.local_decls, self.tcx).ty.kind() {
+                    ty::Ref(_, pointee, Mutability::Not)
+                        if pointee.is_freeze(self.tcx, self.param_env) =>
+                    {
+                        Value::Deref(value)
+                    }
+                    _ => return None,
+                },
+                ProjectionElem::Field(field, _) => Value::Field(value, field),
+                _ => return None,
+            };
+            value = self.insert(value_kind);
+        }
+        Some(value)
+    }
+
+    fn operand_value(
+        &self,
+        operand: &Operand<'tcx>,
+        location: Location,
+        state: &mut State<ValueNumber>,
+    ) -> Option<VnIndex> {
+        match operand {
+            Operand::Constant(constant) => Some(self.insert(Value::Constant(constant.literal))),
+            Operand::Copy(place) | Operand::Move(place) => {
+                self.place_value(place.as_ref(), location, state)
+            }
+        }
+    }
+
+    fn rvalue_value(
+        &self,
+        rvalue: &Rvalue<'tcx>,
+        location: Location,
+        state: &mut State<ValueNumber>,
+    ) -> Option<VnIndex> {
+        let value = match rvalue {
+            Rvalue::Use(operand) => return self.operand_value(operand, location, state),
+            Rvalue::CopyForDeref(place) => {
+                return self.place_value(place.as_ref(), location, state)
+            }
+            Rvalue::Ref(_, BorrowKind::Shared, place) => {
+                // A shared reborrow of a shared reference points to the same place.
+                let (pointer, ProjectionElem::Deref) = place.as_ref().last_projection()? else {
+                    return None;
+                };
+                let ty::Ref(_, _, Mutability::Not) =
+                    pointer.ty(self.local_decls, self.tcx).ty.kind()
+                else {
+                    return None;
+                };
+                return self.place_value(pointer, location, state);
+            }
+            Rvalue::Len(place) => {
+                let (pointer, ProjectionElem::Deref) = place.as_ref().last_projection()? else {
+                    return None;
+                };
+                Value::Len(self.place_value(pointer, location, state)?)
+            }
+            Rvalue::Discriminant(place) => {
+                Value::Discriminant(self.place_value(place.as_ref(), location, state)?)
+            }
+            Rvalue::NullaryOp(op, ty) => Value::NullaryOp(*op, *ty),
+            Rvalue::UnaryOp(op, operand) => {
+                Value::UnaryOp(*op, self.operand_value(operand, location, state)?)
+            }
+            Rvalue::BinaryOp(op, box (lhs, rhs)) => {
+                let lhs = self.operand_value(lhs, location, state);
+                let rhs = self.operand_value(rhs, location, state);
+                Value::BinaryOp(*op, lhs?, rhs?)
+            }
+            Rvalue::CheckedBinaryOp(op, box (lhs, rhs)) => {
+                let lhs = self.operand_value(lhs, location, state);
+                let rhs = self.operand_value(rhs, location, state);
+                Value::CheckedBinaryOp(*op, lhs?, rhs?)
+            }
+            Rvalue::Cast(kind, operand, ty) => match kind {
+                CastKind::PointerExposeAddress
+                | CastKind::PointerFromExposedAddress
+                | CastKind::DynStar => return None,
+                _ => Value::Cast(*kind, self.operand_value(operand, location, state)?, *ty),
+            },
+            Rvalue::Ref(..)
+            | Rvalue::AddressOf(..)
+            | Rvalue::Repeat(..)
+            | Rvalue::ThreadLocalRef(..)
+            | Rvalue::Aggregate(..)
+            | Rvalue::ShallowInitBox(..) => return None,
+        };
+        Some(self.insert(value))
+    }
+}
+
+/// Forgets the values of the tracked places that are moved out of, except for `except`.
+struct ForgetMoved<'a, 'tcx> {
+    map: &'a Map,
+    state: &'a mut State<ValueNumber>,
+    except: Option<Place<'tcx>>,
+}
+
+impl<'tcx> Visitor<'tcx> for ForgetMoved<'_, 'tcx> {
+    fn visit_operand(&mut self, operand: &Operand<'tcx>, _location: Location) {
+        match operand {
+            Operand::Move(place) if Some(*place) != self.except => {
+                self.state.flood(place.as_ref(), self.map);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Collects the assignments whose rvalue computes a value that a local already holds.
+struct Collector<'a, 'tcx> {
+    analysis: &'a ValueNumbering<'a, 'tcx>,
+    /// The tracked locals that an rvalue can be replaced with a copy of.
+    candidates: Vec<(Local, PlaceIndex)>,
+    replacements: FxHashMap<Location, Local>,
+}
+
+impl<'a, 'tcx> Collector<'a, 'tcx> {
+    fn new(analysis: &'a ValueNumbering<'a, 'tcx>) -> Self {
+        let candidates = analysis
+            .local_decls
+            .iter_enumerated()
+            .filter(|(_, decl)| decl.ty.is_scalar() && !decl.ty.is_any_ptr())
+            .filter_map(|(local, _)| Some((local, analysis.map.find(Place::from(local).as_ref())?)))
+            .collect();
+        Self { analysis, candidates, replacements: FxHashMap::default() }
+    }
+}
+
+impl<'mir, 'tcx> ResultsVisitor<'mir, 'tcx> for Collector<'_, 'tcx> {
+    type FlowState = State<ValueNumber>;
+
+    fn visit_statement_before_primary_effect(
+        &mut self,
+        state: &Self::FlowState,
+        statement: &'mir Statement<'tcx>,
+        location: Location,
+    ) {
+        let StatementKind::Assign(box (target, rvalue)) = &statement.kind else { return };
+        match rvalue {
+            // A copy is what a replacement would be. Pointers are not replaced, as they would
+            // be derived from a different borrow.
+            Rvalue::Use(..)
+            | Rvalue::CopyForDeref(..)
+            | Rvalue::Ref(..)
+            | Rvalue::AddressOf(..) => {
+                return;
+            }
+            _ => (),
+        }
+        let analysis = self.analysis;
+        let ty = target.ty(analysis.local_decls, analysis.tcx).ty;
+        if ty.is_any_ptr() {
+            return;
+        }
+
+        // Numbering the rvalue may name the unknown values of its operands.
+        let mut state = state.clone();
+        let Some(value) = analysis.rvalue_value(rvalue, location, &mut state) else { return };
+        let candidate = self.candidates.iter().find(|&&(local, index)| {
+            local != target.local
+                && analysis.local_decls[local].ty == ty
+                && state.get_idx(index, &analysis.map) == ValueNumber::Known(value)
+        });
+        if let Some(&(local, _)) = candidate {
+            debug!(?location, ?rvalue, ?local, "replacing with a copy");
+            self.replacements.insert(location, local);
+        }
+    }
+}
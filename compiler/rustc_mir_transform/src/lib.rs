@@ -69,8 +69,10 @@
 mod ffi_unwind_calls;
 mod function_item_references;
 mod generator;
+mod gvn;
 mod inline;
 mod instcombine;
+mod loop_optimization;
 mod lower_intrinsics;
 mod lower_slice_len;
 mod match_branches;
@@ -574,6 +576,9 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             // Const-prop runs unconditionally, but doesn't mutate the MIR at mir-opt-level=0.
             &const_debuginfo::ConstDebugInfo,
             &o1(simplify_branches::SimplifyConstCondition::new("after-const-prop")),
+            &gvn::GVN,
+            &loop_optimization::LoopInvariantCodeMotion,
+            &loop_optimization::BoundsCheckElimination,
             &early_otherwise_branch::EarlyOtherwiseBranch,
             &simplify_comparison_integral::SimplifyComparisonIntegral,
             &simplify_try::SimplifyArmIdentity,
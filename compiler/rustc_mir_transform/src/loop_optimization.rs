@@ -0,0 +1,498 @@
+//! Optimizations of the natural loops of a body, found on its dominator tree:
+//!
+//! - `LoopInvariantCodeMotion` moves the computations whose operands do not change in a loop to
+//!   the block that enters it, so they are done once instead of on every iteration.
+//! - `BoundsCheckElimination` removes the bounds checks whose index is known to be in bounds,
+//!   because the loop condition (or any other dominating branch) already compared it to the
+//!   length, like in `while i < v.len() { v[i] }`.
+//!
+//! Both only reason about locals that cannot be changed through a pointer, i.e. that are never
+//! borrowed.
+
+use crate::MirPass;
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_data_structures::graph::loops::{loops, Loop};
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, TyCtxt};
+
+pub struct LoopInvariantCodeMotion;
+
+impl<'tcx> MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 3
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let loops = loops(&body.basic_blocks, &body.basic_blocks.dominators());
+        if loops.is_empty() {
+            return;
+        }
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let reverse_postorder: Vec<_> =
+            body.basic_blocks.postorder().iter().rev().copied().collect();
+
+        // Inner loops come first, so what is hoisted out of them can be hoisted out of the loops
+        // they are nested in as well.
+        let mut hoisted = BitSet::new_empty(body.local_decls.len());
+        for l in &loops {
+            let Some(preheader) = preheader(body, l) else { continue };
+            let defs = Defs::new(body);
+            let mut hoisted_here = BitSet::new_empty(body.local_decls.len());
+            let is_invariant = |local: Local, hoisted_here: &BitSet<Local>| {
+                hoisted_here.contains(local)
+                    || (!defs.escaped.contains(local)
+                        && defs.locations[local].iter().all(|location| !l.contains(location.block)))
+            };
+
+            let mut moved = vec![];
+            for &block in reverse_postorder.iter().filter(|&&block| l.contains(block)) {
+                let data = &body.basic_blocks[block];
+                if data.is_cleanup {
+                    continue;
+                }
+                for (statement_index, statement) in data.statements.iter().enumerate() {
+                    let StatementKind::Assign(box (place, rvalue)) = &statement.kind else {
+                        continue;
+                    };
+                    let Some(local) = place.as_local() else { continue };
+                    // The value of a local that is assigned once, and only used after that
+                    // assignment, is the same wherever the assignment is done.
+                    if local.index() > body.arg_count
+                        && defs.single_def(local).is_some()
+                        && body.local_decls[local].ty.is_copy_modulo_regions(tcx, param_env)
+                        && is_hoistable(body, rvalue, |local| is_invariant(local, &hoisted_here))
+                    {
+                        hoisted_here.insert(local);
+                        moved.push(Location { block, statement_index });
+                    }
+                }
+            }
+
+            let basic_blocks = body.basic_blocks.as_mut_preserves_cfg();
+            for location in moved {
+                let statement =
+                    basic_blocks[location.block].statements[location.statement_index].replace_nop();
+                basic_blocks[preheader].statements.push(statement);
+            }
+            hoisted.union(&hoisted_here);
+        }
+
+        if !hoisted.is_empty() {
+            HoistedLocals { tcx, hoisted }.visit_body_preserves_cfg(body);
+        }
+    }
+}
+
+/// Returns the only block outside of `l` that enters it, if it does so with a `goto`, so the
+/// statements hoisted out of the loop can be appended to it.
+fn preheader(body: &Body<'_>, l: &Loop<BasicBlock>) -> Option<BasicBlock> {
+    let mut outside =
+        body.basic_blocks.predecessors()[l.header].iter().filter(|&&block| !l.contains(block));
+    let preheader = *outside.next()?;
+    if outside.next().is_some() {
+        return None;
+    }
+    let data = &body.basic_blocks[preheader];
+    let is_goto = matches!(data.terminator().kind, TerminatorKind::Goto { .. });
+    (is_goto && !data.is_cleanup).then_some(preheader)
+}
+
+/// Returns whether `rvalue` only reads invariant locals, and can be evaluated even where the loop
+/// would not have, as it cannot panic or be UB.
+fn is_hoistable<'tcx>(
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    is_invariant: impl Fn(Local) -> bool,
+) -> bool {
+    let is_invariant_operand = |operand: &Operand<'tcx>| match operand {
+        Operand::Constant(_) => true,
+        Operand::Copy(place) | Operand::Move(place) => {
+            place.as_local().map_or(false, &is_invariant)
+        }
+    };
+    match rvalue {
+        Rvalue::Use(operand)
+        | Rvalue::UnaryOp(_, operand)
+        | Rvalue::Cast(
+            CastKind::IntToInt
+            | CastKind::IntToFloat
+            | CastKind::FloatToInt
+            | CastKind::FloatToFloat,
+            operand,
+            _,
+        ) => is_invariant_operand(operand),
+        // Division by zero and out of bounds offsets are UB, and the checks that exclude them
+        // stay in the loop.
+        Rvalue::BinaryOp(op, box (lhs, rhs)) | Rvalue::CheckedBinaryOp(op, box (lhs, rhs)) => {
+            !matches!(op, BinOp::Div | BinOp::Rem | BinOp::Shl | BinOp::Shr | BinOp::Offset)
+                && is_invariant_operand(lhs)
+                && is_invariant_operand(rhs)
+        }
+        // The length of an array is a constant, and the one of a slice behind a reference
+        // only changes with the reference.
+        Rvalue::Len(place) => match place.as_ref() {
+            PlaceRef { local, projection: [] } => body.local_decls[local].ty.is_array(),
+            PlaceRef { local, projection: [ProjectionElem::Deref] } => {
+                body.local_decls[local].ty.is_ref() && is_invariant(local)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Makes the locals that were hoisted out of a loop live for the whole body, and copies them
+/// instead of moving them, as they are now used by every iteration.
+struct HoistedLocals<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    hoisted: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for HoistedLocals<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        if let StatementKind::StorageLive(local) | StatementKind::StorageDead(local) =
+            statement.kind
+            && self.hoisted.contains(local)
+        {
+            statement.make_nop();
+            return;
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Move(place) = *operand
+            && !place.is_indirect()
+            && self.hoisted.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+        self.super_operand(operand, location);
+    }
+}
+
+pub struct BoundsCheckElimination;
+
+impl<'tcx> MirPass<'tcx> for BoundsCheckElimination {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 3
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let defs = Defs::new(body);
+        let dominators = body.basic_blocks.dominators();
+        let ranges = RangeAnalysis { tcx, param_env, body, defs: &defs, dominators: &dominators };
+
+        let mut in_bounds = vec![];
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            if let TerminatorKind::Assert {
+                cond,
+                expected: true,
+                msg: AssertKind::BoundsCheck { .. },
+                target,
+                ..
+            } = &data.terminator().kind
+                && dominators.is_reachable(block)
+                && let Some(cond) = cond.place().and_then(|place| place.as_local())
+                && ranges.is_always_true(block, cond)
+            {
+                in_bounds.push((block, *target));
+            }
+        }
+
+        for (block, target) in in_bounds {
+            debug!("removing the bounds check of {:?}", block);
+            body.basic_blocks_mut()[block].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// A length, as far as it can be compared to other lengths.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum LenValue {
+    Const(u64),
+    /// The length of the slice behind a reference that never changes.
+    Slice(Local),
+    /// The value of a local at some point, which is only known to be the same as the value of
+    /// the same local at another point if it was not changed in between.
+    Local(Local),
+}
+
+struct RangeAnalysis<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    defs: &'a Defs,
+    dominators: &'a Dominators<BasicBlock>,
+}
+
+impl<'a, 'tcx> RangeAnalysis<'a, 'tcx> {
+    /// Returns whether the comparison `cond = Lt(index, len)` of the bounds check at the end of
+    /// `block` is always true, as the branch that leads to it was only taken if
+    /// `index < bound`, where `bound <= len`.
+    fn is_always_true(&self, block: BasicBlock, cond: Local) -> bool {
+        let end = self.body.basic_blocks[block].statements.len();
+        let Some((cond_index, Rvalue::BinaryOp(BinOp::Lt, box (index, len)))) =
+            self.last_assignment(block, cond, end)
+        else {
+            return false;
+        };
+        let (Some(index), Some(len)) =
+            (index.place().and_then(|place| place.as_local()), self.len_value(len))
+        else {
+            return false;
+        };
+
+        // Look for the `switchInt` on `index < bound` whose `true` edge is the only way to
+        // the bounds check, which has to be the only edge to a block that dominates it.
+        for check_target in self.dominators.dominators(block) {
+            let [switch] = self.body.basic_blocks.predecessors()[check_target][..] else {
+                continue;
+            };
+            let TerminatorKind::SwitchInt { discr, targets } =
+                &self.body.basic_blocks[switch].terminator().kind
+            else {
+                continue;
+            };
+            let mut values = targets.iter();
+            let (Some((0, false_target)), None) = (values.next(), values.next()) else {
+                continue;
+            };
+            if targets.otherwise() != check_target || false_target == check_target {
+                continue;
+            }
+            let Some(check) = discr.place().and_then(|place| place.as_local()) else { continue };
+            let switch_end = self.body.basic_blocks[switch].statements.len();
+            let Some((check_index, Rvalue::BinaryOp(BinOp::Lt, box (checked, bound)))) =
+                self.last_assignment(switch, check, switch_end)
+            else {
+                continue;
+            };
+            let Some(checked) = checked.place().and_then(|place| place.as_local()) else {
+                continue;
+            };
+            let Some(bound) = self.len_value(bound) else { continue };
+
+            // The index may have been copied to temporaries before being compared.
+            let (checked, checked_from) = self.copied_local(
+                checked,
+                Location { block: switch, statement_index: check_index },
+                switch,
+            );
+            let (index, _) = self.copied_local(
+                index,
+                Location { block, statement_index: cond_index },
+                check_target,
+            );
+            if checked != index {
+                continue;
+            }
+
+            let between = self.blocks_between(check_target, block);
+            let is_unchanged = |local: Local, from: usize| {
+                !self.defs.escaped.contains(local)
+                    && self.defs.locations[local].iter().all(|location| {
+                        !between.contains(location.block)
+                            && !(location.block == switch && location.statement_index >= from)
+                    })
+            };
+            let bound_fits = match (bound, len) {
+                (LenValue::Const(bound), LenValue::Const(len)) => bound <= len,
+                (LenValue::Slice(bound), LenValue::Slice(len)) => bound == len,
+                (LenValue::Local(bound), LenValue::Local(len)) => {
+                    bound == len && is_unchanged(bound, check_index)
+                }
+                _ => false,
+            };
+            if bound_fits && is_unchanged(index, checked_from) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the last statement of `block` before `end` that assigns `local`, if it is
+    /// not changed in any other way after it.
+    fn last_assignment(
+        &self,
+        block: BasicBlock,
+        local: Local,
+        end: usize,
+    ) -> Option<(usize, &'a Rvalue<'tcx>)> {
+        let last = self.defs.locations[local]
+            .iter()
+            .filter(|location| location.block == block && location.statement_index < end)
+            .map(|location| location.statement_index)
+            .max()?;
+        match &self.body.basic_blocks[block].statements[last].kind {
+            StatementKind::Assign(box (place, rvalue)) if place.as_local() == Some(local) => {
+                Some((last, rvalue))
+            }
+            _ => None,
+        }
+    }
+
+    /// Follows the copies that `local` was assigned from, as long as they dominate `at` and are
+    /// dominated by `window`, and returns the local that was copied first, along with the
+    /// index of the statement that copied it if it is in `window`.
+    fn copied_local(
+        &self,
+        mut local: Local,
+        mut at: Location,
+        window: BasicBlock,
+    ) -> (Local, usize) {
+        while let Some(def) = self.defs.single_def(local)
+            && local.index() > self.body.arg_count
+            && self.dominators.is_dominated_by(def.block, window)
+            && def.dominates(at, self.dominators)
+            && def != at
+            && let Some(Statement {
+                kind:
+                    StatementKind::Assign(box (
+                        _,
+                        Rvalue::Use(Operand::Copy(source) | Operand::Move(source)),
+                    )),
+                ..
+            }) = self.body.stmt_at(def).left()
+            && let Some(source) = source.as_local()
+        {
+            local = source;
+            at = def;
+        }
+        let from = if at.block == window { at.statement_index } else { 0 };
+        (local, from)
+    }
+
+    /// Returns the blocks on the paths from `from` to `to` that do not go through `from` again.
+    fn blocks_between(&self, from: BasicBlock, to: BasicBlock) -> BitSet<BasicBlock> {
+        let mut blocks = BitSet::new_empty(self.body.basic_blocks.len());
+        blocks.insert(from);
+        let mut stack = vec![];
+        if blocks.insert(to) {
+            stack.push(to);
+        }
+        while let Some(block) = stack.pop() {
+            for &predecessor in &self.body.basic_blocks.predecessors()[block] {
+                if blocks.insert(predecessor) {
+                    stack.push(predecessor);
+                }
+            }
+        }
+        blocks
+    }
+
+    fn len_value(&self, operand: &Operand<'tcx>) -> Option<LenValue> {
+        let local = match operand {
+            Operand::Constant(constant) => {
+                return constant
+                    .literal
+                    .try_eval_usize(self.tcx, self.param_env)
+                    .map(LenValue::Const);
+            }
+            Operand::Copy(place) | Operand::Move(place) => place.as_local()?,
+        };
+        if self.defs.escaped.contains(local) {
+            return None;
+        }
+        if local.index() > self.body.arg_count
+            && let Some(def) = self.defs.single_def(local)
+        {
+            match self.body.stmt_at(def).left().map(|statement| &statement.kind) {
+                Some(StatementKind::Assign(box (_, Rvalue::Len(place)))) => {
+                    return self.len_of_place(*place);
+                }
+                Some(StatementKind::Assign(box (_, Rvalue::Use(operand)))) => {
+                    return self.len_value(operand);
+                }
+                _ => {}
+            }
+        }
+        Some(LenValue::Local(local))
+    }
+
+    fn len_of_place(&self, place: Place<'tcx>) -> Option<LenValue> {
+        if let ty::Array(_, len) = place.ty(self.body, self.tcx).ty.kind() {
+            return len.try_eval_usize(self.tcx, self.param_env).map(LenValue::Const);
+        }
+        match place.as_ref() {
+            PlaceRef { local, projection: [ProjectionElem::Deref] } => {
+                self.fixed_reference(local).map(LenValue::Slice)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the argument that `local` is a copy or a reborrow of, if that argument is never
+    /// changed, so the slice `local` points to always has the same length.
+    fn fixed_reference(&self, local: Local) -> Option<Local> {
+        if self.defs.escaped.contains(local) {
+            return None;
+        }
+        if local.index() <= self.body.arg_count {
+            return self.defs.locations[local].is_empty().then_some(local);
+        }
+        let def = self.defs.single_def(local)?;
+        match &self.body.stmt_at(def).left()?.kind {
+            StatementKind::Assign(box (_, Rvalue::Ref(_, _, source)))
+                if let [ProjectionElem::Deref] = source.projection[..] =>
+            {
+                self.fixed_reference(source.local)
+            }
+            StatementKind::Assign(box (
+                _,
+                Rvalue::Use(Operand::Copy(source) | Operand::Move(source))
+                | Rvalue::CopyForDeref(source),
+            )) => self.fixed_reference(source.as_local()?),
+            _ => None,
+        }
+    }
+}
+
+/// Where every local is changed, and which locals may be changed through a pointer.
+struct Defs {
+    locations: IndexVec<Local, Vec<Location>>,
+    escaped: BitSet<Local>,
+}
+
+impl Defs {
+    fn new(body: &Body<'_>) -> Defs {
+        let mut defs = Defs {
+            locations: IndexVec::from_elem(Vec::new(), &body.local_decls),
+            escaped: BitSet::new_empty(body.local_decls.len()),
+        };
+        defs.visit_body(body);
+        defs
+    }
+
+    /// Returns the only location where `local` is changed, if it cannot be changed through a
+    /// pointer. This does not take into account that arguments are assigned on entry.
+    fn single_def(&self, local: Local) -> Option<Location> {
+        match self.locations[local][..] {
+            [location] if !self.escaped.contains(local) => Some(location),
+            _ => None,
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for Defs {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Changing or borrowing what a local points to does not change the local itself.
+        if place.is_indirect() {
+            return;
+        }
+        if context.is_borrow() || context.is_address_of() {
+            self.escaped.insert(place.local);
+        }
+        if context.is_mutating_use() {
+            self.locations[place.local].push(location);
+        }
+    }
+}
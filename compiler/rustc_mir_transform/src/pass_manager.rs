@@ -1,6 +1,7 @@
 use rustc_middle::mir::{self, Body, MirPhase, RuntimePhase};
 use rustc_middle::ty::TyCtxt;
-use rustc_session::Session;
+use rustc_session::{MirBodySize, Session};
+use std::time::Instant;
 
 use crate::{validate, MirPass};
 
@@ -93,6 +94,7 @@ fn run_passes_inner<'tcx>(
     let validate = validate_each & tcx.sess.opts.unstable_opts.validate_mir & !body.should_skip();
     let overridden_passes = &tcx.sess.opts.unstable_opts.mir_enable_passes;
     trace!(?overridden_passes);
+    let record_stats = tcx.sess.opts.unstable_opts.mir_opt_stats.is_some();
 
     if !body.should_skip() {
         for pass in passes {
@@ -121,7 +123,15 @@ fn run_passes_inner<'tcx>(
                 validate_body(tcx, body, format!("before pass {}", name));
             }
 
-            pass.run_pass(tcx, body);
+            if record_stats {
+                let before = mir_body_size(body);
+                let start = Instant::now();
+                pass.run_pass(tcx, body);
+                let time = start.elapsed();
+                tcx.sess.code_stats.record_mir_pass(&name, before, mir_body_size(body), time);
+            } else {
+                pass.run_pass(tcx, body);
+            }
 
             if dump_enabled {
                 dump_mir_for_pass(tcx, body, &name, true);
@@ -151,6 +161,14 @@ fn run_passes_inner<'tcx>(
     }
 }
 
+fn mir_body_size(body: &Body<'_>) -> MirBodySize {
+    MirBodySize {
+        statements: body.basic_blocks.iter().map(|data| data.statements.len()).sum(),
+        locals: body.local_decls.len(),
+        blocks: body.basic_blocks.len(),
+    }
+}
+
 pub fn validate_body<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>, when: String) {
     validate::Validator { when, mir_phase: body.phase }.run_pass(tcx, body);
 }
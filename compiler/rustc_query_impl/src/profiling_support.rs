@@ -216,7 +216,23 @@ pub(crate) fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
     C::Key: Debug + Clone,
 {
     tcx.prof.with_profiler(|profiler| {
-        let event_id_builder = profiler.event_id_builder();
+        // For `-Z self-profile-summary`, the in-memory events need the query
+        // names, and the keys of the invocations that are reported individually.
+        // As below, the query cache must not stay locked while formatting keys.
+        if profiler.event_log_enabled() {
+            let mut query_keys_and_indices = Vec::new();
+            query_cache.iter(&mut |k, _, i| query_keys_and_indices.push((k.clone(), i)));
+
+            for (query_key, dep_node_index) in query_keys_and_indices {
+                let query_invocation_id = dep_node_index.into();
+                if profiler.record_query_invocation(query_invocation_id, query_name) {
+                    profiler.record_query_key(query_invocation_id, format!("{:?}", query_key));
+                }
+            }
+        }
+
+        // Without `-Z self-profile`, no raw profile data is written.
+        let Some(event_id_builder) = profiler.event_id_builder() else { return };
 
         // Walk the entire query cache and allocate the appropriate
         // string representations. Each cache entry is uniquely
@@ -96,6 +96,11 @@ struct DepGraphData<K: DepKind> {
     /// a particular query result was decoded from disk
     /// (not just marked green)
     debug_loaded_from_disk: Lock<FxHashSet<DepNode<K>>>,
+
+    /// With `-Zincremental-why`, the dependency that prevented each node of
+    /// the previous session from being marked green, i.e. the first of its
+    /// dependencies that turned out red (or could not be forced).
+    red_causes: Option<Lock<FxHashMap<SerializedDepNodeIndex, SerializedDepNodeIndex>>>,
 }
 
 pub fn hash_result<R>(hcx: &mut StableHashingContext<'_>, result: &R) -> Fingerprint
@@ -115,6 +120,7 @@ pub fn new(
         encoder: FileEncoder,
         record_graph: bool,
         record_stats: bool,
+        record_red_causes: bool,
     ) -> DepGraph<K> {
         let prev_graph_node_count = prev_graph.node_count();
 
@@ -158,6 +164,7 @@ pub fn new(
                 previous: prev_graph,
                 colors,
                 debug_loaded_from_disk: Default::default(),
+                red_causes: record_red_causes.then(Default::default),
             })),
             virtual_dep_node_index: Lrc::new(AtomicU32::new(0)),
         }
@@ -797,7 +804,12 @@ fn try_mark_previous_green<Qcx: QueryContext<DepKind = K>>(
         let prev_deps = data.previous.edge_targets_from(prev_dep_node_index);
 
         for &dep_dep_node_index in prev_deps {
-            self.try_mark_parent_green(qcx, data, dep_dep_node_index, dep_node)?
+            if self.try_mark_parent_green(qcx, data, dep_dep_node_index, dep_node).is_none() {
+                if let Some(red_causes) = &data.red_causes {
+                    red_causes.lock().insert(prev_dep_node_index, dep_dep_node_index);
+                }
+                return None;
+            }
         }
 
         // If we got here without hitting a `return` that means that all
@@ -916,6 +928,52 @@ pub fn print_incremental_info(&self) {
         }
     }
 
+    /// Explains why nodes of the previous session had to be re-executed, if the dep-graph was
+    /// created with `record_red_causes`.
+    ///
+    /// For each node that could not be marked green, the chain of first red dependencies is
+    /// followed down to a node that was not re-executed because of a dependency, which usually
+    /// is an input, i.e. an `eval_always` node. Inputs like `hir_crate` depend on the whole
+    /// crate, so the root cause is the deepest node on the chain which is not an input itself
+    /// and whose value changed, like the `hir_owner` that was edited.
+    pub fn rebuild_causes<Tcx: DepContext<DepKind = K>>(
+        &self,
+        tcx: Tcx,
+    ) -> Option<RebuildCauses<K>> {
+        let data = self.data.as_ref()?;
+        let red_causes = data.red_causes.as_ref()?.lock();
+
+        let reexecuted = red_causes
+            .iter()
+            .map(|(&prev_index, &first_red)| {
+                let mut chain = vec![first_red];
+                while let Some(&next) = red_causes.get(chain.last().unwrap()) {
+                    chain.push(next);
+                }
+
+                let bottom = *chain.last().unwrap();
+                let changed = chain
+                    .iter()
+                    .rev()
+                    .copied()
+                    .find(|&index| {
+                        !tcx.is_eval_always(data.previous.index_to_node(index).kind)
+                            && data.colors.get(index) == Some(DepNodeColor::Red)
+                    })
+                    .unwrap_or(bottom);
+
+                ReexecutedNode {
+                    node: data.previous.index_to_node(prev_index),
+                    first_red: data.previous.index_to_node(first_red),
+                    changed: data.previous.index_to_node(changed),
+                    input: (changed != bottom).then(|| data.previous.index_to_node(bottom)),
+                }
+            })
+            .collect();
+
+        Some(RebuildCauses { previous_node_count: data.previous.node_count(), reexecuted })
+    }
+
     pub fn encode(&self, profiler: &SelfProfilerRef) -> FileEncodeResult {
         if let Some(data) = &self.data {
             data.current.encoder.steal().finish(profiler)
@@ -930,6 +988,25 @@ pub(crate) fn next_virtual_depnode_index(&self) -> DepNodeIndex {
     }
 }
 
+/// Why nodes of the previous session were re-executed, see [`DepGraph::rebuild_causes`].
+pub struct RebuildCauses<K: DepKind> {
+    /// The number of nodes in the previous dep-graph. If this is zero, there
+    /// was no previous session to reuse anything from.
+    pub previous_node_count: usize,
+    pub reexecuted: Vec<ReexecutedNode<K>>,
+}
+
+/// A node of the previous session that could not be marked green.
+pub struct ReexecutedNode<K: DepKind> {
+    pub node: DepNode<K>,
+    /// The first dependency of `node` that was found red, or could not be forced.
+    pub first_red: DepNode<K>,
+    /// The root cause: the deepest node, following first red dependencies, whose value changed.
+    pub changed: DepNode<K>,
+    /// The input at the bottom of the chain, if it is not `changed` itself.
+    pub input: Option<DepNode<K>>,
+}
+
 /// A "work product" is an intermediate result that we save into the
 /// incremental directory for later re-use. The primary example are
 /// the object files that we save for each partition at code
@@ -3,11 +3,15 @@
 use rustc_span::{Span, Symbol};
 
 #[derive(Subdiagnostic)]
-#[note(query_system_cycle_stack_middle)]
-pub struct CycleStack {
-    #[primary_span]
-    pub span: Span,
-    pub desc: String,
+pub enum CycleStack {
+    #[note(query_system_cycle_stack_middle)]
+    Frame {
+        #[primary_span]
+        span: Span,
+        desc: String,
+    },
+    #[note(query_system_cycle_stack_repeat)]
+    Repeat { len: usize, more: usize },
 }
 
 #[derive(Copy, Clone)]
@@ -59,6 +63,13 @@ pub struct Cycle {
     pub cycle_usage: Option<CycleUsage>,
 }
 
+#[derive(Diagnostic)]
+#[diag(query_system_cycle_graph_write_failed)]
+pub struct CycleGraphWriteFailed<'a> {
+    pub path: &'a std::path::Path,
+    pub error: std::io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(query_system_reentrant)]
 pub struct Reentrant;
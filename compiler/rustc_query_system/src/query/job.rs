@@ -1,7 +1,8 @@
-use crate::error::CycleStack;
+use crate::error::{CycleGraphWriteFailed, CycleStack};
 use crate::query::plumbing::CycleError;
 use crate::query::{QueryContext, QueryStackFrame};
 
+use rustc_data_structures::compact_stack::{compact_stack, StackGraph, StackSegment};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::{
     Diagnostic, DiagnosticBuilder, ErrorGuaranteed, Handler, IntoDiagnostic, Level,
@@ -562,10 +563,52 @@ pub(crate) fn report_cycle<'a>(
     use crate::error::StackCount;
     let stack_count = if stack.len() == 1 { StackCount::Single } else { StackCount::Multiple };
 
-    for i in 1..stack.len() {
+    let frame = |i: usize| {
         let query = &stack[i].query;
         let span = query.default_span(stack[(i + 1) % stack.len()].span);
-        cycle_stack.push(CycleStack { span, desc: query.description.to_owned() });
+        CycleStack::Frame { span, desc: query.description.to_owned() }
+    };
+    if sess.opts.unstable_opts.compact_cycles {
+        // Queries repeat with different keys, e.g. when the cycle goes through nested types, so
+        // the frames are compared by the kind of query and of the item it is about. The last
+        // repetition is kept as well if it is about other items than the first one, so that the
+        // items at both ends of the repeating part are named.
+        let keys: Vec<_> =
+            stack[1..].iter().map(|entry| (entry.query.name, entry.query.def_kind)).collect();
+        for segment in compact_stack(&keys) {
+            match segment {
+                StackSegment::Frame(i) => cycle_stack.push(frame(i + 1)),
+                StackSegment::Repeated { start, len, more } => {
+                    let first = start + 1..start + 1 + len;
+                    let last = first.start + len * more..first.end + len * more;
+                    let last_differs = first.clone().zip(last.clone()).any(|(first, last)| {
+                        stack[first].query.description != stack[last].query.description
+                    });
+                    cycle_stack.extend(first.map(frame));
+                    if !last_differs {
+                        cycle_stack.push(CycleStack::Repeat { len, more });
+                    } else {
+                        if more > 1 {
+                            cycle_stack.push(CycleStack::Repeat { len, more: more - 1 });
+                        }
+                        cycle_stack.extend(last.map(frame));
+                    }
+                }
+            }
+        }
+    } else {
+        cycle_stack.extend((1..stack.len()).map(frame));
+    }
+
+    if let Some(path) = &sess.opts.unstable_opts.dump_cycle_graph {
+        let labels: Vec<_> = stack
+            .iter()
+            .map(|entry| format!("[{}] {}", entry.query.name, entry.query.description))
+            .collect();
+        let graph = StackGraph { name: "query_cycle", labels: &labels, is_cycle: true };
+        if let Err(error) = graph.write_graphviz(path) {
+            sess.emit_warning(CycleGraphWriteFailed { path, error });
+        }
     }
 
     let mut cycle_usage = None;
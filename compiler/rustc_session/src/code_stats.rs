@@ -1,8 +1,10 @@
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::profiling::duration_to_secs_str;
 use rustc_data_structures::sync::Lock;
 use rustc_span::Symbol;
 use rustc_target::abi::{Align, Size};
 use std::cmp::{self, Ordering};
+use std::time::Duration;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -47,9 +49,31 @@ pub struct TypeSizeInfo {
     pub variants: Vec<VariantInfo>,
 }
 
+/// The size of a MIR body, as seen by `-Z mir-opt-stats`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MirBodySize {
+    pub statements: usize,
+    pub locals: usize,
+    pub blocks: usize,
+}
+
+/// The effect of a MIR pass, summed over every body it ran on.
+#[derive(Clone, Default, Debug)]
+pub struct MirPassStats {
+    pub runs: usize,
+    pub statements_before: usize,
+    pub statements_after: usize,
+    pub locals_before: usize,
+    pub locals_after: usize,
+    pub blocks_before: usize,
+    pub blocks_after: usize,
+    pub time: Duration,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    mir_passes: Lock<FxHashMap<String, MirPassStats>>,
 }
 
 impl CodeStats {
@@ -180,4 +204,82 @@ pub fn print_type_sizes(&self) {
             }
         }
     }
+
+    pub fn record_mir_pass(
+        &self,
+        pass: &str,
+        before: MirBodySize,
+        after: MirBodySize,
+        time: Duration,
+    ) {
+        let mut mir_passes = self.mir_passes.borrow_mut();
+        // Only allocate the name the first time the pass runs, not for every body.
+        if !mir_passes.contains_key(pass) {
+            mir_passes.insert(pass.to_string(), MirPassStats::default());
+        }
+        let stats = mir_passes.get_mut(pass).unwrap();
+        stats.runs += 1;
+        stats.statements_before += before.statements;
+        stats.statements_after += after.statements;
+        stats.locals_before += before.locals;
+        stats.locals_after += after.locals;
+        stats.blocks_before += before.blocks;
+        stats.blocks_after += after.blocks;
+        stats.time += time;
+    }
+
+    /// Returns the statistics of every MIR pass that ran, slowest first.
+    pub fn mir_pass_stats(&self) -> Vec<(String, MirPassStats)> {
+        let mut sorted: Vec<_> = self
+            .mir_passes
+            .borrow()
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.clone()))
+            .collect();
+        sorted.sort_by(|(name1, stats1), (name2, stats2)| {
+            stats2.time.cmp(&stats1.time).then_with(|| name1.cmp(name2))
+        });
+        sorted
+    }
+
+    pub fn print_mir_opt_stats(&self, crate_name: &str) {
+        fn change(before: usize, after: usize) -> String {
+            match after.cmp(&before) {
+                Ordering::Less => format!("-{}", before - after),
+                Ordering::Equal => "0".to_string(),
+                Ordering::Greater => format!("+{}", after - before),
+            }
+        }
+
+        let passes = self.mir_pass_stats();
+        let mut total = MirPassStats::default();
+        eprintln!("mir-opt-stats: {crate_name}");
+        eprintln!(
+            "mir-opt-stats: {:<40} {:>8} {:>10} {:>12} {:>10} {:>10}",
+            "pass", "runs", "time (s)", "statements", "locals", "blocks"
+        );
+        let print_row = |name: &str, stats: &MirPassStats| {
+            eprintln!(
+                "mir-opt-stats: {:<40} {:>8} {:>10} {:>12} {:>10} {:>10}",
+                name,
+                stats.runs,
+                duration_to_secs_str(stats.time),
+                change(stats.statements_before, stats.statements_after),
+                change(stats.locals_before, stats.locals_after),
+                change(stats.blocks_before, stats.blocks_after),
+            );
+        };
+        for (name, stats) in &passes {
+            print_row(name, stats);
+            total.runs += stats.runs;
+            total.statements_before += stats.statements_before;
+            total.statements_after += stats.statements_after;
+            total.locals_before += stats.locals_before;
+            total.locals_after += stats.locals_after;
+            total.blocks_before += stats.blocks_before;
+            total.blocks_after += stats.blocks_after;
+            total.time += stats.time;
+        }
+        print_row("total", &total);
+    }
 }
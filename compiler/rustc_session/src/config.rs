@@ -3,6 +3,7 @@
 
 pub use crate::options::*;
 
+use crate::lint_config::LintConfig;
 use crate::search_paths::SearchPath;
 use crate::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
 use crate::{early_error, early_warn, Session};
@@ -142,6 +143,78 @@ pub enum MirSpanview {
     Block,
 }
 
+/// The output format of `-Z incremental-why`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum IncrementalWhyFormat {
+    /// A summary of root causes, printed along with the `-Z incremental-info` output. The default.
+    Human,
+    /// A JSON object with the root cause of every re-executed query.
+    Json,
+}
+
+/// The output format of `-Z mir-opt-stats`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum MirOptStatsFormat {
+    /// A table with a row for every pass, printed to stderr. The default.
+    Human,
+    /// A JSON object with the statistics of every pass, printed to stdout.
+    Json,
+}
+
+/// The output format of `-Z dump-mir-dataflow`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum DataflowDumpFormat {
+    /// `.dot` files with the state diff of every statement. The default.
+    Graphviz,
+    /// `.json` files with the entry and exit state of every location.
+    Json,
+}
+
+/// The settings of the `-Z dump-mir-dataflow` flag. Which bodies are dumped is still controlled
+/// by `-Z dump-mir`.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct DumpMirDataflow {
+    /// The names of the analyses to dump, e.g. `maybe_init` or `liveness`. Empty means all.
+    pub analyses: Vec<String>,
+    pub format: DataflowDumpFormat,
+}
+
+impl DumpMirDataflow {
+    /// The `NAME`s of the dataflow analyses in the compiler, which are all that
+    /// `-Z dump-mir-dataflow` accepts. Keep in sync when adding an `Analysis`.
+    pub const ANALYSES: &'static [&'static str] = &[
+        "borrows",
+        "ConstAnalysis",
+        "definite_init",
+        "ever_init",
+        "flow_custom_eq",
+        "flow_has_mut_interior",
+        "flow_needs_drop",
+        "flow_needs_nonconst_drop",
+        "liveness",
+        "maybe_borrowed_locals",
+        "maybe_init",
+        "maybe_storage_live",
+        "maybe_uninit",
+        "requires_storage",
+        "transitive liveness",
+        "ValueNumbering",
+    ];
+
+    pub fn enabled_for(&self, analysis: &str) -> bool {
+        self.analyses.is_empty() || self.analyses.iter().any(|a| a == analysis)
+    }
+}
+
+/// The output format of `-Z dump-proof-tree`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum ProofTreeFormat {
+    /// A line for every goal and candidate, indented by their depth in the tree. The default.
+    Text,
+    /// A JSON object with the goals of the tree, their candidates and their nested goals.
+    Json,
+}
+
 /// The different settings that the `-C instrument-coverage` flag can have.
 ///
 /// Coverage instrumentation now supports combining `-C instrument-coverage`
@@ -379,6 +452,11 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF 2.1.0 log, written once compilation is finished, for code scanning tools.
+    Sarif,
+    /// The human readable output, as a self-contained HTML page written once compilation is
+    /// finished.
+    Html,
 }
 
 impl Default for ErrorOutputType {
@@ -459,6 +537,29 @@ pub fn should_link(&self) -> bool {
     }
 }
 
+/// The `--remap-path-prefix` mappings, in the order they were given on the command line.
+///
+/// Both sides of every mapping are hashed for incremental compilation, as the `FROM` side decides
+/// which paths are remapped. Only the `TO` side is part of the crate hash: the `FROM` side is
+/// usually a host-dependent path, like the root of a checkout, and a crate built from another
+/// checkout that is remapped to the same virtual path must have the same crate hash.
+#[derive(Clone, Debug, Default)]
+pub struct RemapPathPrefix(Vec<(PathBuf, PathBuf)>);
+
+impl RemapPathPrefix {
+    pub fn new(mapping: Vec<(PathBuf, PathBuf)>) -> RemapPathPrefix {
+        RemapPathPrefix(mapping)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(PathBuf, PathBuf)> {
+        self.0.iter()
+    }
+
+    pub fn mapping(&self) -> Vec<(PathBuf, PathBuf)> {
+        self.0.clone()
+    }
+}
+
 /// Use tree-based collections to cheaply get a deterministic `Hash` implementation.
 /// *Do not* switch `BTreeMap` or `BTreeSet` out for an unsorted container type! That
 /// would break dependency tracking for command-line arguments.
@@ -552,6 +653,9 @@ pub enum PrintRequest {
     StackProtectorStrategies,
     LinkArgs,
     SplitDebuginfo,
+    DiagnosticCatalog,
+    TranslationCoverage,
+    LintLevels,
 }
 
 pub enum Input {
@@ -722,6 +826,7 @@ fn default() -> Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: LintConfig::default(),
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -743,7 +848,7 @@ fn default() -> Options {
             trimmed_def_paths: TrimmedDefPaths::default(),
             cli_forced_codegen_units: None,
             cli_forced_local_thinlto_off: false,
-            remap_path_prefix: Vec::new(),
+            remap_path_prefix: RemapPathPrefix::default(),
             real_rust_source_base_dir: None,
             edition: DEFAULT_EDITION,
             json_artifact_notifications: false,
@@ -764,7 +869,7 @@ pub fn build_dep_graph(&self) -> bool {
     }
 
     pub fn file_path_mapping(&self) -> FilePathMapping {
-        FilePathMapping::new(self.remap_path_prefix.clone())
+        FilePathMapping::new(self.remap_path_prefix.mapping())
     }
 
     /// Returns `true` if there will be an output file generated.
@@ -790,6 +895,8 @@ pub fn get_symbol_mangling_version(&self) -> SymbolManglingVersion {
 }
 
 impl UnstableOptions {
+    // `threads` is read directly since there is no `Session` yet when the handler is created.
+    #[allow(rustc::bad_opt_access)]
     pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags {
         HandlerFlags {
             can_emit_warnings,
@@ -799,6 +906,8 @@ pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
             track_diagnostics: self.track_diagnostics,
+            sort_diagnostics: self.threads != 1,
+            keep_machine_applicable_suggestions: self.apply_suggestions.is_some(),
         }
     }
 }
@@ -1415,7 +1524,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|html|json|sarif|short",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1590,18 +1699,20 @@ pub fn parse_error_format(
             None | Some("human") => {
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color))
             }
+            Some("html") => ErrorOutputType::Html,
             Some("human-annotate-rs") => {
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::AnnotateSnippet(color))
             }
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
-                     `short` (instead was `{arg}`)"
+                    "argument for `--error-format` must be `human`, `html`, `json`, `sarif` \
+                     or `short` (instead was `{arg}`)"
                 ),
             ),
         }
@@ -1678,6 +1789,18 @@ fn check_error_format_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
+        if let ErrorOutputType::Html = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=html` is unstable",
+            );
+        }
     }
 }
 
@@ -1805,18 +1928,38 @@ fn collect_print_requests(
         ("target-spec-json", PrintRequest::TargetSpec),
         ("link-args", PrintRequest::LinkArgs),
         ("split-debuginfo", PrintRequest::SplitDebuginfo),
+        ("diagnostic-catalog", PrintRequest::DiagnosticCatalog),
+        ("translation-coverage", PrintRequest::TranslationCoverage),
+        ("lint-levels", PrintRequest::LintLevels),
     ];
 
     prints.extend(matches.opt_strs("print").into_iter().map(|req| {
         match PRINT_REQUESTS.iter().find(|&&(name, _)| name == req) {
-            Some((_, PrintRequest::TargetSpec)) => {
+            Some(&(_, PrintRequest::TranslationCoverage))
+                if unstable_opts.unstable_options && unstable_opts.translate_lang.is_none() =>
+            {
+                early_error(
+                    error_format,
+                    "the `-Z translate-lang` flag must also be passed to \
+                     enable the translation-coverage print option",
+                );
+            }
+            Some(&(
+                _,
+                print_request @ (PrintRequest::TargetSpec
+                | PrintRequest::DiagnosticCatalog
+                | PrintRequest::TranslationCoverage
+                | PrintRequest::LintLevels),
+            )) => {
                 if unstable_opts.unstable_options {
-                    PrintRequest::TargetSpec
+                    print_request
                 } else {
                     early_error(
                         error_format,
-                        "the `-Z unstable-options` flag must also be passed to \
-                     enable the target-spec-json print option",
+                        &format!(
+                            "the `-Z unstable-options` flag must also be passed to \
+                             enable the {req} print option"
+                        ),
                     );
                 }
             }
@@ -2199,7 +2342,7 @@ fn parse_remap_path_prefix(
     matches: &getopts::Matches,
     unstable_opts: &UnstableOptions,
     error_format: ErrorOutputType,
-) -> Vec<(PathBuf, PathBuf)> {
+) -> RemapPathPrefix {
     let mut mapping: Vec<(PathBuf, PathBuf)> = matches
         .opt_strs("remap-path-prefix")
         .into_iter()
@@ -2218,7 +2361,7 @@ fn parse_remap_path_prefix(
         },
         None => (),
     };
-    mapping
+    RemapPathPrefix::new(mapping)
 }
 
 // JUSTIFICATION: before wrapper fn is available
@@ -2247,6 +2390,15 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let mut unstable_opts = UnstableOptions::build(matches, error_format);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+    let lint_config = match &unstable_opts.lint_config {
+        Some(path) => LintConfig::load(path).unwrap_or_else(|e| {
+            early_error(
+                error_format,
+                &format!("failed to read the lint configuration file `{}`: {e}", path.display()),
+            )
+        }),
+        None => LintConfig::default(),
+    };
 
     check_error_format_stability(&unstable_opts, error_format, json_rendered);
 
@@ -2433,6 +2585,16 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let crate_name = matches.opt_str("crate-name");
 
+    // The backend is loaded before the crate is parsed, so `-Z codegen-backend-by-crate` can only
+    // match the name passed with `--crate-name`. The last match wins and replaces the backend of
+    // `-Z codegen-backend`, which is tracked, so other entries don't affect the crate.
+    if let Some(crate_name) = &crate_name
+        && let Some((_, backend)) =
+            unstable_opts.codegen_backend_by_crate.iter().rev().find(|(name, _)| name == crate_name)
+    {
+        unstable_opts.codegen_backend = Some(backend.clone());
+    }
+
     let remap_path_prefix = parse_remap_path_prefix(matches, &unstable_opts, error_format);
 
     let pretty = parse_pretty(&unstable_opts, error_format);
@@ -2470,7 +2632,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     });
 
     let (path, remapped) =
-        FilePathMapping::new(remap_path_prefix.clone()).map_prefix(working_dir.clone());
+        FilePathMapping::new(remap_path_prefix.mapping()).map_prefix(working_dir.clone());
     let working_dir = if remapped {
         RealFileName::Remapped { local_path: Some(working_dir), virtual_name: path }
     } else {
@@ -2484,6 +2646,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debuginfo,
         lint_opts,
         lint_cap,
+        lint_config,
         describe_lints,
         output_types,
         search_paths,
@@ -2532,15 +2695,18 @@ fn parse_pretty(unstable_opts: &UnstableOptions, efmt: ErrorOutputType) -> Optio
         "hir,typed" => Hir(PpHirMode::Typed),
         "hir-tree" => HirTree,
         "thir-tree" => ThirTree,
+        "thir-json" => ThirJson,
         "mir" => Mir,
         "mir-cfg" => MirCFG,
+        "smir-json" => SmirJson,
         name => early_error(
             efmt,
             &format!(
                 "argument to `unpretty` must be one of `normal`, `identified`, \
                             `expanded`, `expanded,identified`, `expanded,hygiene`, \
                             `ast-tree`, `ast-tree,expanded`, `hir`, `hir,identified`, \
-                            `hir,typed`, `hir-tree`, `thir-tree`, `mir` or `mir-cfg`; got {name}"
+                            `hir,typed`, `hir-tree`, `thir-tree`, `thir-json`, `mir`, `mir-cfg` or \
+                            `smir-json`; got {name}"
             ),
         ),
     };
@@ -2695,10 +2861,14 @@ pub enum PpMode {
     HirTree,
     /// `-Zunpretty=thir-tree`
     ThirTree,
+    /// `-Zunpretty=thir-json`
+    ThirJson,
     /// `-Zunpretty=mir`
     Mir,
     /// `-Zunpretty=mir-cfg`
     MirCFG,
+    /// `-Zunpretty=smir-json`
+    SmirJson,
 }
 
 impl PpMode {
@@ -2713,8 +2883,10 @@ pub fn needs_ast_map(&self) -> bool {
             | Hir(_)
             | HirTree
             | ThirTree
+            | ThirJson
             | Mir
-            | MirCFG => true,
+            | MirCFG
+            | SmirJson => true,
         }
     }
     pub fn needs_hir(&self) -> bool {
@@ -2722,13 +2894,13 @@ pub fn needs_hir(&self) -> bool {
         match *self {
             Source(_) | AstTree(_) => false,
 
-            Hir(_) | HirTree | ThirTree | Mir | MirCFG => true,
+            Hir(_) | HirTree | ThirTree | ThirJson | Mir | MirCFG | SmirJson => true,
         }
     }
 
     pub fn needs_analysis(&self) -> bool {
         use PpMode::*;
-        matches!(*self, Mir | MirCFG | ThirTree)
+        matches!(*self, Mir | MirCFG | SmirJson | ThirTree | ThirJson)
     }
 }
 
@@ -2754,10 +2926,11 @@ pub(crate) mod dep_tracking {
     use super::{
         BranchProtection, CFGuard, CFProtection, CrateType, DebugInfo, ErrorOutputType,
         InstrumentCoverage, LdImpl, LinkerPluginLto, LocationDetail, LtoCli, OomStrategy, OptLevel,
-        OutputType, OutputTypes, Passes, SourceFileHashAlgorithm, SplitDwarfKind,
+        OutputType, OutputTypes, Passes, RemapPathPrefix, SourceFileHashAlgorithm, SplitDwarfKind,
         SwitchWithOptPath, SymbolManglingVersion, TrimmedDefPaths,
     };
     use crate::lint;
+    use crate::lint_config::LintConfig;
     use crate::options::WasiExecModel;
     use crate::utils::{NativeLib, NativeLibKind};
     use rustc_errors::LanguageIdentifier;
@@ -2818,6 +2991,7 @@ fn hash(
         String,
         PathBuf,
         lint::Level,
+        LintConfig,
         WasiExecModel,
         u32,
         RelocModel,
@@ -2928,6 +3102,24 @@ fn hash(
         }
     }
 
+    impl DepTrackingHash for RemapPathPrefix {
+        fn hash(
+            &self,
+            hasher: &mut DefaultHasher,
+            error_format: ErrorOutputType,
+            for_crate_hash: bool,
+        ) {
+            Hash::hash(&self.0.len(), hasher);
+            for (index, (from, to)) in self.0.iter().enumerate() {
+                Hash::hash(&index, hasher);
+                if !for_crate_hash {
+                    DepTrackingHash::hash(from, hasher, error_format, for_crate_hash);
+                }
+                DepTrackingHash::hash(to, hasher, error_format, for_crate_hash);
+            }
+        }
+    }
+
     // This is a stable hash because BTreeMap is a sorted container
     pub(crate) fn stable_hash(
         sub_hashes: BTreeMap<&'static str, &dyn DepTrackingHash>,
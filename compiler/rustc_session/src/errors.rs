@@ -131,6 +131,27 @@ pub struct FileIsNotWriteable<'a> {
     pub file: &'a std::path::Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_apply_suggestions_failed)]
+pub struct ApplySuggestionsFailed<'a> {
+    pub path: &'a std::path::Path,
+    pub err: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_conflicting_suggestions_skipped)]
+#[note]
+pub struct ConflictingSuggestionsSkipped {
+    pub count: usize,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_write_diagnostic_baseline_failed)]
+pub struct WriteDiagnosticBaselineFailed<'a> {
+    pub path: &'a std::path::Path,
+    pub err: std::io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(session_crate_name_does_not_match)]
 pub struct CrateNameDoesNotMatch {
@@ -0,0 +1,182 @@
+//! The lint configuration file of `-Z lint-config`.
+//!
+//! The file sets lint levels for the whole crate, for modules or for source files, without
+//! changing the source of the crate. Each line of the file is an entry of the form
+//!
+//! ```text
+//! <level> <lint or lint group> [<module path or file>]
+//! ```
+//!
+//! where the level is one of `allow`, `warn`, `deny` or `forbid`, and `#` starts a comment. An
+//! entry without a scope applies to the whole crate. A scope that starts with `crate` is the path
+//! of a module, like `crate::ffi` or `crate::ffi::*`, and the entry applies to the module and to
+//! everything inside it. Any other scope is the name of a source file, as rustc reports it in
+//! diagnostics, in which `*` matches any sequence of characters, like `src/generated/*.rs`.
+//!
+//! For example, this denies `unsafe_code` in the whole crate, except in `crate::ffi`:
+//!
+//! ```text
+//! deny unsafe_code
+//! allow unsafe_code crate::ffi::*
+//! ```
+//!
+//! Entries behave as if they were lint attributes at the top of the modules they apply to, so
+//! they override the `-A`, `-W`, `-D` and `-F` command line flags, and are overridden by the lint
+//! attributes in the source. Entries for a file come before entries for a module path, and later
+//! entries of the file take precedence over earlier ones.
+
+use crate::lint::Level;
+use rustc_span::Symbol;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// The parsed lint configuration file.
+#[derive(Clone, Default, Debug, Hash)]
+pub struct LintConfig {
+    /// The path of the file, for diagnostics.
+    pub path: Option<PathBuf>,
+    pub entries: Vec<LintConfigEntry>,
+}
+
+/// An entry of the lint configuration file.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct LintConfigEntry {
+    pub level: Level,
+    /// The name of the lint or lint group, with dashes replaced by underscores.
+    pub lint_name: String,
+    pub scope: LintConfigScope,
+    /// The line of the entry in the file, starting from 1.
+    pub line: usize,
+}
+
+/// What an entry of the lint configuration file applies to.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum LintConfigScope {
+    /// The whole crate.
+    Crate,
+    /// The module with this path, relative to the crate root, and everything inside it.
+    Module(Vec<String>),
+    /// The source files whose name matches this pattern.
+    File(String),
+}
+
+impl LintConfig {
+    /// Reads and parses the lint configuration file at `path`.
+    pub fn load(path: &Path) -> Result<LintConfig, String> {
+        let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let entries = parse_entries(&text)?;
+        Ok(LintConfig { path: Some(path.to_path_buf()), entries })
+    }
+
+    /// Returns the entries that apply to the module at `module_path`, relative to the crate root,
+    /// in the order they are to be applied. `file` is the name of the source file of the module
+    /// if the module is the crate root or an out-of-line module, as entries for files only apply
+    /// to the modules that their files start with.
+    ///
+    /// Entries for modules that contain the module are not returned, as their levels are
+    /// inherited from the containing modules anyway.
+    pub fn module_entries<'a>(
+        &'a self,
+        module_path: &[Symbol],
+        file: Option<&str>,
+    ) -> Vec<&'a LintConfigEntry> {
+        let mut entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| match &entry.scope {
+                LintConfigScope::Crate => module_path.is_empty(),
+                LintConfigScope::Module(path) => {
+                    path.len() == module_path.len()
+                        && path.iter().zip(module_path).all(|(a, b)| a.as_str() == b.as_str())
+                }
+                LintConfigScope::File(pattern) => {
+                    file.map_or(false, |file| glob_matches(pattern, file))
+                }
+            })
+            .collect();
+        entries.sort_by_key(|entry| {
+            let precedence = match entry.scope {
+                LintConfigScope::Crate => 0,
+                LintConfigScope::File(_) => 1,
+                LintConfigScope::Module(_) => 2,
+            };
+            (precedence, entry.line)
+        });
+        entries
+    }
+}
+
+fn parse_entries(text: &str) -> Result<Vec<LintConfigEntry>, String> {
+    let mut entries = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (level, lint_name, scope) = match words[..] {
+            [level, lint_name] => (level, lint_name, None),
+            [level, lint_name, scope] => (level, lint_name, Some(scope)),
+            _ => {
+                return Err(format!(
+                    "line {line_number}: expected `<level> <lint or lint group> \
+                     [<module path or file>]`"
+                ));
+            }
+        };
+        let Some(level) = Level::from_str(level) else {
+            return Err(format!(
+                "line {line_number}: unknown lint level `{level}`, expected `allow`, `warn`, \
+                 `deny` or `forbid`"
+            ));
+        };
+        let scope = match scope {
+            None => LintConfigScope::Crate,
+            Some(scope) if scope == "crate" || scope.starts_with("crate::") => {
+                let mut path: Vec<String> = scope.split("::").skip(1).map(String::from).collect();
+                if path.last().map_or(false, |segment| segment == "*") {
+                    path.pop();
+                }
+                if path.iter().any(|segment| segment.is_empty() || segment.contains('*')) {
+                    return Err(format!("line {line_number}: invalid module path `{scope}`"));
+                }
+                if path.is_empty() {
+                    LintConfigScope::Crate
+                } else {
+                    LintConfigScope::Module(path)
+                }
+            }
+            Some(scope) => LintConfigScope::File(scope.to_string()),
+        };
+        entries.push(LintConfigEntry {
+            level,
+            lint_name: lint_name.replace('-', "_"),
+            scope,
+            line: line_number,
+        });
+    }
+    Ok(entries)
+}
+
+/// Whether `text` matches `pattern`, in which `*` matches any sequence of characters.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap();
+    let Some(mut rest) = text.strip_prefix(first) else { return false };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*` in the pattern.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
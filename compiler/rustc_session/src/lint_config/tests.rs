@@ -0,0 +1,78 @@
+use super::*;
+
+use rustc_span::create_default_session_globals_then;
+
+const CONFIG: &str = "\
+# Deny unsafe code, except for the bindings.
+deny unsafe-code
+allow unsafe_code crate::ffi::*
+warn dead_code src/generated/*.rs  # generated code has unused items
+forbid unsafe_code crate::ffi::checked
+";
+
+fn module_entries(config: &LintConfig, path: &[&str], file: Option<&str>) -> Vec<(Level, usize)> {
+    let path: Vec<Symbol> = path.iter().map(|segment| Symbol::intern(segment)).collect();
+    config.module_entries(&path, file).iter().map(|entry| (entry.level, entry.line)).collect()
+}
+
+#[test]
+fn parses_entries() {
+    let entries = parse_entries(CONFIG).unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(
+        entries[0],
+        LintConfigEntry {
+            level: Level::Deny,
+            lint_name: "unsafe_code".to_string(),
+            scope: LintConfigScope::Crate,
+            line: 2,
+        }
+    );
+    assert_eq!(entries[1].scope, LintConfigScope::Module(vec!["ffi".to_string()]));
+    assert_eq!(entries[2].scope, LintConfigScope::File("src/generated/*.rs".to_string()));
+    assert_eq!(entries[2].line, 4);
+}
+
+#[test]
+fn rejects_malformed_entries() {
+    assert_eq!(
+        parse_entries("\nexpect unsafe_code").unwrap_err(),
+        "line 2: unknown lint level `expect`, expected `allow`, `warn`, `deny` or `forbid`"
+    );
+    assert_eq!(
+        parse_entries("deny").unwrap_err(),
+        "line 1: expected `<level> <lint or lint group> [<module path or file>]`"
+    );
+    assert_eq!(
+        parse_entries("deny unsafe_code crate::*::ffi").unwrap_err(),
+        "line 1: invalid module path `crate::*::ffi`"
+    );
+}
+
+#[test]
+fn selects_module_entries() {
+    create_default_session_globals_then(|| {
+        let config = LintConfig { path: None, entries: parse_entries(CONFIG).unwrap() };
+        assert_eq!(module_entries(&config, &[], Some("src/lib.rs")), [(Level::Deny, 2)]);
+        assert_eq!(module_entries(&config, &["ffi"], Some("src/ffi.rs")), [(Level::Allow, 3)]);
+        assert_eq!(module_entries(&config, &["ffi", "checked"], None), [(Level::Forbid, 5)]);
+        // Entries for files come before entries for modules.
+        assert_eq!(
+            module_entries(&config, &["ffi"], Some("src/generated/ffi.rs")),
+            [(Level::Warn, 4), (Level::Allow, 3)]
+        );
+        assert!(module_entries(&config, &["generated"], Some("src/generated.rs")).is_empty());
+        // Entries for files don't apply to the inline modules of the files.
+        assert!(module_entries(&config, &["generated"], None).is_empty());
+    });
+}
+
+#[test]
+fn glob_patterns() {
+    assert!(glob_matches("src/lib.rs", "src/lib.rs"));
+    assert!(!glob_matches("src/lib.rs", "src/lib.rs.bak"));
+    assert!(glob_matches("src/*", "src/a/b.rs"));
+    assert!(glob_matches("*/gen_*.rs", "src/gen_a.rs"));
+    assert!(!glob_matches("*/gen_*.rs", "src/gen_a.rs/x"));
+    assert!(!glob_matches("a*ab", "ab"));
+}
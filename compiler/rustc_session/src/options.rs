@@ -2,8 +2,10 @@
 
 use crate::early_error;
 use crate::lint;
+use crate::lint_config::LintConfig;
 use crate::search_paths::SearchPath;
 use crate::utils::NativeLib;
+use rustc_errors::apply_suggestions::ApplySuggestions;
 use rustc_errors::LanguageIdentifier;
 use rustc_target::spec::{CodeModel, LinkerFlavorCli, MergeFunctions, PanicStrategy, SanitizerSet};
 use rustc_target::spec::{
@@ -140,6 +142,9 @@ pub struct Options {
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        /// The entries of the `-Z lint-config` file, which are tracked rather than its path so
+        /// that changes to the file are noticed.
+        lint_config: LintConfig [TRACKED_NO_CRATE_HASH],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -184,7 +189,7 @@ pub struct Options {
         cli_forced_local_thinlto_off: bool [UNTRACKED],
 
         /// Remap source path prefixes in all output (messages, object files, debug, etc.).
-        remap_path_prefix: Vec<(PathBuf, PathBuf)> [TRACKED_NO_CRATE_HASH],
+        remap_path_prefix: RemapPathPrefix [TRACKED],
         /// Base directory containing the `src/` for the Rust standard library, and
         /// potentially `rustc` as well, if we can find it. Right now it's always
         /// `$sysroot/lib/rustlib/src/rust` (i.e. the `rustup` `rust-src` component).
@@ -359,6 +364,8 @@ mod desc {
     pub const parse_list: &str = "a space-separated list of strings";
     pub const parse_list_with_polarity: &str =
         "a comma-separated list of strings, with elements beginning with + or -";
+    pub const parse_codegen_backend_by_crate: &str =
+        "a comma-separated list of `crate=backend` pairs, e.g. `my_crate=cranelift`";
     pub const parse_opt_comma_list: &str = "a comma-separated list of strings";
     pub const parse_number: &str = "a number";
     pub const parse_opt_number: &str = parse_number;
@@ -376,6 +383,14 @@ mod desc {
     pub const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
     pub const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavorCli::one_of();
     pub const parse_optimization_fuel: &str = "crate=integer";
+    pub const parse_dump_mir_dataflow: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or a comma separated list of analysis names (`all`, `borrows`, `liveness`, `maybe_init`, `maybe_uninit`, etc) and an optional format: `graphviz` (default) or `json`";
+    pub const parse_apply_suggestions: &str =
+        "either a boolean (`yes`, `no`, `on`, `off`, etc), `in-place`, `diff`, or omitted";
+    pub const parse_incremental_why: &str =
+        "either a boolean (`yes`, `no`, `on`, `off`, etc), `human` (default), or `json`";
+    pub const parse_proof_tree_format: &str = "`text` (default) or `json`";
+    pub const parse_mir_opt_stats: &str =
+        "either a boolean (`yes`, `no`, `on`, `off`, etc), `human` (default), or `json`";
     pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
     pub const parse_instrument_coverage: &str =
         "`all` (default), `except-unused-generics`, `except-unused-functions`, or `off`";
@@ -521,6 +536,25 @@ pub(crate) fn parse_list(slot: &mut Vec<String>, v: Option<&str>) -> bool {
         }
     }
 
+    pub(crate) fn parse_codegen_backend_by_crate(
+        slot: &mut Vec<(String, String)>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            Some(s) => {
+                for s in s.split(',') {
+                    let Some((crate_name, backend)) = s.split_once('=') else { return false };
+                    if crate_name.is_empty() || backend.is_empty() {
+                        return false;
+                    }
+                    slot.push((crate_name.to_string(), backend.to_string()));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
     pub(crate) fn parse_list_with_polarity(
         slot: &mut Vec<(String, bool)>,
         v: Option<&str>,
@@ -797,6 +831,46 @@ pub(crate) fn parse_unpretty(slot: &mut Option<String>, v: Option<&str>) -> bool
         }
     }
 
+    pub(crate) fn parse_dump_mir_dataflow(
+        slot: &mut Option<DumpMirDataflow>,
+        v: Option<&str>,
+    ) -> bool {
+        let all = DumpMirDataflow { analyses: vec![], format: DataflowDumpFormat::Graphviz };
+
+        if v.is_some() {
+            let mut bool_arg = None;
+            if parse_opt_bool(&mut bool_arg, v) {
+                *slot = if bool_arg.unwrap() { Some(all) } else { None };
+                return true;
+            }
+        }
+
+        let Some(v) = v else {
+            *slot = Some(all);
+            return true;
+        };
+
+        let mut opts = all;
+        let mut all_analyses = false;
+        for s in v.split(',') {
+            match s {
+                "graphviz" | "dot" => opts.format = DataflowDumpFormat::Graphviz,
+                "json" => opts.format = DataflowDumpFormat::Json,
+                "all" => all_analyses = true,
+                "" => return false,
+                analysis if DumpMirDataflow::ANALYSES.contains(&analysis) => {
+                    opts.analyses.push(analysis.to_string())
+                }
+                _ => return false,
+            }
+        }
+        if all_analyses {
+            opts.analyses.clear();
+        }
+        *slot = Some(opts);
+        true
+    }
+
     pub(crate) fn parse_mir_spanview(slot: &mut Option<MirSpanview>, v: Option<&str>) -> bool {
         if v.is_some() {
             let mut bool_arg = None;
@@ -820,6 +894,75 @@ pub(crate) fn parse_mir_spanview(slot: &mut Option<MirSpanview>, v: Option<&str>
         true
     }
 
+    pub(crate) fn parse_apply_suggestions(
+        slot: &mut Option<ApplySuggestions>,
+        v: Option<&str>,
+    ) -> bool {
+        if v.is_some() {
+            let mut bool_arg = None;
+            if parse_opt_bool(&mut bool_arg, v) {
+                *slot = if bool_arg.unwrap() { Some(ApplySuggestions::InPlace) } else { None };
+                return true;
+            }
+        }
+
+        *slot = Some(match v {
+            None | Some("in-place") => ApplySuggestions::InPlace,
+            Some("diff") => ApplySuggestions::Diff,
+            _ => return false,
+        });
+        true
+    }
+
+    pub(crate) fn parse_incremental_why(
+        slot: &mut Option<IncrementalWhyFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        if v.is_some() {
+            let mut bool_arg = None;
+            if parse_opt_bool(&mut bool_arg, v) {
+                *slot = if bool_arg.unwrap() { Some(IncrementalWhyFormat::Human) } else { None };
+                return true;
+            }
+        }
+
+        *slot = Some(match v {
+            None | Some("human") => IncrementalWhyFormat::Human,
+            Some("json") => IncrementalWhyFormat::Json,
+            _ => return false,
+        });
+        true
+    }
+
+    pub(crate) fn parse_mir_opt_stats(
+        slot: &mut Option<MirOptStatsFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        if v.is_some() {
+            let mut bool_arg = None;
+            if parse_opt_bool(&mut bool_arg, v) {
+                *slot = if bool_arg.unwrap() { Some(MirOptStatsFormat::Human) } else { None };
+                return true;
+            }
+        }
+
+        *slot = Some(match v {
+            None | Some("human") => MirOptStatsFormat::Human,
+            Some("json") => MirOptStatsFormat::Json,
+            _ => return false,
+        });
+        true
+    }
+
+    pub(crate) fn parse_proof_tree_format(slot: &mut ProofTreeFormat, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("text") => ProofTreeFormat::Text,
+            Some("json") => ProofTreeFormat::Json,
+            _ => return false,
+        };
+        true
+    }
+
     pub(crate) fn parse_instrument_coverage(
         slot: &mut Option<InstrumentCoverage>,
         v: Option<&str>,
@@ -1214,6 +1357,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "only allow the listed language features to be enabled in code (space separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apply_suggestions: Option<ApplySuggestions> = (None, parse_apply_suggestions, [UNTRACKED],
+        "apply the machine-applicable suggestions of the emitted diagnostics to the source files: \
+        `in-place` (default), or `diff` to print them as a unified diff instead"),
     #[rustc_lint_opt_deny_field_access("use `Session::asm_comments` instead of this field")]
     asm_comments: bool = (false, parse_bool, [TRACKED],
         "generate comments into the assembly (may change behavior) (default: no)"),
@@ -1226,6 +1372,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     binary_dep_depinfo: bool = (false, parse_bool, [TRACKED],
         "include artifacts (sysroot, crate dependencies) used during compilation in dep-info \
         (default: no)"),
+    borrowck_explain: bool = (false, parse_bool, [TRACKED],
+        "explain borrow conflicts (E0499, E0502, E0505) step by step, from where the borrow \
+        starts, through every constraint that keeps it live, to the conflicting use (default: no)"),
     box_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "emit noalias metadata for box (default: yes)"),
     branch_protection: Option<BranchProtection> = (None, parse_branch_protection, [TRACKED],
@@ -1238,8 +1387,14 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "enable the experimental Chalk-based trait solving engine"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
+    codegen_backend_by_crate: Vec<(String, String)> = (Vec::new(), parse_codegen_backend_by_crate, [UNTRACKED],
+        "the backend to use for the crates with the given `--crate-name`, instead of the one of \
+        `-Z codegen-backend` (crates built by different backends cannot be linked together)"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    compact_cycles: bool = (false, parse_bool, [UNTRACKED],
+        "collapse the repeating segments of query cycles and of the requirements that led to a \
+        trait overflow (default: no)"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
         "inject the given attribute in the crate"),
     debug_info_for_profiling: bool = (false, parse_bool, [TRACKED],
@@ -1254,6 +1409,8 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     dep_tasks: bool = (false, parse_bool, [UNTRACKED],
         "print tasks that execute and the color their dep node gets (requires debug build) \
         (default: no)"),
+    diagnostic_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "suppress the lint diagnostics listed in this baseline file, and only report new ones"),
     diagnostic_width: Option<usize> = (None, parse_opt_number, [UNTRACKED],
         "set the current output width for diagnostic truncation"),
     dlltool: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
@@ -1265,6 +1422,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "enables drop tracking in generators (default: no)"),
     dual_proc_macros: bool = (false, parse_bool, [TRACKED],
         "load proc macros for both target and host, but only link to the target (default: no)"),
+    dump_cycle_graph: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write every frame of a query cycle, or every requirement that led to a trait overflow, \
+        to the given file as a graphviz graph (default: no)"),
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv) \
         (default: no)"),
@@ -1277,9 +1437,11 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         `foo` matches all passes for functions whose name contains 'foo',
         `foo & ConstProp` only the 'ConstProp' pass for function names containing 'foo',
         `foo | bar` all passes for function names containing 'foo' or 'bar'."),
-    dump_mir_dataflow: bool = (false, parse_bool, [UNTRACKED],
-        "in addition to `.mir` files, create graphviz `.dot` files with dataflow results \
-        (default: no)"),
+    dump_mir_dataflow: Option<DumpMirDataflow> = (None, parse_dump_mir_dataflow, [UNTRACKED],
+        "in addition to `.mir` files, create graphviz `.dot` files with dataflow results. \
+        Takes an optional comma separated list of analyses (e.g. `maybe_init`, `liveness`, \
+        `maybe_storage_live` or `borrows`) and `json` to write the state before and after \
+        every location as `.json` files instead (default: no)"),
     dump_mir_dir: String = ("mir_dump".to_string(), parse_string, [UNTRACKED],
         "the directory the MIR is dumped into (default: `mir_dump`)"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
@@ -1297,6 +1459,11 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     dump_mono_stats: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
         parse_switch_with_opt_path, [UNTRACKED],
         "output statistics about monomorphization collection (format: markdown)"),
+    dump_proof_tree: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "dump the trait solver proof tree of the obligations that fail or overflow, if their \
+        predicate contains `val` (or for all of them with `all`), to stderr (default: no)"),
+    dump_proof_tree_format: ProofTreeFormat = (ProofTreeFormat::Text, parse_proof_tree_format,
+        [UNTRACKED], "the format of `-Z dump-proof-tree`: `text` or `json` (default: `text`)"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1305,6 +1472,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "emit a section containing stack size metadata (default: no)"),
     emit_thin_lto: bool = (true, parse_bool, [TRACKED],
         "emit the bc module with thin LTO info (default: yes)"),
+    explain_auto_traits: bool = (false, parse_bool, [UNTRACKED],
+        "explain every value that makes a future or generator not implement an auto trait, \
+        like `Send`, with the types that lead to the one not implementing it (default: no)"),
     export_executable_symbols: bool = (false, parse_bool, [TRACKED],
         "export symbols from executables, as if they were dynamic libraries"),
     extra_const_ub_checks: bool = (false, parse_bool, [TRACKED],
@@ -1335,6 +1505,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
+    incremental_import: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "seed an empty incremental compilation cache from the cache directory at `path`, \
+        e.g. one produced by another checkout or on another machine"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
@@ -1342,6 +1515,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "hash spans relative to their parent item for incr. comp. (default: no)"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify incr. comp. hashes of green query instances (default: no)"),
+    incremental_why: Option<IncrementalWhyFormat> = (None, parse_incremental_why, [UNTRACKED],
+        "explain why queries were re-executed, by tracing them back to the changed inputs: \
+        `human` (default) or `json`"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "control whether `#[inline]` functions are in all CGUs"),
     inline_llvm: bool = (true, parse_bool, [TRACKED],
@@ -1374,6 +1550,8 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    lint_config: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "read per-module and per-file lint levels from this file"),
     llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
         "a list LLVM plugins to enable (space separated)"),
     llvm_time_trace: bool = (false, parse_bool, [UNTRACKED],
@@ -1405,6 +1583,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     #[rustc_lint_opt_deny_field_access("use `Session::mir_opt_level` instead of this field")]
     mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
+    mir_opt_stats: Option<MirOptStatsFormat> = (None, parse_mir_opt_stats, [UNTRACKED],
+        "print the number of statements, locals and basic blocks before and after every MIR pass \
+        and the time it took, summed over the crate: `human` (default) or `json`"),
     mir_pretty_relative_line_numbers: bool = (false, parse_bool, [UNTRACKED],
         "use line numbers relative to the function in mir pretty printing"),
     move_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
@@ -1415,6 +1596,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "dump facts from NLL analysis into side files (default: no)"),
     nll_facts_dir: String = ("nll-facts".to_string(), parse_string, [UNTRACKED],
         "the directory the NLL facts are dumped into (default: `nll-facts`)"),
+    nll_facts_json: bool = (false, parse_bool, [UNTRACKED],
+        "dump facts from NLL analysis, the results of region inference and the constraints \
+        blamed for each region error as `facts.json` into `-Z nll-facts-dir` (default: no)"),
     no_analysis: bool = (false, parse_no_flag, [UNTRACKED],
         "parse and expand the source, but run no analysis"),
     no_codegen: bool = (false, parse_no_flag, [TRACKED_NO_CRATE_HASH],
@@ -1517,6 +1701,10 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     self_profile: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
         parse_switch_with_opt_path, [UNTRACKED],
         "run the self profiler and output the raw event data"),
+    self_profile_chrome_trace: bool = (false, parse_bool, [UNTRACKED],
+        "write the self-profiling events in the Chrome trace event format, for \
+        `chrome://tracing`, Perfetto or Speedscope, to the directory of `-Z self-profile` or the \
+        current directory (default: no)"),
     self_profile_counter: String = ("wall-time".to_string(), parse_string, [UNTRACKED],
         "counter used by the self profiler (default: `wall-time`), one of:
         `wall-time` (monotonic clock, i.e. `std::time::Instant`)
@@ -1529,6 +1717,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    self_profile_summary: bool = (false, parse_bool, [UNTRACKED],
+        "print the queries and activities that took the most time, with their cache hits and \
+        incremental loading and hashing times, to stderr (default: no)"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -1580,7 +1771,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     /// the num_cpus behavior.
     #[rustc_lint_opt_deny_field_access("use `Session::threads` instead of this field")]
     threads: usize = (1, parse_threads, [UNTRACKED],
-        "use a thread pool with N threads"),
+        "use a thread pool with N threads (0 means one per CPU). With more than one thread, \
+        diagnostics are sorted by source location so that the output does not depend on the \
+        number of threads (default: 1)"),
     #[rustc_lint_opt_deny_field_access("use `Session::time_llvm_passes` instead of this field")]
     time_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
         "measure time of each LLVM pass (default: no)"),
@@ -1630,7 +1823,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         `hir` (the HIR), `hir,identified`,
         `hir,typed` (HIR with types for each node),
         `hir-tree` (dump the raw HIR),
-        `mir` (the MIR), or `mir-cfg` (graphviz formatted MIR)"),
+        `thir-tree` (dump the raw THIR), `thir-json` (the THIR as JSON),
+        `mir` (the MIR), `mir-cfg` (graphviz formatted MIR),
+        or `smir-json` (stable MIR as versioned JSON)"),
     unsound_mir_opts: bool = (false, parse_bool, [TRACKED],
         "enable unsound and buggy MIR optimizations (default: no)"),
     /// This name is kind of confusing: Most unstable options enable something themselves, while
@@ -1653,6 +1848,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         Requires `-Clto[=[fat,yes]]`"),
     wasi_exec_model: Option<WasiExecModel> = (None, parse_wasi_exec_model, [TRACKED],
         "whether to build a wasi command or reactor"),
+    write_diagnostic_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the lint diagnostics of this compilation to a baseline file, for use with \
+        `-Z diagnostic-baseline`"),
     // tidy-alphabetical-end
 
     // If you add a new option, please update:
@@ -1,14 +1,16 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
 pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+pub use crate::code_stats::{MirBodySize, MirPassStats};
 use crate::config::{self, CrateType, InstrumentCoverage, OptLevel, OutputType, SwitchWithOptPath};
 use crate::errors::{
-    BranchProtectionRequiresAArch64, CannotEnableCrtStaticLinux, CannotMixAndMatchSanitizers,
-    LinkerPluginToWindowsNotSupported, NotCircumventFeature, ProfileSampleUseFileDoesNotExist,
-    ProfileUseFileDoesNotExist, SanitizerCfiEnabled, SanitizerNotSupported, SanitizersNotSupported,
-    SkippingConstChecks, SplitDebugInfoUnstablePlatform, StackProtectorNotSupportedForTarget,
+    ApplySuggestionsFailed, BranchProtectionRequiresAArch64, CannotEnableCrtStaticLinux,
+    CannotMixAndMatchSanitizers, ConflictingSuggestionsSkipped, LinkerPluginToWindowsNotSupported,
+    NotCircumventFeature, ProfileSampleUseFileDoesNotExist, ProfileUseFileDoesNotExist,
+    SanitizerCfiEnabled, SanitizerNotSupported, SanitizersNotSupported, SkippingConstChecks,
+    SplitDebugInfoUnstablePlatform, StackProtectorNotSupportedForTarget,
     TargetRequiresUnwindTables, UnleashedFeatureHelp, UnstableVirtualFunctionElimination,
-    UnsupportedDwarfVersion,
+    UnsupportedDwarfVersion, WriteDiagnosticBaselineFailed,
 };
 use crate::parse::{add_feature_diagnostics, ParseSess};
 use crate::search_paths::{PathKind, SearchPath};
@@ -24,17 +26,21 @@
     self, AtomicU64, AtomicUsize, Lock, Lrc, OnceCell, OneThread, Ordering, Ordering::SeqCst,
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
+use rustc_errors::apply_suggestions::{ApplySuggestions, SuggestionFixes};
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
+use rustc_errors::html::HtmlEmitter;
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{
-    error_code, fallback_fluent_bundle, DiagnosticBuilder, DiagnosticId, DiagnosticMessage,
-    ErrorGuaranteed, FluentBundle, IntoDiagnostic, LazyFallbackBundle, MultiSpan, Noted,
+    error_code, fallback_fluent_bundle, DiagnosticBaseline, DiagnosticBuilder, DiagnosticId,
+    DiagnosticMessage, ErrorGuaranteed, FluentBundle, IntoDiagnostic, LazyFallbackBundle,
+    MultiSpan, Noted,
 };
 use rustc_macros::HashStable_Generic;
 pub use rustc_span::def_id::StableCrateId;
 use rustc_span::edition::Edition;
-use rustc_span::source_map::{FileLoader, RealFileLoader, SourceMap, Span};
+use rustc_span::source_map::{FileLoader, FilePathMapping, RealFileLoader, SourceMap, Span};
 use rustc_span::{sym, SourceFileHashAlgorithm, Symbol};
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel};
@@ -45,6 +51,7 @@
 use std::cell::{self, RefCell};
 use std::env;
 use std::fmt;
+use std::fs;
 use std::ops::{Div, Mul};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -257,10 +264,44 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.apply_suggestions();
+        self.write_diagnostic_baseline();
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
     }
 
+    /// Applies the machine-applicable suggestions of the emitted diagnostics to the source
+    /// files, or prints them as a unified diff, for `-Z apply-suggestions`.
+    fn apply_suggestions(&self) {
+        let Some(mode) = self.opts.unstable_opts.apply_suggestions else { return };
+
+        let suggestions = self.diagnostic().take_machine_applicable_suggestions();
+        let fixes = SuggestionFixes::new(self.source_map(), &suggestions);
+        for file in &fixes.files {
+            let result = file.read_original().and_then(|original| match mode {
+                ApplySuggestions::InPlace => fs::write(&file.path, file.fixed_source(&original)),
+                ApplySuggestions::Diff => {
+                    print!("{}", file.unified_diff(&original));
+                    Ok(())
+                }
+            });
+            if let Err(err) = result {
+                self.emit_err(ApplySuggestionsFailed { path: &file.path, err });
+            }
+        }
+        if fixes.conflicting > 0 {
+            self.emit_warning(ConflictingSuggestionsSkipped { count: fixes.conflicting });
+        }
+    }
+
+    /// Writes the lint diagnostics of this session to the file of `-Z write-diagnostic-baseline`.
+    fn write_diagnostic_baseline(&self) {
+        let Some(path) = &self.opts.unstable_opts.write_diagnostic_baseline else { return };
+        if let Err(err) = self.diagnostic().write_diagnostic_baseline(path) {
+            self.emit_err(WriteDiagnosticBaselineFailed { path, err });
+        }
+    }
+
     fn emit_future_breakage(&self) {
         if !self.opts.json_future_incompat {
             return;
@@ -1303,6 +1344,24 @@ fn default_emitter(
             )
             .ui_testing(sopts.unstable_opts.ui_testing),
         ),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::stderr(
+            Some(registry),
+            source_map,
+            bundle,
+            fallback_bundle,
+        )),
+        config::ErrorOutputType::Html => Box::new(
+            HtmlEmitter::stderr(
+                Some(source_map),
+                bundle,
+                fallback_bundle,
+                sopts.unstable_opts.teach,
+                sopts.diagnostic_width,
+                macro_backtrace,
+                track_diagnostics,
+            )
+            .ui_testing(sopts.unstable_opts.ui_testing),
+        ),
     }
 }
 
@@ -1367,16 +1426,36 @@ pub fn build_session(
         sopts.unstable_opts.diagnostic_handler_flags(can_emit_warnings),
     );
 
-    let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.unstable_opts.self_profile
-    {
-        let directory =
-            if let Some(ref directory) = d { directory } else { std::path::Path::new(".") };
+    if let Some(path) = &sopts.unstable_opts.diagnostic_baseline {
+        let baseline = DiagnosticBaseline::load(path).unwrap_or_else(|e| {
+            early_error(
+                sopts.error_format,
+                &format!("failed to read the diagnostic baseline `{}`: {e}", path.display()),
+            )
+        });
+        span_diagnostic.set_diagnostic_baseline(baseline);
+    } else if sopts.unstable_opts.write_diagnostic_baseline.is_some() {
+        span_diagnostic.set_diagnostic_baseline(DiagnosticBaseline::default());
+    }
+
+    let keep_profile_events_in_memory =
+        sopts.unstable_opts.self_profile_summary || sopts.unstable_opts.self_profile_chrome_trace;
+    let self_profile_directory = match sopts.unstable_opts.self_profile {
+        SwitchWithOptPath::Enabled(ref d) => Some(d.as_deref()),
+        SwitchWithOptPath::Disabled if keep_profile_events_in_memory => Some(None),
+        SwitchWithOptPath::Disabled => None,
+    };
+    let self_profiler = if let Some(d) = self_profile_directory {
+        let directory = d.unwrap_or(std::path::Path::new("."));
 
         let profiler = SelfProfiler::new(
             directory,
             sopts.crate_name.as_deref(),
             sopts.unstable_opts.self_profile_events.as_deref(),
             &sopts.unstable_opts.self_profile_counter,
+            // The summary and the Chrome trace alone don't need the raw profile data.
+            sopts.unstable_opts.self_profile.enabled(),
+            keep_profile_events_in_memory,
         );
         match profiler {
             Ok(profiler) => Some(Arc::new(profiler)),
@@ -1626,6 +1705,15 @@ fn early_error_handler(output: config::ErrorOutputType) -> rustc_errors::Handler
             false,
             false,
         )),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::stderr(
+            None,
+            Lrc::new(SourceMap::new(FilePathMapping::empty())),
+            None,
+            fallback_bundle,
+        )),
+        config::ErrorOutputType::Html => {
+            Box::new(HtmlEmitter::stderr(None, None, fallback_bundle, false, None, false, false))
+        }
     };
     rustc_errors::Handler::with_emitter(true, None, emitter)
 }
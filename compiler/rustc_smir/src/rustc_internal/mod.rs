@@ -0,0 +1,96 @@
+//! Module that implements the bridge between Stable MIR and internal compiler MIR.
+//!
+//! For that, we define APIs that will temporarily be public to 3P that exposes rustc internal APIs
+//! until stable MIR is complete.
+
+use crate::stable_mir::{self, ty::Ty as StableTy, CrateItem, DefId as StableDefId};
+use crate::very_unstable::data_structures::fx::FxIndexSet;
+use crate::very_unstable::middle::ty::{Ty, TyCtxt};
+use crate::very_unstable::span::def_id::DefId;
+use crate::very_unstable::span::Span;
+use std::io;
+
+/// Maps stable MIR identifiers back and forth to the compiler's internal data structures.
+///
+/// All stable identifiers (`DefId`, `Span`, `Ty`) are indices into the sets stored here, and
+/// are only meaningful for the duration of a single [`run`] invocation.
+pub struct Tables<'tcx> {
+    pub tcx: TyCtxt<'tcx>,
+    pub def_ids: FxIndexSet<DefId>,
+    pub spans: FxIndexSet<Span>,
+    pub types: FxIndexSet<Ty<'tcx>>,
+}
+
+impl<'tcx> Tables<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Tables {
+            tcx,
+            def_ids: Default::default(),
+            spans: Default::default(),
+            types: Default::default(),
+        }
+    }
+
+    pub fn item_def_id(&self, item: &CrateItem) -> DefId {
+        self.internal_def_id(item.0)
+    }
+
+    pub fn crate_item(&mut self, did: DefId) -> CrateItem {
+        CrateItem(self.create_def_id(did))
+    }
+
+    pub fn create_def_id(&mut self, did: DefId) -> StableDefId {
+        StableDefId(self.def_ids.insert_full(did).0)
+    }
+
+    pub fn create_span(&mut self, span: Span) -> stable_mir::Span {
+        stable_mir::Span(self.spans.insert_full(span).0)
+    }
+
+    pub fn intern_ty(&mut self, ty: Ty<'tcx>) -> StableTy {
+        StableTy(self.types.insert_full(ty).0)
+    }
+
+    pub fn internal_def_id(&self, def_id: StableDefId) -> DefId {
+        self.def_ids[def_id.0]
+    }
+
+    pub fn internal_span(&self, span: stable_mir::Span) -> Span {
+        self.spans[span.0]
+    }
+
+    pub fn internal_ty(&self, ty: StableTy) -> Ty<'tcx> {
+        self.types[ty.0]
+    }
+}
+
+/// Get the internal `DefId` of a stable item. Must be called from within [`run`].
+pub fn item_def_id(item: &CrateItem) -> DefId {
+    with_tables(|t| t.item_def_id(item))
+}
+
+/// Get the internal span of a stable span. Must be called from within [`run`].
+pub fn internal_span(span: stable_mir::Span) -> Span {
+    with_tables(|t| t.internal_span(span))
+}
+
+fn with_tables<R>(mut f: impl FnMut(&mut Tables<'_>) -> R) -> R {
+    let mut ret = None;
+    stable_mir::with(|tables| tables.rustc_tables(&mut |t| ret = Some(f(t))));
+    ret.unwrap()
+}
+
+/// Run a callback with access to the stable MIR of the crate being compiled.
+///
+/// All stable MIR APIs, e.g. [`stable_mir::all_local_items`], must be called from within `f`.
+pub fn run(tcx: TyCtxt<'_>, f: impl FnOnce()) {
+    stable_mir::run(Tables::new(tcx), f);
+}
+
+/// Write the stable MIR of every body of the local crate as JSON, following the schema described
+/// in [`stable_mir::json`].
+pub fn write_smir_json(tcx: TyCtxt<'_>, w: &mut dyn io::Write) -> io::Result<()> {
+    let smir = Tables::new(tcx).smir_json();
+    serde_json::to_writer(&mut *w, &smir)?;
+    writeln!(w)
+}
@@ -0,0 +1,832 @@
+//! Module that implements what will become the rustc side of Stable MIR.
+//!
+//! This module is responsible for building Stable MIR components from internal components.
+//!
+//! This module is not intended to be invoked directly by users. It will eventually
+//! become the public API of rustc that will be invoked by the `stable_mir` crate.
+//!
+//! For now, we are developing everything inside `rustc`, thus, we keep this module private.
+
+use crate::rustc_internal::Tables;
+use crate::stable_mir::json;
+use crate::stable_mir::mir::{self as smir};
+use crate::stable_mir::ty::{self as sty, Opaque};
+use crate::stable_mir::{self, Context};
+use crate::very_unstable::hir;
+use crate::very_unstable::hir::def::DefKind;
+use crate::very_unstable::middle::mir;
+use crate::very_unstable::middle::mir::mono::MonoItem;
+use crate::very_unstable::middle::ty::{self, Ty, TyCtxt};
+use crate::very_unstable::span::def_id::{CrateNum, LOCAL_CRATE};
+use tracing::debug;
+
+impl<'tcx> Context for Tables<'tcx> {
+    fn local_crate(&self) -> stable_mir::Crate {
+        smir_crate(self.tcx, LOCAL_CRATE)
+    }
+
+    fn external_crates(&self) -> Vec<stable_mir::Crate> {
+        self.tcx.crates(()).iter().map(|crate_num| smir_crate(self.tcx, *crate_num)).collect()
+    }
+
+    fn find_crate(&self, name: &str) -> Option<stable_mir::Crate> {
+        [LOCAL_CRATE].iter().chain(self.tcx.crates(()).iter()).find_map(|crate_num| {
+            let crate_name = self.tcx.crate_name(*crate_num).to_string();
+            (name == crate_name).then(|| smir_crate(self.tcx, *crate_num))
+        })
+    }
+
+    fn all_local_items(&mut self) -> stable_mir::CrateItems {
+        self.tcx.mir_keys(()).iter().map(|item| self.crate_item(item.to_def_id())).collect()
+    }
+
+    fn entry_fn(&mut self) -> Option<stable_mir::CrateItem> {
+        Some(self.crate_item(self.tcx.entry_fn(())?.0))
+    }
+
+    fn mir_body(&mut self, item: &stable_mir::CrateItem) -> stable_mir::mir::Body {
+        let def_id = self.item_def_id(item);
+        let mir =
+            self.tcx.instance_mir(ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)));
+        mir.stable(self)
+    }
+
+    fn item_name(&mut self, def_id: stable_mir::DefId) -> stable_mir::Symbol {
+        self.tcx.def_path_str(self.internal_def_id(def_id))
+    }
+
+    fn item_kind(&mut self, item: stable_mir::CrateItem) -> stable_mir::ItemKind {
+        let def_id = self.item_def_id(&item);
+        match self.tcx.def_kind(def_id) {
+            DefKind::Fn | DefKind::AssocFn | DefKind::Ctor(..) => stable_mir::ItemKind::Fn,
+            DefKind::Closure => stable_mir::ItemKind::Closure,
+            DefKind::Generator => stable_mir::ItemKind::Generator,
+            DefKind::Const | DefKind::AssocConst => stable_mir::ItemKind::Const,
+            DefKind::Static(_) => stable_mir::ItemKind::Static,
+            DefKind::AnonConst | DefKind::InlineConst => stable_mir::ItemKind::AnonConst,
+            kind => unreachable!("item `{def_id:?}` of kind `{kind:?}` does not have a MIR body"),
+        }
+    }
+
+    fn item_span(&mut self, item: stable_mir::CrateItem) -> stable_mir::Span {
+        let span = self.tcx.def_span(self.item_def_id(&item));
+        self.create_span(span)
+    }
+
+    fn span_to_string(&mut self, span: stable_mir::Span) -> String {
+        self.tcx.sess.source_map().span_to_embeddable_string(self.internal_span(span))
+    }
+
+    fn ty_kind(&mut self, ty: sty::Ty) -> sty::TyKind {
+        self.internal_ty(ty).stable(self)
+    }
+
+    fn ty_to_string(&mut self, ty: sty::Ty) -> String {
+        self.internal_ty(ty).to_string()
+    }
+
+    fn rustc_tables(&mut self, f: &mut dyn FnMut(&mut Tables<'_>)) {
+        f(self)
+    }
+}
+
+impl<'tcx> Tables<'tcx> {
+    /// Collect the stable MIR of every body of the local crate and of every instance it code
+    /// generates, along with all the definitions, types and spans that these bodies refer to.
+    pub(crate) fn smir_json(&mut self) -> json::SmirJson {
+        let tcx = self.tcx;
+        let mut items = Vec::new();
+        for local_def_id in tcx.mir_keys(()) {
+            let def_id = local_def_id.to_def_id();
+            let item = self.crate_item(def_id);
+            let body = self.mir_body(&item);
+            let promoted = tcx.promoted_mir(def_id).iter().map(|body| body.stable(self)).collect();
+            items.push(json::Item {
+                def_id: item.0,
+                kind: self.item_kind(item),
+                generic: tcx.generics_of(def_id).requires_monomorphization(tcx),
+                span: self.item_span(item),
+                body,
+                promoted,
+            });
+        }
+
+        let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+        let mut instances: Vec<_> = codegen_units
+            .iter()
+            .flat_map(|cgu| cgu.items().keys())
+            .filter_map(|item| match *item {
+                MonoItem::Fn(instance) => Some((tcx.symbol_name(instance).name, instance)),
+                MonoItem::Static(_) | MonoItem::GlobalAsm(_) => None,
+            })
+            .collect();
+        // Inlined instances are part of several codegen units.
+        instances.sort_by_key(|&(symbol, _)| symbol);
+        instances.dedup_by_key(|&mut (symbol, _)| symbol);
+        let instances = instances
+            .into_iter()
+            .map(|(symbol, instance)| {
+                let body = instance.subst_mir_and_normalize_erasing_regions(
+                    tcx,
+                    ty::ParamEnv::reveal_all(),
+                    tcx.instance_mir(instance.def).clone(),
+                );
+                json::Instance {
+                    def_id: self.create_def_id(instance.def_id()),
+                    args: instance.substs.types().map(|ty| self.intern_ty(ty)).collect(),
+                    name: instance.to_string(),
+                    symbol: symbol.to_string(),
+                    body: body.stable(self),
+                }
+            })
+            .collect();
+
+        // Converting a type can intern more types, so keep going until we reach a fixpoint.
+        let mut types = Vec::new();
+        while types.len() < self.types.len() {
+            let ty = self.types[types.len()];
+            types.push(json::TypeEntry { kind: ty.stable(self), name: ty.to_string() });
+        }
+
+        json::SmirJson {
+            version: json::SCHEMA_VERSION,
+            krate: self.local_crate(),
+            items,
+            instances,
+            defs: self.def_ids.iter().map(|def_id| tcx.def_path_str(*def_id)).collect(),
+            types,
+            spans: self
+                .spans
+                .iter()
+                .map(|span| tcx.sess.source_map().span_to_embeddable_string(*span))
+                .collect(),
+        }
+    }
+}
+
+/// Build a stable mir crate from a given crate number.
+fn smir_crate(tcx: TyCtxt<'_>, crate_num: CrateNum) -> stable_mir::Crate {
+    let crate_name = tcx.crate_name(crate_num).to_string();
+    let is_local = crate_num == LOCAL_CRATE;
+    debug!(?crate_name, ?crate_num, "smir_crate");
+    stable_mir::Crate { id: crate_num.as_usize(), name: crate_name, is_local }
+}
+
+/// Trait used to convert between an internal MIR type to a Stable MIR type.
+pub(crate) trait Stable<'tcx> {
+    /// The stable representation of the type implementing Stable.
+    type T;
+    /// Converts an object to the equivalent Stable MIR representation.
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T;
+}
+
+impl<'tcx> Stable<'tcx> for mir::Body<'tcx> {
+    type T = smir::Body;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::Body {
+            blocks: self
+                .basic_blocks
+                .iter()
+                .map(|block| smir::BasicBlock {
+                    terminator: block.terminator().stable(tables),
+                    statements: block
+                        .statements
+                        .iter()
+                        .map(|statement| statement.stable(tables))
+                        .collect(),
+                    is_cleanup: block.is_cleanup,
+                })
+                .collect(),
+            locals: self.local_decls.iter().map(|decl| decl.stable(tables)).collect(),
+            arg_count: self.arg_count,
+            span: tables.create_span(self.span),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::LocalDecl<'tcx> {
+    type T = smir::LocalDecl;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::LocalDecl {
+            ty: tables.intern_ty(self.ty),
+            span: tables.create_span(self.source_info.span),
+            mutability: self.mutability.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Statement<'tcx> {
+    type T = smir::Statement;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::StatementKind::*;
+        let kind = match &self.kind {
+            Assign(assign) => {
+                smir::StatementKind::Assign(assign.0.stable(tables), assign.1.stable(tables))
+            }
+            FakeRead(fake_read_place) => smir::StatementKind::FakeRead(
+                fake_read_place.0.stable(tables),
+                fake_read_place.1.stable(tables),
+            ),
+            SetDiscriminant { place, variant_index } => smir::StatementKind::SetDiscriminant {
+                place: place.as_ref().stable(tables),
+                variant_index: variant_index.as_usize(),
+            },
+            Deinit(place) => smir::StatementKind::Deinit(place.as_ref().stable(tables)),
+            StorageLive(place) => smir::StatementKind::StorageLive(place.as_usize()),
+            StorageDead(place) => smir::StatementKind::StorageDead(place.as_usize()),
+            Retag(retag, place) => {
+                smir::StatementKind::Retag(retag.stable(tables), place.as_ref().stable(tables))
+            }
+            AscribeUserType(place_projection, variance) => smir::StatementKind::AscribeUserType {
+                place: place_projection.as_ref().0.stable(tables),
+                variance: variance.stable(tables),
+            },
+            Coverage(_) => smir::StatementKind::Coverage,
+            Intrinsic(intrinsic) => smir::StatementKind::Intrinsic(intrinsic.stable(tables)),
+            Nop => smir::StatementKind::Nop,
+        };
+        smir::Statement { kind, span: tables.create_span(self.source_info.span) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NonDivergingIntrinsic<'tcx> {
+    type T = smir::NonDivergingIntrinsic;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::NonDivergingIntrinsic::Assume(op) => {
+                smir::NonDivergingIntrinsic::Assume(op.stable(tables))
+            }
+            mir::NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                smir::NonDivergingIntrinsic::CopyNonOverlapping {
+                    src: copy.src.stable(tables),
+                    dst: copy.dst.stable(tables),
+                    count: copy.count.stable(tables),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::FakeReadCause {
+    type T = smir::FakeReadCause;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use mir::FakeReadCause::*;
+        match self {
+            ForMatchGuard => smir::FakeReadCause::ForMatchGuard,
+            ForMatchedPlace(_) => smir::FakeReadCause::ForMatchedPlace,
+            ForGuardBinding => smir::FakeReadCause::ForGuardBinding,
+            ForLet(_) => smir::FakeReadCause::ForLet,
+            ForIndex => smir::FakeReadCause::ForIndex,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::RetagKind {
+    type T = smir::RetagKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use mir::RetagKind;
+        match self {
+            RetagKind::FnEntry => smir::RetagKind::FnEntry,
+            RetagKind::TwoPhase => smir::RetagKind::TwoPhase,
+            RetagKind::Raw => smir::RetagKind::Raw,
+            RetagKind::Default => smir::RetagKind::Default,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Variance {
+    type T = smir::Variance;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            ty::Variance::Bivariant => smir::Variance::Bivariant,
+            ty::Variance::Contravariant => smir::Variance::Contravariant,
+            ty::Variance::Covariant => smir::Variance::Covariant,
+            ty::Variance::Invariant => smir::Variance::Invariant,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Rvalue<'tcx> {
+    type T = smir::Rvalue;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::Rvalue::*;
+        match self {
+            Use(op) => smir::Rvalue::Use(op.stable(tables)),
+            Repeat(op, len) => smir::Rvalue::Repeat(op.stable(tables), opaque(len)),
+            Ref(region, kind, place) => {
+                smir::Rvalue::Ref(opaque(region), kind.stable(tables), place.stable(tables))
+            }
+            ThreadLocalRef(def_id) => smir::Rvalue::ThreadLocalRef(tables.create_def_id(*def_id)),
+            AddressOf(mutability, place) => {
+                smir::Rvalue::AddressOf(mutability.stable(tables), place.stable(tables))
+            }
+            Len(place) => smir::Rvalue::Len(place.stable(tables)),
+            Cast(cast_kind, op, ty) => smir::Rvalue::Cast(
+                cast_kind.stable(tables),
+                op.stable(tables),
+                tables.intern_ty(*ty),
+            ),
+            BinaryOp(bin_op, ops) => smir::Rvalue::BinaryOp(
+                bin_op.stable(tables),
+                ops.0.stable(tables),
+                ops.1.stable(tables),
+            ),
+            CheckedBinaryOp(bin_op, ops) => smir::Rvalue::CheckedBinaryOp(
+                bin_op.stable(tables),
+                ops.0.stable(tables),
+                ops.1.stable(tables),
+            ),
+            NullaryOp(null_op, ty) => {
+                smir::Rvalue::NullaryOp(null_op.stable(tables), tables.intern_ty(*ty))
+            }
+            UnaryOp(un_op, op) => smir::Rvalue::UnaryOp(un_op.stable(tables), op.stable(tables)),
+            Discriminant(place) => smir::Rvalue::Discriminant(place.stable(tables)),
+            Aggregate(agg_kind, operands) => {
+                let operands = operands.iter().map(|op| op.stable(tables)).collect();
+                smir::Rvalue::Aggregate(agg_kind.stable(tables), operands)
+            }
+            ShallowInitBox(op, ty) => {
+                smir::Rvalue::ShallowInitBox(op.stable(tables), tables.intern_ty(*ty))
+            }
+            CopyForDeref(place) => smir::Rvalue::CopyForDeref(place.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Mutability {
+    type T = smir::Mutability;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use mir::Mutability::*;
+        match *self {
+            Not => smir::Mutability::Not,
+            Mut => smir::Mutability::Mut,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BorrowKind {
+    type T = smir::BorrowKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use mir::BorrowKind::*;
+        match *self {
+            Shared => smir::BorrowKind::Shared,
+            Shallow => smir::BorrowKind::Shallow,
+            Unique => smir::BorrowKind::Unique,
+            Mut { allow_two_phase_borrow } => smir::BorrowKind::Mut { allow_two_phase_borrow },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NullOp {
+    type T = smir::NullOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use mir::NullOp::*;
+        match self {
+            SizeOf => smir::NullOp::SizeOf,
+            AlignOf => smir::NullOp::AlignOf,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::CastKind {
+    type T = smir::CastKind;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::CastKind::*;
+        match self {
+            PointerExposeAddress => smir::CastKind::PointerExposeAddress,
+            PointerFromExposedAddress => smir::CastKind::PointerFromExposedAddress,
+            Pointer(cast) => smir::CastKind::Pointer(cast.stable(tables)),
+            DynStar => smir::CastKind::DynStar,
+            IntToInt => smir::CastKind::IntToInt,
+            FloatToInt => smir::CastKind::FloatToInt,
+            FloatToFloat => smir::CastKind::FloatToFloat,
+            IntToFloat => smir::CastKind::IntToFloat,
+            PtrToPtr => smir::CastKind::PtrToPtr,
+            FnPtrToPtr => smir::CastKind::FnPtrToPtr,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::adjustment::PointerCast {
+    type T = smir::PointerCast;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use ty::adjustment::PointerCast;
+        match self {
+            PointerCast::ReifyFnPointer => smir::PointerCast::ReifyFnPointer,
+            PointerCast::UnsafeFnPointer => smir::PointerCast::UnsafeFnPointer,
+            PointerCast::ClosureFnPointer(unsafety) => {
+                smir::PointerCast::ClosureFnPointer(unsafety.stable(tables))
+            }
+            PointerCast::MutToConstPointer => smir::PointerCast::MutToConstPointer,
+            PointerCast::ArrayToPointer => smir::PointerCast::ArrayToPointer,
+            PointerCast::Unsize => smir::PointerCast::Unsize,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Unsafety {
+    type T = sty::Safety;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            hir::Unsafety::Unsafe => sty::Safety::Unsafe,
+            hir::Unsafety::Normal => sty::Safety::Normal,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Movability {
+    type T = sty::Movability;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            hir::Movability::Static => sty::Movability::Static,
+            hir::Movability::Movable => sty::Movability::Movable,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AggregateKind<'tcx> {
+    type T = smir::AggregateKind;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::AggregateKind::Array(ty) => smir::AggregateKind::Array(tables.intern_ty(*ty)),
+            mir::AggregateKind::Tuple => smir::AggregateKind::Tuple,
+            mir::AggregateKind::Adt(def_id, var_idx, substs, user_ty_index, field_idx) => {
+                smir::AggregateKind::Adt(
+                    sty::AdtDef(tables.create_def_id(*def_id)),
+                    var_idx.as_usize(),
+                    substs.stable(tables),
+                    user_ty_index.map(|idx| idx.as_usize()),
+                    *field_idx,
+                )
+            }
+            mir::AggregateKind::Closure(def_id, substs) => smir::AggregateKind::Closure(
+                sty::ClosureDef(tables.create_def_id(def_id.to_def_id())),
+                substs.stable(tables),
+            ),
+            mir::AggregateKind::Generator(def_id, substs, movability) => {
+                smir::AggregateKind::Generator(
+                    sty::GeneratorDef(tables.create_def_id(def_id.to_def_id())),
+                    substs.stable(tables),
+                    movability.stable(tables),
+                )
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Place<'tcx> {
+    type T = smir::Place;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::Place {
+            local: self.local.as_usize(),
+            projection: self.projection.iter().map(|elem| elem.stable(tables)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::PlaceElem<'tcx> {
+    type T = smir::ProjectionElem;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::ProjectionElem::*;
+        match *self {
+            Deref => smir::ProjectionElem::Deref,
+            Field(field, ty) => smir::ProjectionElem::Field(field.as_usize(), tables.intern_ty(ty)),
+            Index(local) => smir::ProjectionElem::Index(local.as_usize()),
+            ConstantIndex { offset, min_length, from_end } => {
+                smir::ProjectionElem::ConstantIndex { offset, min_length, from_end }
+            }
+            Subslice { from, to, from_end } => {
+                smir::ProjectionElem::Subslice { from, to, from_end }
+            }
+            Downcast(_, variant) => smir::ProjectionElem::Downcast(variant.as_usize()),
+            OpaqueCast(ty) => smir::ProjectionElem::OpaqueCast(tables.intern_ty(ty)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Operand<'tcx> {
+    type T = smir::Operand;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::Operand::*;
+        match self {
+            Copy(place) => smir::Operand::Copy(place.stable(tables)),
+            Move(place) => smir::Operand::Move(place.stable(tables)),
+            Constant(c) => smir::Operand::Constant(c.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Constant<'tcx> {
+    type T = smir::Constant;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        smir::Constant {
+            span: tables.create_span(self.span),
+            ty: tables.intern_ty(self.ty()),
+            literal: opaque(&self.literal),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::UnOp {
+    type T = smir::UnOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::UnOp::Not => smir::UnOp::Not,
+            mir::UnOp::Neg => smir::UnOp::Neg,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BinOp {
+    type T = smir::BinOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use mir::BinOp;
+        match self {
+            BinOp::Add => smir::BinOp::Add,
+            BinOp::Sub => smir::BinOp::Sub,
+            BinOp::Mul => smir::BinOp::Mul,
+            BinOp::Div => smir::BinOp::Div,
+            BinOp::Rem => smir::BinOp::Rem,
+            BinOp::BitXor => smir::BinOp::BitXor,
+            BinOp::BitAnd => smir::BinOp::BitAnd,
+            BinOp::BitOr => smir::BinOp::BitOr,
+            BinOp::Shl => smir::BinOp::Shl,
+            BinOp::Shr => smir::BinOp::Shr,
+            BinOp::Eq => smir::BinOp::Eq,
+            BinOp::Lt => smir::BinOp::Lt,
+            BinOp::Le => smir::BinOp::Le,
+            BinOp::Ne => smir::BinOp::Ne,
+            BinOp::Ge => smir::BinOp::Ge,
+            BinOp::Gt => smir::BinOp::Gt,
+            BinOp::Offset => smir::BinOp::Offset,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::GeneratorKind {
+    type T = smir::GeneratorKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use hir::{AsyncGeneratorKind, GeneratorKind};
+        match self {
+            GeneratorKind::Async(async_gen) => {
+                let async_gen = match async_gen {
+                    AsyncGeneratorKind::Block => smir::AsyncGeneratorKind::Block,
+                    AsyncGeneratorKind::Closure => smir::AsyncGeneratorKind::Closure,
+                    AsyncGeneratorKind::Fn => smir::AsyncGeneratorKind::Fn,
+                };
+                smir::GeneratorKind::Async(async_gen)
+            }
+            GeneratorKind::Gen => smir::GeneratorKind::Gen,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AssertMessage<'tcx> {
+    type T = smir::AssertMessage;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::AssertKind;
+        match self {
+            AssertKind::BoundsCheck { len, index } => smir::AssertMessage::BoundsCheck {
+                len: len.stable(tables),
+                index: index.stable(tables),
+            },
+            AssertKind::Overflow(bin_op, op1, op2) => smir::AssertMessage::Overflow(
+                bin_op.stable(tables),
+                op1.stable(tables),
+                op2.stable(tables),
+            ),
+            AssertKind::OverflowNeg(op) => smir::AssertMessage::OverflowNeg(op.stable(tables)),
+            AssertKind::DivisionByZero(op) => {
+                smir::AssertMessage::DivisionByZero(op.stable(tables))
+            }
+            AssertKind::RemainderByZero(op) => {
+                smir::AssertMessage::RemainderByZero(op.stable(tables))
+            }
+            AssertKind::ResumedAfterReturn(generator) => {
+                smir::AssertMessage::ResumedAfterReturn(generator.stable(tables))
+            }
+            AssertKind::ResumedAfterPanic(generator) => {
+                smir::AssertMessage::ResumedAfterPanic(generator.stable(tables))
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::InlineAsmOperand<'tcx> {
+    type T = smir::InlineAsmOperand;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::InlineAsmOperand;
+
+        let (in_value, out_place) = match self {
+            InlineAsmOperand::In { value, .. } => (Some(value.stable(tables)), None),
+            InlineAsmOperand::Out { place, .. } => {
+                (None, place.as_ref().map(|place| place.stable(tables)))
+            }
+            InlineAsmOperand::InOut { in_value, out_place, .. } => (
+                Some(in_value.stable(tables)),
+                out_place.as_ref().map(|place| place.stable(tables)),
+            ),
+            InlineAsmOperand::Const { .. }
+            | InlineAsmOperand::SymFn { .. }
+            | InlineAsmOperand::SymStatic { .. } => (None, None),
+        };
+
+        smir::InlineAsmOperand { in_value, out_place, raw_rpr: format!("{self:?}") }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Terminator<'tcx> {
+    type T = smir::Terminator;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::TerminatorKind::*;
+        let kind = match &self.kind {
+            Goto { target } => smir::TerminatorKind::Goto { target: target.as_usize() },
+            SwitchInt { discr, targets } => smir::TerminatorKind::SwitchInt {
+                discr: discr.stable(tables),
+                targets: targets
+                    .iter()
+                    .map(|(value, target)| smir::SwitchTarget { value, target: target.as_usize() })
+                    .collect(),
+                otherwise: targets.otherwise().as_usize(),
+            },
+            Resume => smir::TerminatorKind::Resume,
+            Abort => smir::TerminatorKind::Abort,
+            Return => smir::TerminatorKind::Return,
+            Unreachable => smir::TerminatorKind::Unreachable,
+            Drop { place, target, unwind } => smir::TerminatorKind::Drop {
+                place: place.stable(tables),
+                target: target.as_usize(),
+                unwind: unwind.map(|block| block.as_usize()),
+            },
+            DropAndReplace { place, value, target, unwind } => {
+                smir::TerminatorKind::DropAndReplace {
+                    place: place.stable(tables),
+                    value: value.stable(tables),
+                    target: target.as_usize(),
+                    unwind: unwind.map(|block| block.as_usize()),
+                }
+            }
+            Call { func, args, destination, target, cleanup, from_hir_call: _, fn_span: _ } => {
+                smir::TerminatorKind::Call {
+                    func: func.stable(tables),
+                    args: args.iter().map(|arg| arg.stable(tables)).collect(),
+                    destination: destination.stable(tables),
+                    target: target.map(|t| t.as_usize()),
+                    cleanup: cleanup.map(|t| t.as_usize()),
+                }
+            }
+            Assert { cond, expected, msg, target, cleanup } => smir::TerminatorKind::Assert {
+                cond: cond.stable(tables),
+                expected: *expected,
+                msg: msg.stable(tables),
+                target: target.as_usize(),
+                cleanup: cleanup.map(|t| t.as_usize()),
+            },
+            Yield { value, resume, resume_arg, drop } => smir::TerminatorKind::Yield {
+                value: value.stable(tables),
+                resume: resume.as_usize(),
+                resume_arg: resume_arg.stable(tables),
+                drop: drop.map(|d| d.as_usize()),
+            },
+            GeneratorDrop => smir::TerminatorKind::GeneratorDrop,
+            // `FalseEdge` and `FalseUnwind` are removed during drop elaboration, so they only
+            // show up in MIR that was never optimized. They behave like a plain `goto`.
+            FalseEdge { real_target, .. } | FalseUnwind { real_target, .. } => {
+                smir::TerminatorKind::Goto { target: real_target.as_usize() }
+            }
+            InlineAsm { template, operands, options, line_spans, destination, cleanup } => {
+                smir::TerminatorKind::InlineAsm {
+                    template: format!("{template:?}"),
+                    operands: operands.iter().map(|operand| operand.stable(tables)).collect(),
+                    options: format!("{options:?}"),
+                    line_spans: format!("{line_spans:?}"),
+                    destination: destination.map(|d| d.as_usize()),
+                    cleanup: cleanup.map(|c| c.as_usize()),
+                }
+            }
+        };
+        smir::Terminator { kind, span: tables.create_span(self.source_info.span) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::SubstsRef<'tcx> {
+    type T = sty::GenericArgs;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        sty::GenericArgs(self.iter().map(|arg| arg.unpack().stable(tables)).collect())
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::GenericArgKind<'tcx> {
+    type T = sty::GenericArgKind;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            ty::GenericArgKind::Lifetime(region) => sty::GenericArgKind::Lifetime(opaque(region)),
+            ty::GenericArgKind::Type(ty) => sty::GenericArgKind::Type(tables.intern_ty(*ty)),
+            ty::GenericArgKind::Const(cnst) => sty::GenericArgKind::Const(opaque(cnst)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for Ty<'tcx> {
+    type T = sty::TyKind;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let rigid = match self.kind() {
+            ty::Bool => sty::RigidTy::Bool,
+            ty::Char => sty::RigidTy::Char,
+            ty::Int(int_ty) => sty::RigidTy::Int(match int_ty {
+                ty::IntTy::Isize => sty::IntTy::Isize,
+                ty::IntTy::I8 => sty::IntTy::I8,
+                ty::IntTy::I16 => sty::IntTy::I16,
+                ty::IntTy::I32 => sty::IntTy::I32,
+                ty::IntTy::I64 => sty::IntTy::I64,
+                ty::IntTy::I128 => sty::IntTy::I128,
+            }),
+            ty::Uint(uint_ty) => sty::RigidTy::Uint(match uint_ty {
+                ty::UintTy::Usize => sty::UintTy::Usize,
+                ty::UintTy::U8 => sty::UintTy::U8,
+                ty::UintTy::U16 => sty::UintTy::U16,
+                ty::UintTy::U32 => sty::UintTy::U32,
+                ty::UintTy::U64 => sty::UintTy::U64,
+                ty::UintTy::U128 => sty::UintTy::U128,
+            }),
+            ty::Float(float_ty) => sty::RigidTy::Float(match float_ty {
+                ty::FloatTy::F32 => sty::FloatTy::F32,
+                ty::FloatTy::F64 => sty::FloatTy::F64,
+            }),
+            ty::Adt(adt_def, substs) => sty::RigidTy::Adt(
+                sty::AdtDef(tables.create_def_id(adt_def.did())),
+                substs.stable(tables),
+            ),
+            ty::Foreign(def_id) => {
+                sty::RigidTy::Foreign(sty::ForeignDef(tables.create_def_id(*def_id)))
+            }
+            ty::Str => sty::RigidTy::Str,
+            ty::Array(ty, constant) => sty::RigidTy::Array(tables.intern_ty(*ty), opaque(constant)),
+            ty::Slice(ty) => sty::RigidTy::Slice(tables.intern_ty(*ty)),
+            ty::RawPtr(ty::TypeAndMut { ty, mutbl }) => {
+                sty::RigidTy::RawPtr(tables.intern_ty(*ty), mutbl.stable(tables))
+            }
+            ty::Ref(region, ty, mutbl) => {
+                sty::RigidTy::Ref(opaque(region), tables.intern_ty(*ty), mutbl.stable(tables))
+            }
+            ty::FnDef(def_id, substs) => sty::RigidTy::FnDef(
+                sty::FnDef(tables.create_def_id(*def_id)),
+                substs.stable(tables),
+            ),
+            ty::FnPtr(poly_fn_sig) => {
+                let fn_sig = poly_fn_sig.skip_binder();
+                sty::RigidTy::FnPtr(sty::PolyFnSig {
+                    inputs: fn_sig.inputs().iter().map(|ty| tables.intern_ty(*ty)).collect(),
+                    output: tables.intern_ty(fn_sig.output()),
+                    c_variadic: fn_sig.c_variadic,
+                    unsafety: fn_sig.unsafety.stable(tables),
+                    abi: fn_sig.abi.name().to_string(),
+                })
+            }
+            ty::Dynamic(..) => sty::RigidTy::Dynamic(opaque(self)),
+            ty::Closure(def_id, substs) => sty::RigidTy::Closure(
+                sty::ClosureDef(tables.create_def_id(*def_id)),
+                substs.stable(tables),
+            ),
+            ty::Generator(def_id, substs, movability) => sty::RigidTy::Generator(
+                sty::GeneratorDef(tables.create_def_id(*def_id)),
+                substs.stable(tables),
+                movability.stable(tables),
+            ),
+            ty::Never => sty::RigidTy::Never,
+            ty::Tuple(fields) => {
+                sty::RigidTy::Tuple(fields.iter().map(|ty| tables.intern_ty(ty)).collect())
+            }
+            ty::Alias(kind, alias_ty) => {
+                let kind = match kind {
+                    ty::AliasKind::Projection => sty::AliasKind::Projection,
+                    ty::AliasKind::Opaque => sty::AliasKind::Opaque,
+                };
+                return sty::TyKind::Alias(
+                    kind,
+                    sty::AliasTy {
+                        def_id: sty::AliasDef(tables.create_def_id(alias_ty.def_id)),
+                        args: alias_ty.substs.stable(tables),
+                    },
+                );
+            }
+            ty::Param(param_ty) => {
+                return sty::TyKind::Param(sty::ParamTy {
+                    index: param_ty.index,
+                    name: param_ty.name.to_string(),
+                });
+            }
+            ty::GeneratorWitness(_)
+            | ty::Bound(..)
+            | ty::Placeholder(..)
+            | ty::Infer(_)
+            | ty::Error(_) => return sty::TyKind::Other(opaque(self)),
+        };
+        sty::TyKind::RigidTy(rigid)
+    }
+}
+
+/// Build an [`Opaque`] from the `Display` rendering of an internal compiler value.
+fn opaque<T: std::fmt::Display>(value: &T) -> Opaque {
+    Opaque(value.to_string())
+}
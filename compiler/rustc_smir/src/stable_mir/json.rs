@@ -0,0 +1,84 @@
+//! The versioned schema written by `-Zunpretty=smir-json`.
+//!
+//! The output is a single JSON object of type [`SmirJson`]. All stable MIR types are serialized
+//! with serde's default representation: structs become objects, unit enum variants become
+//! strings and all other enum variants become single-key objects, e.g. `{"Goto": {"target": 1}}`.
+//!
+//! Identifiers are indices into the tables of the top level object:
+//! * a [`DefId`] is an index into [`SmirJson::defs`],
+//! * a [`Ty`](super::ty::Ty) is an index into [`SmirJson::types`],
+//! * a [`Span`] is an index into [`SmirJson::spans`].
+//!
+//! [`SCHEMA_VERSION`] is bumped every time the meaning of an existing field changes or a field
+//! is removed. Adding new fields or enum variants does not bump the version, so consumers should
+//! ignore data they do not know about.
+
+use super::mir::Body;
+use super::ty::{Ty, TyKind};
+use super::{Crate, DefId, ItemKind, Span, Symbol};
+use serde::Serialize;
+
+/// The version of the schema described in this module.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The top level object of a stable MIR dump.
+#[derive(Clone, Debug, Serialize)]
+pub struct SmirJson {
+    /// Always [`SCHEMA_VERSION`].
+    pub version: u32,
+    #[serde(rename = "crate")]
+    pub krate: Crate,
+    /// Every item of the local crate that has a MIR body, in definition order.
+    pub items: Vec<Item>,
+    /// Every function instance that the local crate code generates, sorted by symbol name.
+    pub instances: Vec<Instance>,
+    /// The fully qualified paths of all definitions referenced by this dump.
+    pub defs: Vec<Symbol>,
+    /// All types referenced by this dump, including types only mentioned by other types.
+    pub types: Vec<TypeEntry>,
+    /// All spans referenced by this dump, rendered as `file:line:col: line:col`.
+    pub spans: Vec<String>,
+}
+
+/// An item of the local crate with its MIR.
+#[derive(Clone, Debug, Serialize)]
+pub struct Item {
+    pub def_id: DefId,
+    pub kind: ItemKind,
+    /// Whether this item has type or const parameters, in which case its body is polymorphic
+    /// and contains [`TyKind::Param`] types.
+    pub generic: bool,
+    pub span: Span,
+    /// The optimized MIR for functions, or the MIR used for const evaluation of constants
+    /// and statics.
+    pub body: Body,
+    /// The bodies of constants promoted out of `body`, referred to as `promoted[N]` in
+    /// constant operands.
+    pub promoted: Vec<Body>,
+}
+
+/// A function instance code generated by the local crate, with all of its generic parameters
+/// substituted. This includes instances of generic items of other crates and compiler generated
+/// shims such as drop glue.
+#[derive(Clone, Debug, Serialize)]
+pub struct Instance {
+    /// The item or shim this is an instance of. For shims, this is the item the shim is
+    /// generated for, e.g. the `drop_in_place` function for drop glue.
+    pub def_id: DefId,
+    /// The types that the generic parameters of the item are substituted with.
+    pub args: Vec<Ty>,
+    /// The instance as printed by the compiler, e.g. `generic::<u64>`.
+    pub name: String,
+    /// The mangled name of the instance in the object file.
+    pub symbol: String,
+    /// The optimized MIR of the item, after substituting its generic parameters.
+    pub body: Body,
+}
+
+/// An entry of the type table.
+#[derive(Clone, Debug, Serialize)]
+pub struct TypeEntry {
+    pub kind: TyKind,
+    /// The type as printed by the compiler in diagnostics.
+    pub name: String,
+}
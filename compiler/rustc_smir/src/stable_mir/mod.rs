@@ -0,0 +1,191 @@
+//! Module that implements the public interface to the Stable MIR.
+//!
+//! This module shall contain all type definitions and APIs that we expect 3P tools to invoke to
+//! interact with the compiler.
+//!
+//! The goal is to eventually move this module to its own crate which shall be published on
+//! [crates.io](https://crates.io).
+//!
+//! ## Note:
+//!
+//! There shouldn't be any direct references to internal compiler constructs in this module.
+//! If you need an internal construct, consider using `rustc_internal` or `rustc_smir`.
+
+use serde::Serialize;
+use std::cell::Cell;
+
+use self::ty::Ty;
+use crate::rustc_internal;
+
+pub mod json;
+pub mod mir;
+pub mod ty;
+
+/// Use String for now but we should replace it.
+pub type Symbol = String;
+
+/// The number that identifies a crate.
+pub type CrateNum = usize;
+
+/// A unique identification number for each item accessible for the current compilation unit.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
+pub struct DefId(pub(crate) usize);
+
+/// A unique identification number for each source span accessible for the current compilation
+/// unit.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
+pub struct Span(pub(crate) usize);
+
+impl Span {
+    /// Render this span as `file:line:col: line:col`, the same format used by `-Zunpretty=mir`.
+    pub fn render(&self) -> String {
+        with(|cx| cx.span_to_string(*self))
+    }
+}
+
+/// A list of crate items.
+pub type CrateItems = Vec<CrateItem>;
+
+/// Holds information about a crate.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct Crate {
+    pub id: CrateNum,
+    pub name: Symbol,
+    pub is_local: bool,
+}
+
+/// Holds information about an item in the crate.
+/// For now, it only stores the item DefId. Use functions inside `rustc_internal` module to
+/// use this item.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct CrateItem(pub(crate) DefId);
+
+impl CrateItem {
+    /// The body of this item. This is the optimized MIR for functions and the const-eval MIR
+    /// for constants and statics.
+    pub fn body(&self) -> mir::Body {
+        with(|cx| cx.mir_body(self))
+    }
+
+    /// The fully qualified path of this item, e.g. `my_crate::module::function`.
+    pub fn name(&self) -> Symbol {
+        with(|cx| cx.item_name(self.0))
+    }
+
+    /// The kind of item this is.
+    pub fn kind(&self) -> ItemKind {
+        with(|cx| cx.item_kind(*self))
+    }
+
+    /// The span of this item's definition.
+    pub fn span(&self) -> Span {
+        with(|cx| cx.item_span(*self))
+    }
+
+    /// The identifier of this item.
+    pub fn def_id(&self) -> DefId {
+        self.0
+    }
+}
+
+/// The kinds of items that have a MIR body.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub enum ItemKind {
+    Fn,
+    Closure,
+    Generator,
+    Const,
+    Static,
+    AnonConst,
+}
+
+/// Return the function where execution starts if the current
+/// crate defines that. This is usually `main`, but could be
+/// `start` if the crate is a no-std crate.
+pub fn entry_fn() -> Option<CrateItem> {
+    with(|cx| cx.entry_fn())
+}
+
+/// Access to the local crate.
+pub fn local_crate() -> Crate {
+    with(|cx| cx.local_crate())
+}
+
+/// Try to find a crate with the given name.
+pub fn find_crate(name: &str) -> Option<Crate> {
+    with(|cx| cx.find_crate(name))
+}
+
+/// Access to all external crates.
+pub fn external_crates() -> Vec<Crate> {
+    with(|cx| cx.external_crates())
+}
+
+/// Retrieve all items in the local crate that have a MIR associated with them.
+pub fn all_local_items() -> CrateItems {
+    with(|cx| cx.all_local_items())
+}
+
+/// The type-level representation of the interface between the compiler and a stable MIR
+/// consumer. It is implemented by `rustc_smir` on top of the internal compiler data structures.
+pub trait Context {
+    fn entry_fn(&mut self) -> Option<CrateItem>;
+    /// Retrieve all items of the local crate that have a MIR associated with them.
+    fn all_local_items(&mut self) -> CrateItems;
+    fn mir_body(&mut self, item: &CrateItem) -> mir::Body;
+    /// Get information about the local crate.
+    fn local_crate(&self) -> Crate;
+    /// Retrieve a list of all external crates.
+    fn external_crates(&self) -> Vec<Crate>;
+
+    /// Find a crate with the given name.
+    fn find_crate(&self, name: &str) -> Option<Crate>;
+
+    /// Prints the name of given `DefId`
+    fn item_name(&mut self, def_id: DefId) -> Symbol;
+
+    /// The kind of the given item.
+    fn item_kind(&mut self, item: CrateItem) -> ItemKind;
+
+    /// The span of the given item's definition.
+    fn item_span(&mut self, item: CrateItem) -> Span;
+
+    /// Renders a span as `file:line:col: line:col`.
+    fn span_to_string(&mut self, span: Span) -> String;
+
+    /// Obtain the representation of a type.
+    fn ty_kind(&mut self, ty: Ty) -> ty::TyKind;
+
+    /// Render a type the way the compiler prints it in diagnostics.
+    fn ty_to_string(&mut self, ty: Ty) -> String;
+
+    /// HACK: Until we have fully stable consumers, we need an escape hatch
+    /// to get `DefId`s out of `CrateItem`s.
+    fn rustc_tables(&mut self, f: &mut dyn FnMut(&mut rustc_internal::Tables<'_>));
+}
+
+// A thread local variable that stores a pointer to the tables mapping between TyCtxt
+// datastructures and stable MIR datastructures
+scoped_tls::scoped_thread_local! (static TLV: Cell<*mut ()>);
+
+pub fn run(mut context: impl Context, f: impl FnOnce()) {
+    assert!(!TLV.is_set());
+    fn g<'a>(mut context: &mut (dyn Context + 'a), f: impl FnOnce()) {
+        let ptr: *mut () = &mut context as *mut &mut _ as _;
+        TLV.set(&Cell::new(ptr), || {
+            f();
+        });
+    }
+    g(&mut context, f);
+}
+
+/// Loads the current context and calls a function with it.
+/// Do not nest these, as that will ICE.
+pub(crate) fn with<R>(f: impl FnOnce(&mut dyn Context) -> R) -> R {
+    assert!(TLV.is_set());
+    TLV.with(|tlv| {
+        let ptr = tlv.get();
+        assert!(!ptr.is_null());
+        f(unsafe { *(ptr as *mut &mut dyn Context) })
+    })
+}
@@ -0,0 +1,180 @@
+use super::{mir::Mutability, with, DefId, Symbol};
+use serde::Serialize;
+
+/// An interned type. Use [`Ty::kind`] to inspect it.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize)]
+pub struct Ty(pub(crate) usize);
+
+impl Ty {
+    pub fn kind(&self) -> TyKind {
+        with(|context| context.ty_kind(*self))
+    }
+
+    /// Render this type the way the compiler prints it in diagnostics.
+    pub fn render(&self) -> String {
+        with(|context| context.ty_to_string(*self))
+    }
+}
+
+/// A textual representation of a compiler entity that does not have a stable
+/// counterpart yet, e.g. constant values, regions or bound variables.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct Opaque(pub String);
+
+impl Opaque {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+pub type Const = Opaque;
+pub type Region = Opaque;
+
+#[derive(Clone, Debug, Serialize)]
+pub enum TyKind {
+    RigidTy(RigidTy),
+    /// An alias such as `<T as Trait>::Assoc` or an opaque `impl Trait` type that could not be
+    /// normalized in this context.
+    Alias(AliasKind, AliasTy),
+    /// A type parameter, e.g. `T` in `fn foo<T>()`.
+    Param(ParamTy),
+    /// Bound variables, placeholders and inference variables. These should not show up in
+    /// the MIR of a body that type checked successfully.
+    Other(Opaque),
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum RigidTy {
+    Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    Adt(AdtDef, GenericArgs),
+    Foreign(ForeignDef),
+    Str,
+    Array(Ty, Const),
+    Slice(Ty),
+    RawPtr(Ty, Mutability),
+    Ref(Region, Ty, Mutability),
+    FnDef(FnDef, GenericArgs),
+    FnPtr(PolyFnSig),
+    Closure(ClosureDef, GenericArgs),
+    Generator(GeneratorDef, GenericArgs, Movability),
+    Dynamic(Opaque),
+    Never,
+    Tuple(Vec<Ty>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Movability {
+    Static,
+    Movable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum AliasKind {
+    Projection,
+    Opaque,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AliasTy {
+    pub def_id: AliasDef,
+    pub args: GenericArgs,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ParamTy {
+    pub index: u32,
+    pub name: Symbol,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PolyFnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Ty,
+    pub c_variadic: bool,
+    pub unsafety: Safety,
+    pub abi: Symbol,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Safety {
+    Unsafe,
+    Normal,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct ForeignDef(pub(crate) DefId);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct FnDef(pub(crate) DefId);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct ClosureDef(pub(crate) DefId);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct GeneratorDef(pub(crate) DefId);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct AdtDef(pub(crate) DefId);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct AliasDef(pub(crate) DefId);
+
+macro_rules! def_id_accessors {
+    ($($ty:ident),*) => {
+        $(
+            impl $ty {
+                pub fn def_id(&self) -> DefId {
+                    self.0
+                }
+
+                /// The fully qualified path of this definition.
+                pub fn name(&self) -> Symbol {
+                    with(|cx| cx.item_name(self.0))
+                }
+            }
+        )*
+    };
+}
+
+def_id_accessors!(ForeignDef, FnDef, ClosureDef, GeneratorDef, AdtDef, AliasDef);
+
+/// A list of generic arguments, e.g. the `u32, String` in `HashMap<u32, String>`.
+#[derive(Clone, Debug, Serialize)]
+pub struct GenericArgs(pub Vec<GenericArgKind>);
+
+#[derive(Clone, Debug, Serialize)]
+pub enum GenericArgKind {
+    Lifetime(Region),
+    Type(Ty),
+    Const(Const),
+}
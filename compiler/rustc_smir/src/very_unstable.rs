@@ -16,6 +16,7 @@ macro_rules! crates {
 
 crates! {
     rustc_borrowck -> borrowck,
+    rustc_data_structures -> data_structures,
     rustc_driver -> driver,
     rustc_hir -> hir,
     rustc_interface -> interface,
@@ -23,5 +24,6 @@ macro_rules! crates {
     rustc_mir_dataflow -> dataflow,
     rustc_mir_transform -> transform,
     rustc_serialize -> serialize,
+    rustc_span -> span,
     rustc_trait_selection -> trait_selection,
 }
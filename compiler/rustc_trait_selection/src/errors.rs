@@ -21,6 +21,13 @@ pub struct UnableToConstructConstantValue<'a> {
     pub unevaluated: ty::UnevaluatedConst<'a>,
 }
 
+#[derive(Diagnostic)]
+#[diag(trait_selection_overflow_graph_write_failed)]
+pub struct OverflowGraphWriteFailed<'a> {
+    pub path: &'a std::path::Path,
+    pub error: std::io::Error,
+}
+
 #[derive(Diagnostic)]
 #[help]
 #[diag(trait_selection_auto_deref_reached_recursion_limit, code = "E0055")]
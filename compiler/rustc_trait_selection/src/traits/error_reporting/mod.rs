@@ -1,12 +1,13 @@
 mod ambiguity;
 pub mod method_chain;
 pub mod on_unimplemented;
+mod overflow;
 pub mod suggestions;
 
 use super::{
     FulfillmentError, FulfillmentErrorCode, MismatchedProjectionTypes, Obligation, ObligationCause,
     ObligationCauseCode, ObligationCtxt, OutputTypeParameterMismatch, Overflow,
-    PredicateObligation, SelectionContext, SelectionError, TraitNotObjectSafe,
+    PredicateObligation, SelectionContext, SelectionError, TraitNotObjectSafe, TraitQueryMode,
 };
 use crate::infer::error_reporting::{TyCategory, TypeAnnotationNeeded as ErrorCode};
 use crate::infer::type_variable::{TypeVariableOrigin, TypeVariableOriginKind};
@@ -40,6 +41,7 @@
     self, SubtypePredicate, ToPolyTraitRef, ToPredicate, TraitRef, Ty, TyCtxt, TypeFoldable,
     TypeVisitable,
 };
+use rustc_session::config::ProofTreeFormat;
 use rustc_session::Limit;
 use rustc_span::def_id::LOCAL_CRATE;
 use rustc_span::symbol::sym;
@@ -530,19 +532,46 @@ fn report_overflow_obligation<T>(
     {
         let predicate = obligation.predicate.clone().to_predicate(self.tcx);
         let predicate = self.resolve_vars_if_possible(predicate);
+        let predicate_obligation = obligation.with(self.tcx, predicate);
+        self.dump_proof_tree(&predicate_obligation, &predicate_obligation);
+        let (mut chain, root_code) = overflow::requirement_chain(obligation.cause.code());
+        for requirement in &mut chain {
+            requirement.trait_pred = self.resolve_vars_if_possible(requirement.trait_pred);
+        }
+        if let Some(path) = &self.tcx.sess.opts.unstable_opts.dump_cycle_graph {
+            overflow::dump_overflow_graph(self.tcx, path, &chain, predicate);
+        }
+        let segments = if self.tcx.sess.opts.unstable_opts.compact_cycles {
+            overflow::compact_requirements(&chain)
+        } else {
+            None
+        };
         self.report_overflow_error(
             &predicate,
             obligation.cause.span,
             suggest_increasing_limit,
-            |err| {
-                self.note_obligation_cause_code(
-                    err,
-                    predicate,
-                    obligation.param_env,
-                    obligation.cause.code(),
-                    &mut vec![],
-                    &mut Default::default(),
-                );
+            |err| match (segments, chain.last()) {
+                (Some(segments), Some(root)) => {
+                    overflow::note_compact_requirements(self.tcx, err, &chain, &segments);
+                    self.note_obligation_cause_code(
+                        err,
+                        root.trait_pred,
+                        obligation.param_env,
+                        root_code,
+                        &mut vec![],
+                        &mut Default::default(),
+                    );
+                }
+                _ => {
+                    self.note_obligation_cause_code(
+                        err,
+                        predicate,
+                        obligation.param_env,
+                        obligation.cause.code(),
+                        &mut vec![],
+                        &mut Default::default(),
+                    );
+                }
             },
         );
     }
@@ -1505,6 +1534,12 @@ fn is_recursive_obligation(
         obligated_types: &mut Vec<Ty<'tcx>>,
         cause_code: &ObligationCauseCode<'tcx>,
     ) -> bool;
+
+    fn dump_proof_tree(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        root_obligation: &PredicateObligation<'tcx>,
+    );
 }
 
 impl<'tcx> InferCtxtPrivExt<'tcx> for TypeErrCtxt<'_, 'tcx> {
@@ -1555,6 +1590,11 @@ fn report_fulfillment_error(
         error: &FulfillmentError<'tcx>,
         body_id: Option<hir::BodyId>,
     ) {
+        // Cycles are reported as overflows, which dump their proof tree themselves.
+        if !matches!(error.code, FulfillmentErrorCode::CodeCycle(_)) {
+            self.dump_proof_tree(&error.obligation, &error.root_obligation);
+        }
+
         match error.code {
             FulfillmentErrorCode::CodeSelectionError(ref selection_error) => {
                 self.report_selection_error(
@@ -2748,6 +2788,36 @@ fn is_recursive_obligation(
         }
         false
     }
+
+    /// Prints the proof tree of `root_obligation` for `-Z dump-proof-tree`, if the predicate of
+    /// `obligation` or `root_obligation` matches its filter.
+    fn dump_proof_tree(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        root_obligation: &PredicateObligation<'tcx>,
+    ) {
+        let Some(filter) = &self.tcx.sess.opts.unstable_opts.dump_proof_tree else { return };
+        let obligation = self.resolve_vars_if_possible(obligation.clone());
+        let root_obligation = self.resolve_vars_if_possible(root_obligation.clone());
+        let matches = |obligation: &PredicateObligation<'tcx>| {
+            filter == "all" || obligation.predicate.to_string().contains(filter.as_str())
+        };
+        if !matches(&obligation) && !matches(&root_obligation) {
+            return;
+        }
+
+        // Evaluate the root obligation again from the start, in canonical mode so that an
+        // overflow ends up in the tree instead of being reported.
+        let root_obligation = Obligation { recursion_depth: 0, ..root_obligation };
+        let mut selcx = SelectionContext::with_query_mode(&self, TraitQueryMode::Canonical);
+        selcx.enable_proof_tree_recording();
+        let _ = selcx.evaluate_root_obligation(&root_obligation);
+        let proof_tree = selcx.take_proof_tree();
+        match self.tcx.sess.opts.unstable_opts.dump_proof_tree_format {
+            ProofTreeFormat::Text => eprint!("{}", proof_tree.to_text()),
+            ProofTreeFormat::Json => eprintln!("{}", proof_tree.to_json()),
+        }
+    }
 }
 
 /// Look for type `param` in an ADT being used only through a reference to confirm that suggesting
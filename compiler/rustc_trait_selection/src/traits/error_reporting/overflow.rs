@@ -0,0 +1,138 @@
+//! Reporting of the requirements that led to a trait overflow as a compact cycle, where the
+//! requirements that repeat are only listed once (see `-Zcompact-cycles`), and as a graph of
+//! the whole chain (see `-Zdump-cycle-graph`).
+
+use rustc_data_structures::compact_stack::{compact_stack, StackGraph, StackSegment};
+use rustc_errors::{pluralize, Diagnostic};
+use rustc_hir::def_id::DefId;
+use rustc_infer::traits::ObligationCauseCode;
+use rustc_middle::ty::{self, TyCtxt};
+use std::path::Path;
+
+use crate::errors::OverflowGraphWriteFailed;
+
+/// A trait predicate that had to hold for the overflowing obligation to be required.
+pub struct Requirement<'tcx> {
+    pub trait_pred: ty::PolyTraitPredicate<'tcx>,
+    /// The impl whose where clauses required the previous requirement, if any.
+    pub impl_def_id: Option<DefId>,
+}
+
+/// Returns the requirements that `code` was derived from, starting from the one closest to the
+/// overflowing obligation, as well as the cause of the root requirement.
+pub fn requirement_chain<'a, 'tcx>(
+    mut code: &'a ObligationCauseCode<'tcx>,
+) -> (Vec<Requirement<'tcx>>, &'a ObligationCauseCode<'tcx>) {
+    let mut chain = vec![];
+    loop {
+        match code {
+            ObligationCauseCode::ImplDerivedObligation(data) => {
+                chain.push(Requirement {
+                    trait_pred: data.derived.parent_trait_pred,
+                    impl_def_id: Some(data.impl_def_id),
+                });
+                code = &data.derived.parent_code;
+            }
+            ObligationCauseCode::BuiltinDerivedObligation(data)
+            | ObligationCauseCode::DerivedObligation(data) => {
+                chain.push(Requirement { trait_pred: data.parent_trait_pred, impl_def_id: None });
+                code = &data.parent_code;
+            }
+            _ => return (chain, code),
+        }
+    }
+}
+
+/// Splits `chain` into single requirements and repeating segments, or returns `None` if no
+/// requirement repeats. Requirements are the same if they are of the same trait and come from
+/// the same impl, as their types usually grow with every repetition.
+pub fn compact_requirements(chain: &[Requirement<'_>]) -> Option<Vec<StackSegment>> {
+    let keys: Vec<_> = chain
+        .iter()
+        .map(|requirement| (requirement.trait_pred.def_id(), requirement.impl_def_id))
+        .collect();
+    let segments = compact_stack(&keys);
+    segments
+        .iter()
+        .any(|segment| matches!(segment, StackSegment::Repeated { .. }))
+        .then_some(segments)
+}
+
+/// Adds a note for every requirement of `segments`, and a note with the number of repetitions
+/// after every repeating segment.
+pub fn note_compact_requirements<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    err: &mut Diagnostic,
+    chain: &[Requirement<'tcx>],
+    segments: &[StackSegment],
+) {
+    for &segment in segments {
+        match segment {
+            StackSegment::Frame(i) => note_requirement(tcx, err, &chain[i]),
+            StackSegment::Repeated { start, len, more } => {
+                for requirement in &chain[start..start + len] {
+                    note_requirement(tcx, err, requirement);
+                }
+                if len == 1 {
+                    err.note(&format!(
+                        "...and the last requirement repeats {more} more time{}",
+                        pluralize!(more)
+                    ));
+                } else {
+                    err.note(&format!(
+                        "...and the last {len} requirements repeat {more} more time{}",
+                        pluralize!(more)
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn note_requirement<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    err: &mut Diagnostic,
+    requirement: &Requirement<'tcx>,
+) {
+    let trait_pred = requirement.trait_pred;
+    let (self_ty, file) = tcx.short_ty_string(trait_pred.skip_binder().self_ty());
+    let msg = format!(
+        "required for `{self_ty}` to implement `{}`",
+        trait_pred.print_modifiers_and_trait_path()
+    );
+    match requirement.impl_def_id {
+        Some(impl_def_id) if impl_def_id.is_local() => {
+            err.span_note(tcx.def_span(impl_def_id), &msg);
+        }
+        _ => {
+            err.note(&msg);
+        }
+    }
+    if let Some(file) = file {
+        err.note(&format!("the full type name has been written to '{}'", file.display()));
+    }
+}
+
+/// Writes `chain` to `path` as a graph, from the root requirement to `overflowing`.
+pub fn dump_overflow_graph<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    path: &Path,
+    chain: &[Requirement<'tcx>],
+    overflowing: ty::Predicate<'tcx>,
+) {
+    let mut labels: Vec<_> = chain
+        .iter()
+        .rev()
+        .map(|requirement| match requirement.impl_def_id {
+            Some(impl_def_id) => {
+                format!("{} [{}]", requirement.trait_pred, tcx.def_path_str(impl_def_id))
+            }
+            None => requirement.trait_pred.to_string(),
+        })
+        .collect();
+    labels.push(overflowing.to_string());
+    let graph = StackGraph { name: "trait_overflow", labels: &labels, is_cycle: false };
+    if let Err(error) = graph.write_graphviz(path) {
+        tcx.sess.emit_warning(OverflowGraphWriteFailed { path, error });
+    }
+}
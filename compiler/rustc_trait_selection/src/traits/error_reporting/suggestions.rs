@@ -295,6 +295,14 @@ fn note_obligation_cause_for_async_await(
         next_code: Option<&ObligationCauseCode<'tcx>>,
     );
 
+    fn note_auto_trait_failure_chains(
+        &self,
+        err: &mut Diagnostic,
+        generator_did: DefId,
+        trait_pred: ty::TraitPredicate<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+    );
+
     fn note_obligation_cause_code<T>(
         &self,
         err: &mut Diagnostic,
@@ -2383,6 +2391,17 @@ fn note_obligation_cause_for_async_await(
             }
         }
 
+        if self.tcx.sess.opts.unstable_opts.explain_auto_traits
+            && let Some(generator_did) = outer_generator
+        {
+            self.note_auto_trait_failure_chains(
+                err,
+                generator_did,
+                trait_pred,
+                obligation.param_env,
+            );
+        }
+
         // Add a note for the item obligation that remains - normally a note pointing to the
         // bound that introduced the obligation (e.g. `T: Send`).
         debug!(?next_code);
@@ -2396,6 +2415,98 @@ fn note_obligation_cause_for_async_await(
         );
     }
 
+    /// Adds a note for every captured value and every value held across a yield point that
+    /// makes the generator `generator_did` not implement the auto trait of `trait_pred`, for
+    /// `-Z explain-auto-traits`. Each note shows the chain of types from the type of the value to
+    /// the type that does not implement the trait, like ``` `Rc<u8>` (field `rc`) inside `Foo`
+    /// inside `Vec<Foo>` ```.
+    fn note_auto_trait_failure_chains(
+        &self,
+        err: &mut Diagnostic,
+        generator_did: DefId,
+        trait_pred: ty::TraitPredicate<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let trait_def_id = trait_pred.def_id();
+        if !tcx.trait_is_auto(trait_def_id) {
+            return;
+        }
+        let trait_name = tcx.item_name(trait_def_id);
+        let (future_or_generator, an_await_or_yield) = if tcx.generator_is_async(generator_did) {
+            ("future", "an await")
+        } else {
+            ("generator", "a yield")
+        };
+
+        let generator_did_root = tcx.typeck_root_def_id(generator_did);
+        let generator_data = match &self.typeck_results {
+            Some(t) if t.hir_owner.to_def_id() == generator_did_root => GeneratorData::Local(&t),
+            _ if generator_did.is_local() => {
+                GeneratorData::Local(tcx.typeck(generator_did.expect_local()))
+            }
+            _ if let Some(generator_diag_data) = tcx.generator_diagnostic_data(generator_did) => {
+                GeneratorData::Foreign(generator_diag_data)
+            }
+            _ => return,
+        };
+
+        // The span, description and type of every value that is part of the generator.
+        let mut values = vec![];
+        if let GeneratorData::Local(typeck_results) = generator_data
+            && let Some(upvars) = tcx.upvars_mentioned(generator_did)
+        {
+            for (upvar_id, upvar) in upvars {
+                let upvar_ty = self.resolve_vars_if_possible(typeck_results.node_type(*upvar_id));
+                let name = tcx.hir().name(*upvar_id);
+                values.push((
+                    upvar.span,
+                    format!("`{name}` is captured by the {future_or_generator}"),
+                    tcx.erase_regions(upvar_ty),
+                ));
+            }
+        }
+        let interior_types = generator_data.get_generator_interior_types();
+        let mut seen_spans = FxHashSet::default();
+        for cause in interior_types.skip_binder() {
+            if !seen_spans.insert(cause.span) {
+                continue;
+            }
+            let value = match tcx.sess.source_map().span_to_snippet(cause.span) {
+                Ok(snippet) if !snippet.contains('\n') => format!("`{snippet}`"),
+                _ => "this value".to_string(),
+            };
+            let ty = tcx.erase_late_bound_regions(interior_types.rebind(cause.ty));
+            values.push((
+                cause.span,
+                format!("{value} is held across {an_await_or_yield}"),
+                tcx.erase_regions(ty),
+            ));
+        }
+
+        for (span, value, ty) in values {
+            let Some(path) = auto_trait_failure_path(
+                self,
+                trait_def_id,
+                ty,
+                param_env,
+                generator_did,
+                &mut FxHashSet::default(),
+            ) else {
+                continue;
+            };
+            let msg = if path.len() == 1 {
+                format!("{value}, and has type `{ty}` which is not `{trait_name}`")
+            } else {
+                format!(
+                    "{value}, and has type `{ty}` which is not `{trait_name}` because of {}",
+                    describe_auto_trait_failure_path(&path)
+                )
+            };
+            err.span_note(span, &msg);
+        }
+    }
+
     fn note_obligation_cause_code<T>(
         &self,
         err: &mut Diagnostic,
@@ -3705,3 +3816,122 @@ fn tcx(&self) -> TyCtxt<'tcx> {
         self.tcx
     }
 }
+
+/// A type on the path from the type of a value to a type that does not implement an auto trait.
+struct AutoTraitPathStep<'tcx> {
+    ty: Ty<'tcx>,
+    /// How the type is reached from the previous step, like "field `rc`".
+    via: Option<String>,
+    /// Whether the step is shown, which it is not for types that are implementation details,
+    /// like the types of the private fields of types from other modules.
+    visible: bool,
+}
+
+/// Returns the path from `ty` to a type that does not implement the auto trait `trait_def_id`
+/// by itself, through the components that the implementation of the trait is derived from:
+/// fields, elements, captured values and values held across yield points. A type with an
+/// explicit implementation of the trait, like `impl<T> !Send for Rc<T>`, doesn't implement it
+/// by itself, unless one of its type arguments doesn't. Returns `None` if `ty` implements the
+/// trait.
+///
+/// Fields are only shown if they are accessible from `module`.
+fn auto_trait_failure_path<'tcx>(
+    infcx: &InferCtxt<'tcx>,
+    trait_def_id: DefId,
+    ty: Ty<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    module: DefId,
+    visited: &mut FxHashSet<Ty<'tcx>>,
+) -> Option<Vec<AutoTraitPathStep<'tcx>>> {
+    let tcx = infcx.tcx;
+    if infcx.type_implements_trait(trait_def_id, [ty], param_env).may_apply()
+        || !tcx.recursion_limit().value_within_limit(visited.len())
+        || !visited.insert(ty)
+    {
+        return None;
+    }
+
+    let components: Vec<(Ty<'tcx>, Option<String>, bool)> =
+        match (tcx.find_map_relevant_impl(trait_def_id, ty, Some), *ty.kind()) {
+            (Some(impl_def_id), _)
+                if tcx.impl_polarity(impl_def_id) == ty::ImplPolarity::Negative =>
+            {
+                vec![]
+            }
+            (Some(_), ty::Adt(_, substs)) => substs.types().map(|ty| (ty, None, true)).collect(),
+            (Some(_), _) => vec![],
+            (None, ty::Adt(def, substs)) => def
+                .all_fields()
+                .map(|field| {
+                    (
+                        field.ty(tcx, substs),
+                        Some(format!("field `{}`", field.name)),
+                        field.vis.is_accessible_from(module, tcx),
+                    )
+                })
+                .collect(),
+            (None, ty::Tuple(tys)) => tys
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| (ty, Some(format!("field `{i}`")), true))
+                .collect(),
+            (None, ty::Array(ty, _) | ty::Slice(ty)) => vec![(ty, None, true)],
+            (None, ty::Closure(_, substs)) => substs
+                .as_closure()
+                .upvar_tys()
+                .map(|ty| (ty, Some("captured value".to_string()), true))
+                .collect(),
+            (None, ty::Generator(_, substs, _)) => {
+                let generator = substs.as_generator();
+                let mut components: Vec<_> = generator
+                    .upvar_tys()
+                    .map(|ty| (ty, Some("captured value".to_string()), true))
+                    .collect();
+                if let ty::GeneratorWitness(tys) = *generator.witness().kind() {
+                    components.extend(
+                        tcx.erase_late_bound_regions(tys)
+                            .iter()
+                            .map(|ty| (ty, Some("value held across a yield".to_string()), true)),
+                    );
+                }
+                components
+            }
+            // The future of an `async fn`, whose generator is an implementation detail.
+            (None, ty::Alias(ty::Opaque, ty::AliasTy { def_id, substs, .. })) => {
+                vec![(tcx.bound_type_of(def_id).subst(tcx, substs), None, false)]
+            }
+            _ => vec![],
+        };
+
+    for (component, via, visible) in components {
+        let component = tcx.erase_regions(component);
+        let component =
+            tcx.try_normalize_erasing_regions(param_env, component).unwrap_or(component);
+        let path = ensure_sufficient_stack(|| {
+            auto_trait_failure_path(infcx, trait_def_id, component, param_env, module, visited)
+        });
+        if let Some(mut path) = path {
+            path[0].via = via;
+            path[0].visible = visible;
+            path.insert(0, AutoTraitPathStep { ty, via: None, visible: true });
+            return Some(path);
+        }
+    }
+    Some(vec![AutoTraitPathStep { ty, via: None, visible: true }])
+}
+
+/// Describes `path` starting from the type that does not implement the auto trait, like
+/// ``` `Rc<u8>` (field `rc`) inside `Foo` inside `Vec<Foo>` ```.
+fn describe_auto_trait_failure_path(path: &[AutoTraitPathStep<'_>]) -> String {
+    let last = path.len() - 1;
+    path.iter()
+        .enumerate()
+        .rev()
+        .filter(|&(i, step)| i == last || step.visible)
+        .map(|(_, step)| match &step.via {
+            Some(via) => format!("`{}` ({via})", step.ty),
+            None => format!("`{}`", step.ty),
+        })
+        .collect::<Vec<_>>()
+        .join(" inside ")
+}
@@ -59,6 +59,10 @@
 
 mod candidate_assembly;
 mod confirmation;
+mod proof_tree;
+
+use self::proof_tree::CandidateStatus;
+pub use self::proof_tree::ProofTree;
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum IntercrateAmbiguityCause {
@@ -119,6 +123,11 @@ pub struct SelectionContext<'cx, 'tcx> {
     /// computing it may negatively impact performance.
     intercrate_ambiguity_causes: Option<FxIndexSet<IntercrateAmbiguityCause>>,
 
+    /// If set, every goal that is evaluated or selected is recorded in this
+    /// tree, for `-Z dump-proof-tree`. The evaluation and candidate caches
+    /// are not used while recording.
+    proof_tree: Option<ProofTree<'tcx>>,
+
     /// The mode that trait queries run in, which informs our error handling
     /// policy. In essence, canonicalized queries need their errors propagated
     /// rather than immediately reported because we do not have accurate spans.
@@ -209,6 +218,7 @@ pub fn new(infcx: &'cx InferCtxt<'tcx>) -> SelectionContext<'cx, 'tcx> {
             infcx,
             freshener: infcx.freshener_keep_static(),
             intercrate_ambiguity_causes: None,
+            proof_tree: None,
             query_mode: TraitQueryMode::Standard,
         }
     }
@@ -238,6 +248,20 @@ pub fn take_intercrate_ambiguity_causes(&mut self) -> FxIndexSet<IntercrateAmbig
         self.intercrate_ambiguity_causes.take().unwrap_or_default()
     }
 
+    /// Enables recording of the proof tree of the goals that are evaluated
+    /// or selected. See the documentation of [`Self::proof_tree`] for more.
+    pub fn enable_proof_tree_recording(&mut self) {
+        assert!(self.proof_tree.is_none());
+        self.proof_tree = Some(ProofTree::default());
+    }
+
+    /// Gets the proof tree recorded since recording was enabled and
+    /// disables recording at the same time. If recording is not enabled,
+    /// just returns an empty tree.
+    pub fn take_proof_tree(&mut self) -> ProofTree<'tcx> {
+        self.proof_tree.take().unwrap_or_default()
+    }
+
     pub fn tcx(&self) -> TyCtxt<'tcx> {
         self.infcx.tcx
     }
@@ -303,7 +327,10 @@ pub(crate) fn select_from_obligation(
         let pec = &ProvisionalEvaluationCache::default();
         let stack = self.push_stack(TraitObligationStackList::empty(pec), obligation);
 
-        self.candidate_from_obligation(&stack)
+        self.proof_tree_enter_goal(obligation.predicate);
+        let candidate = self.candidate_from_obligation(&stack);
+        self.proof_tree_exit_goal(proof_tree::describe_selection(&candidate));
+        candidate
     }
 
     #[instrument(level = "debug", skip(self), ret)]
@@ -395,13 +422,16 @@ fn candidate_from_obligation_no_cache<'o>(
                     }
                 }
             }
+            self.proof_tree_note("not knowable, as other crates may implement it");
             return Ok(None);
         }
 
         let candidate_set = self.assemble_candidates(stack)?;
+        self.proof_tree_assembled(&candidate_set.vec);
 
         if candidate_set.ambiguous {
             debug!("candidate set contains ambig");
+            self.proof_tree_note("candidate assembly is ambiguous");
             return Ok(None);
         }
 
@@ -419,6 +449,7 @@ fn candidate_from_obligation_no_cache<'o>(
         // usize`, etc. This spells an ambiguity.
 
         let mut candidates = self.filter_impls(candidates, stack.obligation);
+        self.proof_tree_filtered(&candidates);
 
         // If there is more than one candidate, first winnow them down
         // by considering extra conditions (nested obligations and so
@@ -435,7 +466,9 @@ fn candidate_from_obligation_no_cache<'o>(
         // Instead, we select the right impl now but report "`Bar` does
         // not implement `Clone`".
         if candidates.len() == 1 {
-            return self.filter_reservation_impls(candidates.pop().unwrap(), stack.obligation);
+            let candidate = candidates.pop().unwrap();
+            self.proof_tree_set_status(&candidate, CandidateStatus::Selected);
+            return self.filter_reservation_impls(candidate, stack.obligation);
         }
 
         // Winnow, but record the exact outcome of evaluation, which
@@ -446,7 +479,10 @@ fn candidate_from_obligation_no_cache<'o>(
                 Ok(eval) if eval.may_apply() => {
                     Ok(Some(EvaluatedCandidate { candidate: c, evaluation: eval }))
                 }
-                Ok(_) => Ok(None),
+                Ok(_) => {
+                    self.proof_tree_set_status(&c, CandidateStatus::DoesNotApply);
+                    Ok(None)
+                }
                 Err(OverflowError::Canonical) => Err(Overflow(OverflowError::Canonical)),
                 Err(OverflowError::ErrorReporting) => Err(ErrorReporting),
                 Err(OverflowError::Error(e)) => Err(Overflow(OverflowError::Error(e))),
@@ -473,6 +509,7 @@ fn candidate_from_obligation_no_cache<'o>(
                 });
                 if is_dup {
                     debug!(candidate = ?candidates[i], "Dropping candidate #{}/{}", i, candidates.len());
+                    self.proof_tree_set_status(&candidates[i].candidate, CandidateStatus::Dropped);
                     candidates.swap_remove(i);
                 } else {
                     debug!(candidate = ?candidates[i], "Retaining candidate #{}/{}", i, candidates.len());
@@ -482,6 +519,9 @@ fn candidate_from_obligation_no_cache<'o>(
                     // and report ambiguity.
                     if i > 1 {
                         debug!("multiple matches, ambig");
+                        for c in &candidates {
+                            self.proof_tree_set_status(&c.candidate, CandidateStatus::Ambiguous);
+                        }
                         return Ok(None);
                     }
                 }
@@ -498,6 +538,7 @@ fn candidate_from_obligation_no_cache<'o>(
         // who might care about this case, like coherence, should use
         // that function).
         if candidates.is_empty() {
+            self.proof_tree_note("no candidate applies");
             // If there's an error type, 'downgrade' our result from
             // `Err(Unimplemented)` to `Ok(None)`. This helps us avoid
             // emitting additional spurious errors, since we're guaranteed
@@ -510,7 +551,9 @@ fn candidate_from_obligation_no_cache<'o>(
         }
 
         // Just one candidate left.
-        self.filter_reservation_impls(candidates.pop().unwrap().candidate, stack.obligation)
+        let candidate = candidates.pop().unwrap().candidate;
+        self.proof_tree_set_status(&candidate, CandidateStatus::Selected);
+        self.filter_reservation_impls(candidate, stack.obligation)
     }
 
     ///////////////////////////////////////////////////////////////////////////
@@ -610,6 +653,17 @@ fn evaluate_predicate_recursively<'o>(
         &mut self,
         previous_stack: TraitObligationStackList<'o, 'tcx>,
         obligation: PredicateObligation<'tcx>,
+    ) -> Result<EvaluationResult, OverflowError> {
+        self.proof_tree_enter_goal(obligation.predicate);
+        let result = self.evaluate_predicate_recursively_inner(previous_stack, obligation);
+        self.proof_tree_exit_goal(proof_tree::describe_evaluation(&result));
+        result
+    }
+
+    fn evaluate_predicate_recursively_inner<'o>(
+        &mut self,
+        previous_stack: TraitObligationStackList<'o, 'tcx>,
+        obligation: PredicateObligation<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
         // `previous_stack` stores a `TraitObligation`, while `obligation` is
         // a `PredicateObligation`. These are distinct types, so we can't
@@ -1004,6 +1058,7 @@ fn evaluate_trait_predicate_recursively<'o>(
 
         if let Some(result) = stack.cache().get_provisional(fresh_trait_pred) {
             debug!("PROVISIONAL CACHE HIT");
+            self.proof_tree_note("provisional result of a goal in a cycle");
             stack.update_reached_depth(result.reached_depth);
             return Ok(result.result);
         }
@@ -1095,9 +1150,11 @@ fn check_evaluation_cycle(
             let cycle = cycle.map(|stack| stack.obligation.predicate.to_predicate(tcx));
             if self.coinductive_match(cycle) {
                 debug!("evaluate_stack --> recursive, coinductive");
+                self.proof_tree_note("coinductive cycle");
                 Some(EvaluatedToOk)
             } else {
                 debug!("evaluate_stack --> recursive, inductive");
+                self.proof_tree_note("inductive cycle");
                 Some(EvaluatedToRecur)
             }
         } else {
@@ -1147,6 +1204,7 @@ fn evaluate_stack<'o>(
             })
         {
             debug!("evaluate_stack --> unbound argument, recursive --> giving up",);
+            self.proof_tree_note("recursive goal with unbound inputs");
             return Ok(EvaluatedToUnknown);
         }
 
@@ -1200,7 +1258,8 @@ fn evaluate_candidate<'o>(
         stack: &TraitObligationStack<'o, 'tcx>,
         candidate: &SelectionCandidate<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
-        let mut result = self.evaluation_probe(|this| {
+        self.proof_tree_enter_candidate(candidate);
+        let result = self.evaluation_probe(|this| {
             let candidate = (*candidate).clone();
             match this.confirm_candidate(stack.obligation, candidate) {
                 Ok(selection) => {
@@ -1212,7 +1271,9 @@ fn evaluate_candidate<'o>(
                 }
                 Err(..) => Ok(EvaluatedToErr),
             }
-        })?;
+        });
+        self.proof_tree_exit_candidate(proof_tree::describe_evaluation(&result));
+        let mut result = result?;
 
         // If we erased any lifetimes, then we want to use
         // `EvaluatedToOkModuloRegions` instead of `EvaluatedToOk`
@@ -1242,6 +1303,12 @@ fn check_evaluation_cache(
             return None;
         }
 
+        // While recording a proof tree, goals are evaluated again so that
+        // their subtree is recorded too.
+        if self.proof_tree.is_some() {
+            return None;
+        }
+
         let tcx = self.tcx();
         if self.can_use_global_caches(param_env) {
             if let Some(res) = tcx.evaluation_cache.get(&(param_env, trait_pred), tcx) {
@@ -1497,6 +1564,10 @@ fn check_candidate_cache(
         if self.is_intercrate() {
             return None;
         }
+        // See the comment in `check_evaluation_cache`.
+        if self.proof_tree.is_some() {
+            return None;
+        }
         let tcx = self.tcx();
         let mut pred = cache_fresh_trait_pred.skip_binder();
         pred.remap_constness(&mut param_env);
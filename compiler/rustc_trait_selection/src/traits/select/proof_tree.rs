@@ -0,0 +1,377 @@
+//! The proof tree of an evaluation, recorded for `-Z dump-proof-tree`.
+//!
+//! While recording, the [`SelectionContext`] adds a goal for every predicate it evaluates or
+//! selects. A trait goal has the candidates assembled for it and what became of each of them
+//! while winnowing, and every candidate that was evaluated has the goals of its nested
+//! obligations. Other goals, like projection or well-formedness predicates, have the goals of
+//! their nested obligations directly.
+//!
+//! The evaluation and candidate caches are not used while recording, so that every goal has
+//! its whole subtree instead of a cached result.
+
+use super::{EvaluationResult, OverflowError, SelectionCandidate, SelectionContext};
+use super::{SelectionError, SelectionResult};
+
+use rustc_middle::ty::{TyCtxt, TypeFoldable};
+use serde_json::{json, Value};
+use std::fmt::{Display, Write};
+
+const PROOF_TREE_JSON_VERSION: u32 = 1;
+
+/// What became of a candidate of a trait goal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateStatus {
+    /// Selection stopped before the candidate was considered any further, e.g. because the goal
+    /// is not knowable.
+    Assembled,
+    /// Removed before winnowing, as its constness or polarity does not match the goal.
+    FilteredOut,
+    /// Removed while winnowing, as it does not apply.
+    DoesNotApply,
+    /// Removed while winnowing, as another candidate that applies is preferred to it.
+    Dropped,
+    /// Applies, but so does another candidate, so the goal is ambiguous.
+    Ambiguous,
+    Selected,
+}
+
+impl CandidateStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            CandidateStatus::Assembled => "assembled",
+            CandidateStatus::FilteredOut => "filtered out",
+            CandidateStatus::DoesNotApply => "does not apply",
+            CandidateStatus::Dropped => "dropped in favor of another candidate",
+            CandidateStatus::Ambiguous => "ambiguous",
+            CandidateStatus::Selected => "selected",
+        }
+    }
+}
+
+struct Goal {
+    predicate: String,
+    /// `None` if the goal is still being evaluated.
+    result: Option<&'static str>,
+    /// Why the goal has the result it has, if that is not clear from its candidates.
+    note: Option<&'static str>,
+    candidates: Vec<usize>,
+    nested: Vec<usize>,
+}
+
+struct Candidate<'tcx> {
+    candidate: SelectionCandidate<'tcx>,
+    description: String,
+    status: CandidateStatus,
+    /// `None` if the candidate was not evaluated.
+    result: Option<&'static str>,
+    nested: Vec<usize>,
+}
+
+enum Frame {
+    Goal(usize),
+    Candidate(usize),
+}
+
+/// The goals and candidates of the tree are stored in two arenas, and refer to each other by
+/// their index in them.
+#[derive(Default)]
+pub struct ProofTree<'tcx> {
+    goals: Vec<Goal>,
+    candidates: Vec<Candidate<'tcx>>,
+    roots: Vec<usize>,
+    /// The goals and candidates being evaluated, innermost last.
+    stack: Vec<Frame>,
+}
+
+impl<'tcx> ProofTree<'tcx> {
+    fn enter_goal(&mut self, predicate: String) {
+        let goal = self.goals.len();
+        self.goals.push(Goal {
+            predicate,
+            result: None,
+            note: None,
+            candidates: vec![],
+            nested: vec![],
+        });
+        match self.stack.last() {
+            Some(&Frame::Goal(parent)) => self.goals[parent].nested.push(goal),
+            Some(&Frame::Candidate(parent)) => self.candidates[parent].nested.push(goal),
+            None => self.roots.push(goal),
+        }
+        self.stack.push(Frame::Goal(goal));
+    }
+
+    fn exit_goal(&mut self, result: &'static str) {
+        let Some(Frame::Goal(goal)) = self.stack.pop() else {
+            bug!("exiting a goal of the proof tree while evaluating a candidate")
+        };
+        self.goals[goal].result = Some(result);
+    }
+
+    /// The goal being evaluated, unless one of its candidates is.
+    fn current_goal(&mut self) -> Option<&mut Goal> {
+        match self.stack.last() {
+            Some(&Frame::Goal(goal)) => Some(&mut self.goals[goal]),
+            _ => None,
+        }
+    }
+
+    fn find_candidate(&self, candidate: &SelectionCandidate<'tcx>) -> Option<usize> {
+        let Some(&Frame::Goal(goal)) = self.stack.last() else { return None };
+        self.goals[goal]
+            .candidates
+            .iter()
+            .copied()
+            .find(|&c| self.candidates[c].candidate == *candidate)
+    }
+
+    fn add_candidate(&mut self, candidate: SelectionCandidate<'tcx>, description: String) -> usize {
+        let index = self.candidates.len();
+        self.candidates.push(Candidate {
+            candidate,
+            description,
+            status: CandidateStatus::Assembled,
+            result: None,
+            nested: vec![],
+        });
+        if let Some(goal) = self.current_goal() {
+            goal.candidates.push(index);
+        }
+        index
+    }
+
+    fn set_status(&mut self, candidate: &SelectionCandidate<'tcx>, status: CandidateStatus) {
+        if let Some(index) = self.find_candidate(candidate) {
+            self.candidates[index].status = status;
+        }
+    }
+
+    /// The tree as indented text, with a line for every goal and candidate:
+    ///
+    /// ```text
+    /// `Vec<Foo>: Bar`: does not hold
+    ///   candidate impl `Bar for Vec<T>` at src/lib.rs:5:1: selected, does not hold
+    ///     `Foo: Baz`: does not hold (no candidate applies)
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for &root in &self.roots {
+            self.goal_to_text(&mut text, root, 0);
+        }
+        text
+    }
+
+    fn goal_to_text(&self, text: &mut String, goal: usize, depth: usize) {
+        let goal = &self.goals[goal];
+        let indent = depth * 2;
+        write!(text, "{:indent$}`{}`: {}", "", goal.predicate, goal.result.unwrap_or("unfinished"))
+            .unwrap();
+        if let Some(note) = goal.note {
+            write!(text, " ({note})").unwrap();
+        }
+        text.push('\n');
+
+        for &candidate in &goal.candidates {
+            let candidate = &self.candidates[candidate];
+            write!(
+                text,
+                "{:indent$}candidate {}: {}",
+                "",
+                candidate.description,
+                candidate.status.as_str(),
+                indent = indent + 2
+            )
+            .unwrap();
+            if let Some(result) = candidate.result {
+                write!(text, ", {result}").unwrap();
+            }
+            text.push('\n');
+            for &nested in &candidate.nested {
+                self.goal_to_text(text, nested, depth + 2);
+            }
+        }
+        for &nested in &goal.nested {
+            self.goal_to_text(text, nested, depth + 1);
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "version": PROOF_TREE_JSON_VERSION,
+            "goals": self.goals_to_json(&self.roots),
+        })
+    }
+
+    fn goal_to_json(&self, goal: usize) -> Value {
+        let goal = &self.goals[goal];
+        let candidates: Vec<_> = goal
+            .candidates
+            .iter()
+            .map(|&candidate| {
+                let candidate = &self.candidates[candidate];
+                json!({
+                    "candidate": candidate.description,
+                    "status": candidate.status.as_str(),
+                    "result": candidate.result,
+                    "nested": self.goals_to_json(&candidate.nested),
+                })
+            })
+            .collect();
+        json!({
+            "predicate": goal.predicate,
+            "result": goal.result,
+            "note": goal.note,
+            "candidates": candidates,
+            "nested": self.goals_to_json(&goal.nested),
+        })
+    }
+
+    fn goals_to_json(&self, goals: &[usize]) -> Vec<Value> {
+        goals.iter().map(|&goal| self.goal_to_json(goal)).collect()
+    }
+}
+
+pub(super) fn describe_evaluation(
+    result: &Result<EvaluationResult, OverflowError>,
+) -> &'static str {
+    match result {
+        Ok(EvaluationResult::EvaluatedToOk) => "holds",
+        Ok(EvaluationResult::EvaluatedToOkModuloRegions) => "holds modulo regions",
+        Ok(EvaluationResult::EvaluatedToOkModuloOpaqueTypes) => "holds modulo opaque types",
+        Ok(EvaluationResult::EvaluatedToAmbig) => "ambiguous",
+        Ok(EvaluationResult::EvaluatedToUnknown) => "unknown",
+        Ok(EvaluationResult::EvaluatedToRecur) => "recursive",
+        Ok(EvaluationResult::EvaluatedToErr) => "does not hold",
+        Err(_) => "overflow",
+    }
+}
+
+pub(super) fn describe_selection<'tcx>(
+    result: &SelectionResult<'tcx, SelectionCandidate<'tcx>>,
+) -> &'static str {
+    match result {
+        Ok(Some(_)) => "selected a candidate",
+        Ok(None) => "ambiguous",
+        Err(SelectionError::Overflow(_)) | Err(SelectionError::ErrorReporting) => "overflow",
+        Err(_) => "does not hold",
+    }
+}
+
+fn describe_candidate<'tcx>(tcx: TyCtxt<'tcx>, candidate: &SelectionCandidate<'tcx>) -> String {
+    match *candidate {
+        SelectionCandidate::ImplCandidate(def_id) => {
+            let trait_ref = tcx.impl_trait_ref(def_id).unwrap();
+            format!(
+                "impl `{} for {}` at {}",
+                trait_ref.print_only_trait_path(),
+                trait_ref.self_ty(),
+                tcx.sess.source_map().span_to_diagnostic_string(tcx.def_span(def_id)),
+            )
+        }
+        SelectionCandidate::ParamCandidate(bound) => format!("where-clause `{bound}`"),
+        SelectionCandidate::ProjectionCandidate(index, _) => {
+            format!("bound #{index} of the associated or opaque type")
+        }
+        SelectionCandidate::ObjectCandidate(index) => {
+            format!("supertrait #{index} of the trait object")
+        }
+        SelectionCandidate::TraitUpcastingUnsizeCandidate(index) => {
+            format!("upcasting to supertrait #{index} of the trait object")
+        }
+        SelectionCandidate::AutoImplCandidate => "auto trait impl".to_string(),
+        SelectionCandidate::BuiltinCandidate { .. } => "builtin impl".to_string(),
+        SelectionCandidate::TransmutabilityCandidate => "builtin transmutability impl".to_string(),
+        SelectionCandidate::ClosureCandidate => "closure".to_string(),
+        SelectionCandidate::GeneratorCandidate => "generator".to_string(),
+        SelectionCandidate::FutureCandidate => "async construct".to_string(),
+        SelectionCandidate::FnPointerCandidate { .. } => "function pointer".to_string(),
+        SelectionCandidate::TraitAliasCandidate => "trait alias".to_string(),
+        SelectionCandidate::BuiltinObjectCandidate => "builtin trait object impl".to_string(),
+        SelectionCandidate::BuiltinUnsizeCandidate => "builtin unsizing impl".to_string(),
+        SelectionCandidate::ConstDestructCandidate(_) => "builtin `Destruct` impl".to_string(),
+    }
+}
+
+impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
+    pub(super) fn proof_tree_enter_goal<T>(&mut self, predicate: T)
+    where
+        T: TypeFoldable<'tcx> + Display,
+    {
+        if let Some(proof_tree) = &mut self.proof_tree {
+            let predicate = self.infcx.resolve_vars_if_possible(predicate);
+            proof_tree.enter_goal(predicate.to_string());
+        }
+    }
+
+    pub(super) fn proof_tree_exit_goal(&mut self, result: &'static str) {
+        if let Some(proof_tree) = &mut self.proof_tree {
+            proof_tree.exit_goal(result);
+        }
+    }
+
+    /// Explains the result of the goal being evaluated.
+    pub(super) fn proof_tree_note(&mut self, note: &'static str) {
+        if let Some(goal) = self.proof_tree.as_mut().and_then(|tree| tree.current_goal()) {
+            goal.note = Some(note);
+        }
+    }
+
+    pub(super) fn proof_tree_assembled(&mut self, candidates: &[SelectionCandidate<'tcx>]) {
+        let tcx = self.tcx();
+        if let Some(proof_tree) = &mut self.proof_tree {
+            for candidate in candidates {
+                proof_tree.add_candidate(candidate.clone(), describe_candidate(tcx, candidate));
+            }
+        }
+    }
+
+    /// Marks the candidates of the goal being evaluated that are not in `retained` as filtered
+    /// out.
+    pub(super) fn proof_tree_filtered(&mut self, retained: &[SelectionCandidate<'tcx>]) {
+        let Some(proof_tree) = &mut self.proof_tree else { return };
+        let Some(&Frame::Goal(goal)) = proof_tree.stack.last() else { return };
+        for &candidate in &proof_tree.goals[goal].candidates {
+            let candidate = &mut proof_tree.candidates[candidate];
+            if !retained.contains(&candidate.candidate) {
+                candidate.status = CandidateStatus::FilteredOut;
+            }
+        }
+    }
+
+    pub(super) fn proof_tree_set_status(
+        &mut self,
+        candidate: &SelectionCandidate<'tcx>,
+        status: CandidateStatus,
+    ) {
+        if let Some(proof_tree) = &mut self.proof_tree {
+            proof_tree.set_status(candidate, status);
+        }
+    }
+
+    /// Starts recording the nested goals of `candidate`, replacing those of a previous
+    /// evaluation of it.
+    pub(super) fn proof_tree_enter_candidate(&mut self, candidate: &SelectionCandidate<'tcx>) {
+        let tcx = self.tcx();
+        if let Some(proof_tree) = &mut self.proof_tree {
+            let index = match proof_tree.find_candidate(candidate) {
+                Some(index) => {
+                    proof_tree.candidates[index].nested.clear();
+                    index
+                }
+                None => {
+                    proof_tree.add_candidate(candidate.clone(), describe_candidate(tcx, candidate))
+                }
+            };
+            proof_tree.stack.push(Frame::Candidate(index));
+        }
+    }
+
+    pub(super) fn proof_tree_exit_candidate(&mut self, result: &'static str) {
+        if let Some(proof_tree) = &mut self.proof_tree {
+            let Some(Frame::Candidate(candidate)) = proof_tree.stack.pop() else {
+                bug!("exiting a candidate of the proof tree while evaluating a goal")
+            };
+            proof_tree.candidates[candidate].result = Some(result);
+        }
+    }
+}
@@ -1368,6 +1368,9 @@ fn run(self, builder: &Builder<'_>) -> Compiler {
         dist::maybe_install_llvm_runtime(builder, target_compiler.host, &sysroot);
         dist::maybe_install_llvm_target(builder, target_compiler.host, &sysroot);
 
+        // Ensure that the translations of diagnostics can be found by `-Z translate-lang`.
+        dist::install_diagnostic_translations(builder, &sysroot);
+
         // Link the compiler binary itself into place
         let out_dir = builder.cargo_out(build_compiler, Mode::Rustc, host);
         let rustc = out_dir.join(exe("rustc-main", host));
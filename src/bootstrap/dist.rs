@@ -477,6 +477,9 @@ fn prepare_image(builder: &Builder<'_>, compiler: Compiler, image: &Path) {
                 t!(fs::copy(&page_src, &page_dst));
             }
 
+            // Translations of diagnostics
+            install_diagnostic_translations(builder, image);
+
             // Debugger scripts
             builder
                 .ensure(DebuggerScripts { sysroot: INTERNER.intern_path(image.to_owned()), host });
@@ -1960,6 +1963,16 @@ pub fn maybe_install_llvm_target(builder: &Builder<'_>, target: TargetSelection,
     }
 }
 
+/// Add the translations of rustc's diagnostics to `share/locale`, one directory per locale,
+/// which is where `-Z translate-lang` looks for them. The "en-US" messages are built into
+/// rustc, so they are not installed.
+pub fn install_diagnostic_translations(builder: &Builder<'_>, sysroot: &Path) {
+    let src = builder.src.join("compiler/rustc_error_messages/locales");
+    let dst = sysroot.join("share/locale");
+    t!(fs::create_dir_all(&dst));
+    builder.cp_filtered(&src, &dst, &|path| !path.starts_with("en-US"));
+}
+
 /// Maybe add libLLVM.so to the runtime lib-dir for rustc itself.
 pub fn maybe_install_llvm_runtime(builder: &Builder<'_>, target: TargetSelection, sysroot: &Path) {
     let dst_libdir =
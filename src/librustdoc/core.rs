@@ -3,7 +3,9 @@
 use rustc_data_structures::sync::{self, Lrc};
 use rustc_data_structures::unord::UnordSet;
 use rustc_errors::emitter::{Emitter, EmitterWriter};
+use rustc_errors::html::HtmlEmitter;
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_feature::UnstableFeatures;
 use rustc_hir::def::{Namespace, Res};
 use rustc_hir::def_id::{DefId, DefIdMap, LocalDefId};
@@ -143,8 +145,8 @@ pub(crate) fn with_all_trait_impls(&mut self, f: impl FnOnce(&mut Self, &[DefId]
 
 /// Creates a new diagnostic `Handler` that can be used to emit warnings and errors.
 ///
-/// If the given `error_format` is `ErrorOutputType::Json` and no `SourceMap` is given, a new one
-/// will be created for the handler.
+/// If the given `error_format` is `ErrorOutputType::Json` or `ErrorOutputType::Sarif` and no
+/// `SourceMap` is given, a new one will be created for the handler.
 pub(crate) fn new_handler(
     error_format: ErrorOutputType,
     source_map: Option<Lrc<source_map::SourceMap>>,
@@ -190,6 +192,24 @@ pub(crate) fn new_handler(
                 .ui_testing(unstable_opts.ui_testing),
             )
         }
+        ErrorOutputType::Sarif => {
+            let source_map = source_map.unwrap_or_else(|| {
+                Lrc::new(source_map::SourceMap::new(source_map::FilePathMapping::empty()))
+            });
+            Box::new(SarifEmitter::stderr(None, source_map, None, fallback_bundle))
+        }
+        ErrorOutputType::Html => Box::new(
+            HtmlEmitter::stderr(
+                source_map.map(|sm| sm as _),
+                None,
+                fallback_bundle,
+                unstable_opts.teach,
+                diagnostic_width,
+                false,
+                unstable_opts.track_diagnostics,
+            )
+            .ui_testing(unstable_opts.ui_testing),
+        ),
     };
 
     rustc_errors::Handler::with_emitter_and_flags(
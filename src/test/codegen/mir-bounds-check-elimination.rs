@@ -0,0 +1,44 @@
+// Checks that the MIR bounds check elimination removes the bounds checks of indexes that the
+// loop condition compares to the length, even without LLVM optimizations.
+// compile-flags: -Copt-level=0 -Zmir-enable-passes=+BoundsCheckElimination
+
+#![crate_type = "lib"]
+
+// CHECK-LABEL: @sum_slice
+#[no_mangle]
+pub fn sum_slice(v: &[u32]) -> u32 {
+    // CHECK-NOT: panic_bounds_check
+    let mut sum = 0u32;
+    let mut i = 0;
+    while i < v.len() {
+        sum = sum.wrapping_add(v[i]);
+        i += 1;
+    }
+    sum
+}
+
+// CHECK-LABEL: @sum_array
+#[no_mangle]
+pub fn sum_array(a: &[u32; 8]) -> u32 {
+    // CHECK-NOT: panic_bounds_check
+    let mut sum = 0u32;
+    let mut i = 0;
+    while i < 8 {
+        sum = sum.wrapping_add(a[i]);
+        i += 1;
+    }
+    sum
+}
+
+// CHECK-LABEL: @shifted
+#[no_mangle]
+pub fn shifted(v: &[u32]) -> u32 {
+    // CHECK: panic_bounds_check
+    let mut sum = 0u32;
+    let mut i = 0;
+    while i < v.len() {
+        i += 1;
+        sum = sum.wrapping_add(v[i]);
+    }
+    sum
+}
@@ -0,0 +1,57 @@
+// unit-test: BoundsCheckElimination
+// compile-flags: -Zmir-enable-passes=+LowerSliceLenCalls
+
+// EMIT_MIR bounds_check_elimination.slice_loop.BoundsCheckElimination.diff
+fn slice_loop(v: &[u32]) -> u32 {
+    let len = v.len();
+    let mut sum = 0;
+    let mut i = 0;
+    while i < len {
+        sum += v[i];
+        i += 1;
+    }
+    sum
+}
+
+// EMIT_MIR bounds_check_elimination.array_loop.BoundsCheckElimination.diff
+fn array_loop(a: [u32; 4]) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < 4 {
+        sum += a[i];
+        i += 1;
+    }
+    sum
+}
+
+// The index changes between the loop condition and the access, so the check stays.
+// EMIT_MIR bounds_check_elimination.shifted.BoundsCheckElimination.diff
+fn shifted(v: &[u32]) -> u32 {
+    let len = v.len();
+    let mut sum = 0;
+    let mut i = 0;
+    while i < len {
+        i += 1;
+        sum += v[i];
+    }
+    sum
+}
+
+// The loop bound is larger than the array, so the check stays.
+// EMIT_MIR bounds_check_elimination.larger_bound.BoundsCheckElimination.diff
+fn larger_bound(a: [u32; 4]) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < 5 {
+        sum += a[i];
+        i += 1;
+    }
+    sum
+}
+
+fn main() {
+    slice_loop(&[1, 2, 3]);
+    array_loop([1, 2, 3, 4]);
+    shifted(&[1, 2, 3]);
+    larger_bound([1, 2, 3, 4]);
+}
@@ -0,0 +1,75 @@
+// unit-test: GVN
+// compile-flags: -Zmir-enable-passes=+LowerSliceLenCalls
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::discriminant_value;
+
+// EMIT_MIR gvn.binary_op.GVN.diff
+fn binary_op(x: u64, y: u64) -> u64 {
+    let a = x + y;
+    let b = x + y;
+    a * b
+}
+
+// EMIT_MIR gvn.len.GVN.diff
+fn len(v: &[u32]) -> usize {
+    let a = v.len();
+    let b = v.len();
+    a + b
+}
+
+// EMIT_MIR gvn.discriminant.GVN.diff
+fn discriminant(x: &Option<u32>) -> isize {
+    let a = discriminant_value(x);
+    let b = discriminant_value(x);
+    a + b
+}
+
+// Loads through `x` are numbered, but not the ones through `y`, as what a mutable reference
+// points to may change.
+// EMIT_MIR gvn.deref.GVN.diff
+fn deref(x: &u32, y: &mut u32) -> u32 {
+    let a = *x + 1;
+    let b = *x + 1;
+    let c = *y + 1;
+    let d = *y + 1;
+    a + b + c + d
+}
+
+// `i` changes in the loop, so `i * 2` is not merged with `a`, unlike `n * 2` with `b`.
+// EMIT_MIR gvn.loop_header.GVN.diff
+fn loop_header(mut i: u32, n: u32) -> u32 {
+    let a = i * 2;
+    let b = n * 2;
+    let mut sum = 0;
+    while i < n {
+        sum += i * 2 + n * 2;
+        i += 1;
+    }
+    sum + a + b
+}
+
+// At the loop header, `last` holds `i * 3` of the previous iteration, or nothing in the first
+// one, so it is not merged with `i * 3` of the current iteration.
+// EMIT_MIR gvn.previous_iteration.GVN.diff
+fn previous_iteration(n: u32) -> u32 {
+    let mut i = 0;
+    let mut sum = 0;
+    let mut last;
+    while i < n {
+        sum += i * 3;
+        last = i * 3;
+        i += 1;
+    }
+    sum
+}
+
+fn main() {
+    binary_op(1, 2);
+    len(&[1, 2, 3]);
+    discriminant(&Some(1));
+    deref(&1, &mut 2);
+    loop_header(0, 3);
+    previous_iteration(3);
+}
@@ -0,0 +1,35 @@
+// unit-test: LoopInvariantCodeMotion
+
+// The product is hoisted out of the inner loop, and then out of the outer one.
+// EMIT_MIR loop_invariant_code_motion.nested.LoopInvariantCodeMotion.diff
+fn nested(x: u64, y: u64, n: u64) -> u64 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        let mut j = 0;
+        while j < n {
+            sum += x * y;
+            j += 1;
+        }
+        i += 1;
+    }
+    sum
+}
+
+// The comparison of the divisor with zero is hoisted, but not the division, which has to stay
+// behind the check that panics if the divisor is zero.
+// EMIT_MIR loop_invariant_code_motion.division.LoopInvariantCodeMotion.diff
+fn division(x: u64, y: u64, n: u64) -> u64 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        sum += x / y;
+        i += 1;
+    }
+    sum
+}
+
+fn main() {
+    nested(3, 5, 2);
+    division(15, 5, 2);
+}
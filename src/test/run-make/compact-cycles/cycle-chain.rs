@@ -0,0 +1,6 @@
+type A = B;
+type B = C;
+type C = D;
+type D = A;
+
+fn main() {}
@@ -0,0 +1,11 @@
+trait Trait {}
+
+struct Wrapper<T>(T);
+
+impl<T> Trait for T where Wrapper<T>: Trait {}
+
+fn check<T: Trait>() {}
+
+fn main() {
+    check::<u8>();
+}
@@ -0,0 +1,6 @@
+// Lines added above the known diagnostic don't make it new.
+
+fn main() {
+    let   known = 1;
+    let new = 2;
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let mut v = vec![1, 2, 3];
+    let r = &mut v;
+    r.push(4);
+    let w = v;
+    drop(w);
+}
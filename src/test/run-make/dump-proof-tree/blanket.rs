@@ -0,0 +1,15 @@
+#![crate_type = "lib"]
+
+pub trait Trait {}
+pub trait Helper {}
+
+impl<T: Helper> Trait for Vec<T> {}
+impl Helper for u8 {}
+
+pub struct Local;
+
+fn requires_trait<T: Trait>() {}
+
+pub fn f() {
+    requires_trait::<Vec<Local>>();
+}
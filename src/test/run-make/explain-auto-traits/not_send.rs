@@ -0,0 +1,29 @@
+use std::future::Future;
+use std::rc::Rc;
+
+pub struct Foo {
+    rc: Rc<u8>,
+}
+
+fn is_send<T: Send>(_: T) {}
+
+async fn yield_now() {}
+
+async fn held() {
+    let foos = vec![Foo { rc: Rc::new(1) }];
+    yield_now().await;
+    drop(foos);
+}
+
+fn captured() -> impl Future<Output = ()> {
+    let shared = Rc::new(0u8);
+    async move {
+        yield_now().await;
+        drop(shared);
+    }
+}
+
+fn main() {
+    is_send(held());
+    is_send(captured());
+}
@@ -0,0 +1,14 @@
+#![feature(rustc_attrs)]
+#![crate_type = "rlib"]
+#![rustc_partition_reused(module = "foo", cfg = "imported")]
+#![rustc_partition_reused(module = "foo-bar", cfg = "imported")]
+
+pub fn foo() -> u32 {
+    bar::bar() + 1
+}
+
+pub mod bar {
+    pub fn bar() -> u32 {
+        41
+    }
+}
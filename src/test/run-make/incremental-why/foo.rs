@@ -0,0 +1,9 @@
+#![crate_type = "rlib"]
+
+pub fn unchanged() -> u32 {
+    1
+}
+
+pub fn edited() -> u32 {
+    EDITED
+}
@@ -0,0 +1,11 @@
+#![crate_type = "lib"]
+
+pub fn read(p: *const u8) -> u8 {
+    unsafe { *p }
+}
+
+pub mod ffi {
+    pub fn read(p: *const u8) -> u8 {
+        unsafe { *p }
+    }
+}
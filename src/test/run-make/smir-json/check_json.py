@@ -0,0 +1,48 @@
+#!/usr/bin/env python
+
+# Checks the `-Z unpretty=smir-json` output of `foo.rs` against the schema documented in
+# `rustc_smir/src/stable_mir/json.rs`.
+
+import sys
+import json
+
+smir = json.load(sys.stdin)
+assert smir["version"] == 1
+assert smir["crate"]["name"] == "foo"
+
+defs = smir["defs"]
+types = smir["types"]
+spans = smir["spans"]
+
+
+def check_body(body):
+    assert body["blocks"]
+    for local in body["locals"]:
+        assert 0 <= local["ty"] < len(types)
+        assert 0 <= local["span"] < len(spans)
+
+
+def is_param(ty):
+    return "Param" in types[ty]["kind"]
+
+
+items = {defs[item["def_id"]]: item for item in smir["items"]}
+assert items["generic"]["kind"] == "Fn" and items["generic"]["generic"]
+assert items["add"]["kind"] == "Fn" and not items["add"]["generic"]
+for item in items.values():
+    check_body(item["body"])
+# The polymorphic body of `generic` returns `T`.
+assert is_param(items["generic"]["body"]["locals"][0]["ty"])
+
+instances = {instance["name"]: instance for instance in smir["instances"]}
+generic = instances["generic::<u64>"]
+assert defs[generic["def_id"]] == "generic"
+assert [types[ty]["name"] for ty in generic["args"]] == ["u64"]
+# The monomorphized body of `generic::<u64>` returns `u64`.
+assert types[generic["body"]["locals"][0]["ty"]]["name"] == "u64"
+assert instances["add"]["args"] == []
+for instance in instances.values():
+    check_body(instance["body"])
+    assert not any(is_param(local["ty"]) for local in instance["body"]["locals"])
+symbols = [instance["symbol"] for instance in smir["instances"]]
+assert symbols == sorted(set(symbols))
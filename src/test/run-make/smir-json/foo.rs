@@ -0,0 +1,14 @@
+pub struct Wrapper<T>(T);
+
+pub fn generic<T: Clone>(x: &Wrapper<T>) -> T {
+    x.0.clone()
+}
+
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn main() {
+    let _ = generic(&Wrapper(1_u64));
+    let _ = add(1, 2);
+}
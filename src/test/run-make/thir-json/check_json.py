@@ -0,0 +1,63 @@
+#!/usr/bin/env python
+
+# Checks the `-Z unpretty=thir-json` output of `foo.rs` against the format documented in
+# `rustc_mir_build/src/thir/json.rs`.
+
+import sys
+import json
+
+bodies = {body["def_path"]: body for body in json.load(sys.stdin)}
+assert set(bodies) == {"classify", "main", "main::{closure#0}"}
+
+for body in bodies.values():
+    assert body["version"] == 1
+    # Every table is indexed by the number of the ids of its nodes.
+    for table, prefix in [("params", "p"), ("exprs", "e"), ("blocks", "b"), ("stmts", "s"),
+                          ("arms", "a")]:
+        for i, node in enumerate(body[table]):
+            assert node["id"] == prefix + str(i), node
+    assert body["body"] in [expr["id"] for expr in body["exprs"]]
+
+
+def expr(body, id):
+    return body["exprs"][int(id[1:])]
+
+
+def unscope(body, id):
+    while expr(body, id)["kind"] == "Scope":
+        id = expr(body, id)["value"]
+    return expr(body, id)
+
+
+classify = bodies["classify"]
+assert [param["ty"] for param in classify["params"]] == ["&Point", "&[u8]"]
+assert classify["params"][1]["pat"]["kind"] == "Binding"
+assert classify["params"][1]["pat"]["name"] == "v"
+
+let = classify["stmts"][0]
+assert let["kind"] == "Let"
+assert let["pat"]["kind"] == "Leaf" and let["pat"]["ty"] == "Point"
+assert let["pat"]["subpatterns"][0]["pat"]["name"] == "x"
+assert unscope(classify, let["initializer"])["kind"] == "Deref"
+
+[match] = [expr for expr in classify["exprs"] if expr["kind"] == "Match"]
+assert match["ty"] == "i32"
+assert match["span"]["lo"] == [8, 5] and match["span"]["hi"] == [12, 6]
+arms = [classify["arms"][int(arm[1:])] for arm in match["arms"]]
+assert [arm["guard"] and arm["guard"]["kind"] for arm in arms] == [None, "If", None]
+# The slice patterns are matched through the reference.
+slices = [arm["pat"]["subpattern"] for arm in arms]
+assert [pat["kind"] for pat in slices] == ["Slice"] * 3
+assert slices[1]["suffix"][0]["kind"] == "Range"
+assert slices[1]["suffix"][0]["lo"].startswith("0") and slices[1]["suffix"][0]["hi"].startswith("9")
+assert slices[2]["slice"]["name"] == "rest" and slices[2]["slice"]["ty"] == "&[u8]"
+add = unscope(classify, arms[1]["body"])
+assert add["kind"] == "Binary" and add["op"] == "Add" and add["ty"] == "i32"
+
+main = bodies["main"]
+[closure] = [expr for expr in main["exprs"] if expr["kind"] == "Closure"]
+assert closure["closure"] == "main::{closure#0}" and len(closure["upvars"]) == 1
+upvars = [expr for expr in bodies["main::{closure#0}"]["exprs"] if expr["kind"] == "UpvarRef"]
+assert upvars[0]["var"]["name"] == "p"
+[adt] = [expr for expr in main["exprs"] if expr["kind"] == "Adt"]
+assert adt["adt"] == "Point" and [field["name"] for field in adt["fields"]] == ["x", "y"]
@@ -0,0 +1,19 @@
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+pub fn classify(p: &Point, v: &[u8]) -> i32 {
+    let Point { x, .. } = *p;
+    match v {
+        [] => x,
+        [first, .., 0..=9] if *first > 1 => x + i32::from(*first),
+        [_, rest @ ..] => rest.len() as i32,
+    }
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    let f = |z: i32| z * p.y;
+    let _ = f(classify(&p, &[1, 2, 3]));
+}
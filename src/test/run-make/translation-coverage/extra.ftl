@@ -0,0 +1,4 @@
+parse_unexpected_if_with_if = `if` inesperado en la expresión de la condición
+    .suggestion = elimine el `if`
+
+parse_no_such_message = este mensaje no existe
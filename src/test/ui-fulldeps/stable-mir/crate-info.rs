@@ -0,0 +1,144 @@
+// run-pass
+// Test that users are able to use stable mir APIs to retrieve information of the current crate
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_smir;
+
+use rustc_driver::{Callbacks, Compilation, RunCompiler};
+use rustc_interface::{interface, Queries};
+use rustc_smir::{rustc_internal, stable_mir};
+use std::io::Write;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir() {
+    // Get the local crate using stable_mir API.
+    let local = stable_mir::local_crate();
+    assert_eq!(&local.name, CRATE_NAME);
+    assert!(local.is_local);
+
+    assert_eq!(stable_mir::entry_fn(), None);
+
+    // Find items in the local crate.
+    let items = stable_mir::all_local_items();
+    assert!(get_item(&items, "input::foo").is_some());
+
+    // Find the `std` crate.
+    assert!(stable_mir::find_crate("std").is_some());
+
+    let bar = get_item(&items, "input::bar").unwrap();
+    assert_eq!(bar.kind(), stable_mir::ItemKind::Fn);
+    let body = bar.body();
+    assert_eq!(body.arg_count, 1);
+    assert_eq!(body.locals.len(), 2);
+    assert_eq!(body.blocks.len(), 1);
+    let block = &body.blocks[0];
+    assert_eq!(block.statements.len(), 1);
+    match &block.statements[0].kind {
+        stable_mir::mir::StatementKind::Assign(..) => {}
+        other => panic!("{other:?}"),
+    }
+    match &block.terminator.kind {
+        stable_mir::mir::TerminatorKind::Return => {}
+        other => panic!("{other:?}"),
+    }
+    match body.arg_locals()[0].ty.kind() {
+        stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::Uint(
+            stable_mir::ty::UintTy::U64,
+        )) => {}
+        other => panic!("{other:?}"),
+    }
+
+    let foo_bar = get_item(&items, "input::foo_bar").unwrap();
+    let body = foo_bar.body();
+    assert_eq!(body.arg_count, 2);
+    let block = &body.blocks[0];
+    match &block.terminator.kind {
+        stable_mir::mir::TerminatorKind::Call { .. } => {}
+        other => panic!("{other:?}"),
+    }
+
+    let konst = get_item(&items, "input::KONST").unwrap();
+    assert_eq!(konst.kind(), stable_mir::ItemKind::Const);
+}
+
+// Use internal API to find a function in a crate.
+fn get_item<'a>(
+    items: &'a stable_mir::CrateItems,
+    name: &str,
+) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|item| item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// It will invoke the compiler using a custom Callback implementation, which will
+/// invoke Stable MIR APIs after the compiler has finished its analysis.
+fn main() {
+    let path = "input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_driver::catch_fatal_errors(|| {
+        RunCompiler::new(&args, &mut SMirCalls {}).run().unwrap();
+    })
+    .unwrap();
+}
+
+struct SMirCalls {}
+
+impl Callbacks for SMirCalls {
+    /// Called after analysis. Return value instructs the compiler whether to
+    /// continue the compilation afterwards (defaults to `Compilation::Continue`)
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+            rustc_internal::run(tcx, test_stable_mir);
+        });
+        // No need to keep going.
+        Compilation::Stop
+    }
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub const KONST: u32 = 42;
+
+    pub fn foo(i: u32) -> u32 {{
+        i + KONST
+    }}
+
+    pub fn bar(x: u64) -> u64 {{
+        x
+    }}
+
+    pub fn foo_bar(x: &str, y: &str) -> String {{
+        let mut sum = String::with_capacity(x.len() + y.len());
+        sum.push_str(x);
+        sum.push_str(y);
+        sum
+    }}"#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// Checks the steps that `-Zborrowck-explain` adds to borrow conflicts: where the borrow is
+// created, the constraints it flows through, the conflicting use and the later use that keeps
+// the borrow alive.
+
+// compile-flags: -Zborrowck-explain
+
+fn first_of(v: &Vec<i32>) -> &i32 {
+    &v[0]
+}
+
+fn two_mutable_borrows() {
+    let mut v = vec![1];
+    let first = &mut v;
+    let alias = first;
+    v.push(2); //~ ERROR cannot borrow `v` as mutable more than once at a time
+    alias.push(3);
+}
+
+fn shared_then_mutable() {
+    let mut v = vec![1];
+    let shared = &v;
+    let first = first_of(shared);
+    v.push(2); //~ ERROR cannot borrow `v` as mutable because it is also borrowed as immutable
+    println!("{}", first);
+}
+
+fn move_while_borrowed() {
+    let s = String::new();
+    let r = &s;
+    let moved = s; //~ ERROR cannot move out of `s` because it is borrowed
+    drop(moved);
+    drop(r);
+}
+
+fn main() {
+    two_mutable_borrows();
+    shared_then_mutable();
+    move_while_borrowed();
+}
@@ -0,0 +1,81 @@
+// Checks that global value numbering only merges the computations of values that are equal.
+// run-pass
+// compile-flags: -Zmir-opt-level=3
+
+use std::cell::Cell;
+
+fn repeated(a: u32, b: u32) -> u32 {
+    let x = a.wrapping_add(b);
+    let y = a.wrapping_add(b);
+    x.wrapping_mul(y)
+}
+
+fn lengths(v: &[u32], w: &[u32]) -> usize {
+    let n = v.len();
+    let r = &*v;
+    n + r.len() + w.len()
+}
+
+fn discriminants(o: &Option<u32>) -> u32 {
+    let a = if let Some(x) = o { *x } else { 1 };
+    let b = if let Some(x) = o { *x } else { 2 };
+    a + b
+}
+
+// Every iteration computes a new value at the same location, and `prev` holds the one of the
+// previous iteration.
+fn previous_iteration(v: &[u32]) -> u32 {
+    let mut prev = 0;
+    let mut sum = 0;
+    for &x in v {
+        let cur = x.wrapping_mul(3);
+        sum += cur - prev.min(cur);
+        prev = cur;
+    }
+    sum
+}
+
+fn calls(f: &mut impl FnMut() -> u32) -> u32 {
+    let a = f();
+    let b = f();
+    a * 10 + b
+}
+
+// The value behind a mutable reference changes between the loads.
+fn mutable_reference(r: &mut u32) -> u32 {
+    let a = *r + 1;
+    *r += 1;
+    let b = *r + 1;
+    a * 10 + b
+}
+
+// The value behind a shared reference to a `Cell` changes between the loads.
+fn shared_cell(c: &(u32, Cell<u32>)) -> u32 {
+    let a = c.1.get() + c.0;
+    c.1.set(a);
+    let b = c.1.get() + c.0;
+    a * 10 + b
+}
+
+fn main() {
+    assert_eq!(repeated(2, 3), 25);
+    assert_eq!(lengths(&[1, 2, 3], &[4]), 7);
+    assert_eq!(discriminants(&Some(4)), 8);
+    assert_eq!(discriminants(&None), 3);
+    assert_eq!(previous_iteration(&[1, 2, 1, 3]), 12);
+
+    let mut counter = 0;
+    assert_eq!(
+        calls(&mut || {
+            counter += 1;
+            counter
+        }),
+        12
+    );
+
+    let mut x = 1;
+    assert_eq!(mutable_reference(&mut x), 23);
+    assert_eq!(x, 2);
+
+    assert_eq!(shared_cell(&(1, Cell::new(1))), 23);
+}
@@ -0,0 +1,87 @@
+// Checks that the loop optimizations keep the bounds checks they cannot prove, and that hoisting
+// computations out of loops does not change their results.
+// run-pass
+// needs-unwind
+// compile-flags: -Zmir-opt-level=3
+
+use std::panic;
+
+fn sum_while(v: &[u32]) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < v.len() {
+        sum += v[i];
+        i += 1;
+    }
+    sum
+}
+
+fn sum_array(a: [u32; 4]) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < 4 {
+        sum += a[i];
+        i += 1;
+    }
+    sum
+}
+
+// The index changes between the check and the access, so the check has to stay.
+fn shifted(v: &[u32]) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < v.len() {
+        i += 1;
+        sum += v[i];
+    }
+    sum
+}
+
+// The bound of the loop is larger than the array, so the check has to stay.
+fn larger_bound(a: [u32; 4]) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < 5 {
+        sum += a[i];
+        i += 1;
+    }
+    sum
+}
+
+// The length is compared to another slice, so the check has to stay.
+fn other_slice(v: &[u32], w: &[u32]) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < w.len() {
+        sum += v[i];
+        i += 1;
+    }
+    sum
+}
+
+fn invariant(x: u64, y: u64, n: u64) -> u64 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        let mut j = 0;
+        while j < n {
+            sum += (x * y + 1) ^ (x as u8 as u64);
+            j += 1;
+        }
+        i += 1;
+    }
+    sum
+}
+
+fn main() {
+    assert_eq!(sum_while(&[1, 2, 3]), 6);
+    assert_eq!(sum_while(&[]), 0);
+    assert_eq!(sum_array([1, 2, 3, 4]), 10);
+    assert_eq!(invariant(3, 5, 3), 9 * ((3 * 5 + 1) ^ 3));
+    assert_eq!(invariant(3, 5, 0), 0);
+
+    assert!(panic::catch_unwind(|| shifted(&[1, 2, 3])).is_err());
+    assert!(panic::catch_unwind(|| larger_bound([1, 2, 3, 4])).is_err());
+    assert!(panic::catch_unwind(|| other_slice(&[1], &[1, 2])).is_err());
+    assert_eq!(other_slice(&[1, 2], &[1]), 1);
+}
@@ -0,0 +1,19 @@
+// Checks that diagnostics are held back and sorted by source location when the front end is
+// allowed to use more than one thread, so that their order does not depend on which thread
+// reported them first. Without sorting, the unused variable in `second` is reported by the
+// liveness pass before the dead code lint reports `first`.
+
+// check-pass
+// compile-flags: -Zthreads=4
+
+#![warn(dead_code, unused_variables)]
+
+fn first() {} //~ WARN function `first` is never used
+
+fn second() {
+    let x = 1; //~ WARN unused variable: `x`
+}
+
+fn main() {
+    second();
+}
@@ -0,0 +1,42 @@
+// Check that `-Zunpretty=smir-json` can serialize a variety of bodies without ICEing.
+// The output itself is checked by the `smir-json` run-make test.
+
+// compile-flags: -Z unpretty=smir-json
+// edition:2021
+// check-pass
+// dont-check-compiler-stdout
+
+pub const KONST: &[u32] = &[1, 2, 3];
+
+pub static STATIC: u8 = 0;
+
+pub struct Wrapper<T>(T);
+
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+pub fn generic<T: Clone>(x: &Wrapper<T>) -> Either<T, ()> {
+    Either::Left(x.0.clone())
+}
+
+pub fn index(x: &[u8], i: usize) -> u8 {
+    x[i] + KONST.len() as u8
+}
+
+pub fn closure() -> impl Fn(i32) -> i32 {
+    let y = 1;
+    move |x| x + y
+}
+
+pub async fn future(x: u32) -> u32 {
+    x
+}
+
+pub fn main() {
+    let _ = generic(&Wrapper(1_u64));
+    let _ = index(&[STATIC; 4], 2);
+    let _ = closure()(1);
+    let _ = future(4);
+}